@@ -0,0 +1,172 @@
+// src/config.rs - Configuration management
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use solana_program::pubkey::Pubkey;
+use crate::connection::websocket::{AccountEncoding, Commitment};
+use crate::dex::pool::DexKind;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub connection: ConnectionConfig,
+    pub ui: UiConfig,
+    pub trading: TradingConfig,
+    pub pools: Vec<PoolConfig>,
+    #[serde(default)]
+    pub wallet: WalletConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionConfig {
+    pub rpc_endpoint: String,
+    pub ws_endpoint: String,
+    pub timeout_seconds: u64,
+    /// Reconnects after the WebSocket drops, and retries of an HTTP request that failed
+    /// transiently.
+    pub retry_attempts: u32,
+    pub auto_reconnect: bool,
+    /// Commitment of account reads and subscriptions: processed, confirmed or finalized.
+    #[serde(default)]
+    pub commitment: Commitment,
+    /// Account data encoding: "base64", or "base64+zstd" to compress it in transit.
+    #[serde(default)]
+    pub encoding: AccountEncoding,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiConfig {
+    pub theme: String,
+    pub chart_update_interval_ms: u64,
+    pub max_chart_points: usize,
+    pub default_timeframe: String,
+    pub show_volume: bool,
+    pub show_grid: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradingConfig {
+    pub default_slippage: f64,
+    pub max_price_impact: f64,
+    pub price_alert_threshold: f64,
+    pub enable_arbitrage_detection: bool,
+}
+
+/// Live trading settings, used when the `wallet` feature is enabled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalletConfig {
+    /// Solana CLI keypair file (JSON array of 64 bytes). Live trading is off without one.
+    pub keypair_path: Option<String>,
+    pub compute_unit_limit: u32,
+    pub priority_fee_micro_lamports: u64,
+}
+
+impl Default for WalletConfig {
+    fn default() -> Self {
+        Self {
+            keypair_path: None,
+            compute_unit_limit: 200_000,
+            priority_fee_micro_lamports: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolConfig {
+    pub name: String,
+    pub pubkey: String,
+    pub dex: DexKind,
+    pub token_a: String,
+    pub token_b: String,
+    pub enabled: bool,
+    pub priority: u32,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            connection: ConnectionConfig {
+                rpc_endpoint: "https://api.mainnet-beta.solana.com".to_string(),
+                ws_endpoint: "wss://api.mainnet-beta.solana.com".to_string(),
+                timeout_seconds: 30,
+                retry_attempts: 3,
+                auto_reconnect: true,
+                commitment: Commitment::default(),
+                encoding: AccountEncoding::default(),
+            },
+            ui: UiConfig {
+                theme: "dark".to_string(),
+                chart_update_interval_ms: 100,
+                max_chart_points: 10000,
+                default_timeframe: "5m".to_string(),
+                show_volume: true,
+                show_grid: true,
+            },
+            trading: TradingConfig {
+                default_slippage: 1.0, // 1%
+                max_price_impact: 5.0, // 5%
+                price_alert_threshold: 2.0, // 2%
+                enable_arbitrage_detection: true,
+            },
+            pools: vec![
+                PoolConfig {
+                    name: "SOL/USDC".to_string(),
+                    pubkey: "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ".to_string(),
+                    dex: DexKind::Whirlpool,
+                    token_a: "So11111111111111111111111111111111111111112".to_string(),
+                    token_b: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+                    enabled: true,
+                    priority: 1,
+                },
+            ],
+            wallet: WalletConfig::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn load_from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)?;
+        let config: AppConfig = toml::from_str(&contents)?;
+        Ok(config)
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let contents = toml::to_string_pretty(self)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Loads the config, writing the defaults only if the file does not exist yet. A file
+    /// that fails to parse is left alone so the user's settings are not lost.
+    pub fn load_or_default(path: &str) -> Self {
+        if !std::path::Path::new(path).exists() {
+            let default_config = Self::default();
+            if let Err(e) = default_config.save_to_file(path) {
+                eprintln!("Failed to save default config: {}", e);
+            }
+            return default_config;
+        }
+        Self::load_from_file(path).unwrap_or_else(|e| {
+            eprintln!("Failed to load config from {}, using defaults: {}", path, e);
+            Self::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_section_with_only_keypair_path() {
+        let mut contents = toml::to_string_pretty(&AppConfig::default()).unwrap();
+        let start = contents.find("[wallet]").unwrap();
+        contents.truncate(start);
+        contents.push_str("[wallet]\nkeypair_path = \"id.json\"\n");
+
+        let config: AppConfig = toml::from_str(&contents).unwrap();
+        assert_eq!(config.wallet.keypair_path.as_deref(), Some("id.json"));
+        assert_eq!(config.wallet.compute_unit_limit, WalletConfig::default().compute_unit_limit);
+    }
+}
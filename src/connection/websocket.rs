@@ -1,34 +1,611 @@
 // src/connection/websocket.rs
 
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use solana_program::hash::Hash;
+use solana_program::instruction::Instruction;
+use solana_program::message::Message as TransactionMessage;
 use solana_program::pubkey::Pubkey;
 use tokio::sync::mpsc;
-use std::collections::HashMap;
-use crate::dex::whirlpool::state::Whirlpool;
-use crate::data::price_data::{PricePoint, whirlpool_math};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::config::ConnectionConfig;
+use crate::dex::pool::{AccountDataMap, DexKind, DexPool};
+use crate::dex::whirlpool::instruction::{SwapAccounts, SwapInstructionArgs, SwapInstructionBuilder};
+use crate::dex::whirlpool::snapshot::PoolSnapshot;
+use crate::dex::whirlpool::state::{TickArray, Whirlpool};
+use crate::dex::whirlpool::{constants, derive_surrounding_tick_array_starts, get_tick_array_address};
+use crate::utils::spl_token;
+
+type WsStream = tokio_tungstenite::WebSocketStream<
+    tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+>;
+
+// A stream that has been silent this long is treated as dead even if the socket is open
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+const STALE_STREAM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How settled the state a read or subscription reflects must be. Lower levels arrive
+/// sooner but can still be rolled back.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Commitment {
+    Processed,
+    #[default]
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+}
+
+/// Wire encoding of account data. `Base64Zstd` compresses it in transit, which pays off for
+/// large accounts such as tick arrays and order books.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AccountEncoding {
+    #[default]
+    #[serde(rename = "base64")]
+    Base64,
+    #[serde(rename = "base64+zstd")]
+    Base64Zstd,
+}
+
+impl AccountEncoding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AccountEncoding::Base64 => "base64",
+            AccountEncoding::Base64Zstd => "base64+zstd",
+        }
+    }
+}
+
+/// Commitment and encoding of an account subscription or read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AccountOptions {
+    pub commitment: Commitment,
+    pub encoding: AccountEncoding,
+}
+
+impl From<&ConnectionConfig> for AccountOptions {
+    fn from(config: &ConnectionConfig) -> Self {
+        Self {
+            commitment: config.commitment,
+            encoding: config.encoding,
+        }
+    }
+}
+
+impl AccountOptions {
+    fn rpc_config(&self) -> Value {
+        json!({
+            "encoding": self.encoding.as_str(),
+            "commitment": self.commitment.as_str()
+        })
+    }
+}
+
+/// Account data from an RPC `[data, encoding]` pair, decompressed if it was sent as
+/// `base64+zstd`.
+fn decode_account_bytes(data: &Value) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let data_str = data.get(0).and_then(|s| s.as_str()).ok_or("account data is not a base64 string")?;
+    let bytes = BASE64.decode(data_str)?;
+    match data.get(1).and_then(|e| e.as_str()) {
+        Some("base64+zstd") => Ok(zstd::decode_all(bytes.as_slice())?),
+        _ => Ok(bytes),
+    }
+}
 
 pub struct SolanaWebSocketClient {
     sender: mpsc::UnboundedSender<WebSocketCommand>,
-    receiver: mpsc::UnboundedReceiver<WhirlpoolUpdate>,
+    receiver: mpsc::UnboundedReceiver<WebSocketEvent>,
+    // Used by `subscribe` and `subscribe_whirlpools`
+    options: AccountOptions,
 }
 
 #[derive(Debug, Clone)]
 pub enum WebSocketCommand {
-    Subscribe(Pubkey),
+    Subscribe(Pubkey, DexKind, AccountOptions),
     Unsubscribe(Pubkey),
+    SubscribeWhirlpools(WhirlpoolFilter, AccountOptions),
+    UnsubscribeWhirlpools(WhirlpoolFilter),
     Shutdown,
 }
 
+/// Narrows a `programSubscribe` on the Whirlpool program to pools of the given mints. With
+/// neither mint set, every Whirlpool on chain is streamed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WhirlpoolFilter {
+    pub token_mint_a: Option<Pubkey>,
+    pub token_mint_b: Option<Pubkey>,
+}
+
+impl WhirlpoolFilter {
+    /// Filters covering every pool of two tokens. A pool stores its mints in a fixed order,
+    /// so each ordering needs its own subscription.
+    pub fn pair(mint_x: Pubkey, mint_y: Pubkey) -> [Self; 2] {
+        [
+            Self { token_mint_a: Some(mint_x), token_mint_b: Some(mint_y) },
+            Self { token_mint_a: Some(mint_y), token_mint_b: Some(mint_x) },
+        ]
+    }
+
+    fn rpc_filters(&self) -> Vec<Value> {
+        let mut filters = vec![json!({ "dataSize": Whirlpool::LEN })];
+        for (offset, mint) in [
+            (Whirlpool::TOKEN_MINT_A_OFFSET, self.token_mint_a),
+            (Whirlpool::TOKEN_MINT_B_OFFSET, self.token_mint_b),
+        ] {
+            if let Some(mint) = mint {
+                filters.push(json!({ "memcmp": { "offset": offset, "bytes": mint.to_string() } }));
+            }
+        }
+        filters
+    }
+
+    /// Whether Whirlpool account `data` passes this filter, as the server would decide.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        let mint_at = |offset: usize, mint: Option<Pubkey>| {
+            mint.is_none_or(|mint| data.get(offset..offset + 32) == Some(mint.as_ref()))
+        };
+        data.len() == Whirlpool::LEN
+            && mint_at(Whirlpool::TOKEN_MINT_A_OFFSET, self.token_mint_a)
+            && mint_at(Whirlpool::TOKEN_MINT_B_OFFSET, self.token_mint_b)
+    }
+}
+
 #[derive(Debug, Clone)]
-pub struct WhirlpoolUpdate {
+pub struct PoolUpdate {
     pub pubkey: Pubkey,
-    pub whirlpool: Whirlpool,
+    pub dex: DexKind,
+    pub pool: Arc<dyn DexPool>,
     pub timestamp: u64,
     pub slot: u64,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionState {
+    /// Opening the first connection.
+    Connecting { attempt: u32 },
+    /// Connected and (re)subscribed.
+    Live,
+    /// The stream dropped; reconnecting after a backoff.
+    Degraded { attempt: u32, retry_in: Duration, reason: String },
+    /// Reconnection is disabled or retries are exhausted. The client is no longer running.
+    Failed { reason: String },
+    /// Shut down on request.
+    Disconnected,
+}
+
+#[derive(Debug, Clone)]
+pub enum WebSocketEvent {
+    Update(PoolUpdate),
+    ConnectionState(ConnectionState),
+    /// The server rejected an `accountSubscribe`. The account is dropped from the active set.
+    SubscribeFailed { pubkey: Pubkey, error: String },
+    /// A Whirlpool subscription produced a pool the caller never subscribed to. Its updates
+    /// follow as `Update`s.
+    PoolDiscovered { pubkey: Pubkey, filter: WhirlpoolFilter },
+    /// The server rejected a Whirlpool `programSubscribe`, which is dropped.
+    WhirlpoolSubscribeFailed { filter: WhirlpoolFilter, error: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    pub auto_reconnect: bool,
+    /// Consecutive failed attempts allowed before giving up.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            auto_reconnect: true,
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl From<&ConnectionConfig> for ReconnectPolicy {
+    fn from(config: &ConnectionConfig) -> Self {
+        Self {
+            auto_reconnect: config.auto_reconnect,
+            max_attempts: config.retry_attempts,
+            ..Self::default()
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Exponential backoff with equal jitter: half the capped delay is fixed, the other half
+    /// is scaled by `jitter` (expected in `0.0..=1.0`).
+    pub fn backoff_delay(&self, attempt: u32, jitter: f64) -> Duration {
+        jittered_backoff(self.base_delay, self.max_delay, attempt, jitter)
+    }
+}
+
+// Delay before retry number `attempt` (from 1): `base_delay` doubling per retry up to
+// `max_delay`, of which the upper half is scaled by `jitter`
+fn jittered_backoff(base_delay: Duration, max_delay: Duration, attempt: u32, jitter: f64) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16);
+    let capped = base_delay.saturating_mul(1u32 << exponent).min(max_delay);
+    capped / 2 + capped.mul_f64(jitter.clamp(0.0, 1.0) / 2.0)
+}
+
+fn random_jitter() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    nanos as f64 / 1_000_000_000.0
+}
+
+// Why a live session ended
+enum SessionEnd {
+    Shutdown,
+    Dropped(String),
+}
+
+/// What a subscription streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SubscriptionTarget {
+    Account(Pubkey),
+    Whirlpools(WhirlpoolFilter),
+}
+
+impl SubscriptionTarget {
+    fn subscribe_request(&self, id: u64, options: AccountOptions) -> Value {
+        let mut config = options.rpc_config();
+        match self {
+            SubscriptionTarget::Account(pubkey) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "accountSubscribe",
+                "params": [pubkey.to_string(), config]
+            }),
+            SubscriptionTarget::Whirlpools(filter) => {
+                config["filters"] = json!(filter.rpc_filters());
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": "programSubscribe",
+                    "params": [constants::WHIRLPOOL_PROGRAM_ID, config]
+                })
+            }
+        }
+    }
+
+    fn method(&self) -> &'static str {
+        match self {
+            SubscriptionTarget::Account(_) => "accountSubscribe",
+            SubscriptionTarget::Whirlpools(_) => "programSubscribe",
+        }
+    }
+
+    fn unsubscribe_method(&self) -> &'static str {
+        match self {
+            SubscriptionTarget::Account(_) => "accountUnsubscribe",
+            SubscriptionTarget::Whirlpools(_) => "programUnsubscribe",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PendingRequest {
+    Subscribe(SubscriptionTarget),
+    Unsubscribe(SubscriptionTarget),
+}
+
+/// What a JSON-RPC response meant for the subscription state.
+#[derive(Debug, Clone, PartialEq)]
+enum ResponseOutcome {
+    Subscribed(Pubkey),
+    SubscribeFailed { pubkey: Pubkey, error: String },
+    WhirlpoolsSubscribed(WhirlpoolFilter),
+    WhirlpoolSubscribeFailed { filter: WhirlpoolFilter, error: String },
+    /// The caller unsubscribed while the subscribe was in flight; this request undoes it.
+    CancelSubscription(Value),
+    Unsubscribed(SubscriptionTarget),
+    Ignored,
+}
+
+/// Correlates JSON-RPC requests with their responses and maps the subscription ids the server
+/// hands out back to the accounts or program filters they were opened for. State is per
+/// connection.
+#[derive(Debug, Default)]
+struct SubscriptionTracker {
+    next_request_id: u64,
+    pending: HashMap<u64, PendingRequest>,
+    by_subscription: HashMap<u64, SubscriptionTarget>,
+    by_target: HashMap<SubscriptionTarget, u64>,
+    // Subscribes still in flight when the caller asked to unsubscribe
+    cancelled: HashSet<SubscriptionTarget>,
+}
+
+impl SubscriptionTracker {
+    fn next_id(&mut self) -> u64 {
+        self.next_request_id += 1;
+        self.next_request_id
+    }
+
+    fn subscribe_request(&mut self, pubkey: Pubkey, options: AccountOptions) -> Option<Value> {
+        self.request_subscription(SubscriptionTarget::Account(pubkey), options)
+    }
+
+    fn unsubscribe_request(&mut self, pubkey: Pubkey) -> Option<Value> {
+        self.request_unsubscription(SubscriptionTarget::Account(pubkey))
+    }
+
+    fn whirlpools_subscribe_request(&mut self, filter: WhirlpoolFilter, options: AccountOptions) -> Option<Value> {
+        self.request_subscription(SubscriptionTarget::Whirlpools(filter), options)
+    }
+
+    fn whirlpools_unsubscribe_request(&mut self, filter: WhirlpoolFilter) -> Option<Value> {
+        self.request_unsubscription(SubscriptionTarget::Whirlpools(filter))
+    }
+
+    fn request_subscription(&mut self, target: SubscriptionTarget, options: AccountOptions) -> Option<Value> {
+        self.cancelled.remove(&target);
+        let in_flight = self.pending.values().any(|p| *p == PendingRequest::Subscribe(target));
+        if self.by_target.contains_key(&target) || in_flight {
+            return None;
+        }
+
+        let id = self.next_id();
+        self.pending.insert(id, PendingRequest::Subscribe(target));
+        Some(target.subscribe_request(id, options))
+    }
+
+    fn request_unsubscription(&mut self, target: SubscriptionTarget) -> Option<Value> {
+        match self.by_target.remove(&target) {
+            Some(subscription) => {
+                self.by_subscription.remove(&subscription);
+                Some(self.unsubscribe(target, subscription))
+            }
+            None => {
+                if self.pending.values().any(|p| *p == PendingRequest::Subscribe(target)) {
+                    self.cancelled.insert(target);
+                }
+                None
+            }
+        }
+    }
+
+    fn unsubscribe(&mut self, target: SubscriptionTarget, subscription: u64) -> Value {
+        let id = self.next_id();
+        self.pending.insert(id, PendingRequest::Unsubscribe(target));
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": target.unsubscribe_method(),
+            "params": [subscription]
+        })
+    }
+
+    fn handle_response(&mut self, id: u64, result: Option<&Value>, error: Option<&Value>) -> ResponseOutcome {
+        let Some(request) = self.pending.remove(&id) else {
+            return ResponseOutcome::Ignored;
+        };
+
+        match request {
+            PendingRequest::Subscribe(target) => {
+                let subscription = match (result.and_then(|r| r.as_u64()), error) {
+                    (Some(subscription), None) => subscription,
+                    (_, error) => {
+                        self.cancelled.remove(&target);
+                        let error = error
+                            .and_then(|e| e.get("message"))
+                            .and_then(|m| m.as_str())
+                            .map(str::to_string)
+                            .unwrap_or_else(|| format!("unexpected {} response", target.method()));
+                        return match target {
+                            SubscriptionTarget::Account(pubkey) => ResponseOutcome::SubscribeFailed { pubkey, error },
+                            SubscriptionTarget::Whirlpools(filter) => {
+                                ResponseOutcome::WhirlpoolSubscribeFailed { filter, error }
+                            }
+                        };
+                    }
+                };
+
+                if self.cancelled.remove(&target) {
+                    return ResponseOutcome::CancelSubscription(self.unsubscribe(target, subscription));
+                }
+                self.by_subscription.insert(subscription, target);
+                self.by_target.insert(target, subscription);
+                match target {
+                    SubscriptionTarget::Account(pubkey) => ResponseOutcome::Subscribed(pubkey),
+                    SubscriptionTarget::Whirlpools(filter) => ResponseOutcome::WhirlpoolsSubscribed(filter),
+                }
+            }
+            PendingRequest::Unsubscribe(target) => {
+                if let Some(error) = error {
+                    eprintln!("{} failed for {:?}: {}", target.unsubscribe_method(), target, error);
+                }
+                ResponseOutcome::Unsubscribed(target)
+            }
+        }
+    }
+
+    fn target_for_subscription(&self, subscription: u64) -> Option<SubscriptionTarget> {
+        self.by_subscription.get(&subscription).copied()
+    }
+
+    #[cfg(test)]
+    fn pubkey_for_subscription(&self, subscription: u64) -> Option<Pubkey> {
+        match self.target_for_subscription(subscription)? {
+            SubscriptionTarget::Account(pubkey) => Some(pubkey),
+            SubscriptionTarget::Whirlpools(_) => None,
+        }
+    }
+}
+
+/// Pools the caller asked for plus the companion accounts they decode with (fee configs,
+/// vaults), and the Whirlpool filters pools are discovered through. Outlives individual
+/// sessions so everything can be resubscribed after a reconnect.
+#[derive(Default)]
+struct PoolCache {
+    pools: HashMap<Pubkey, DexKind>,
+    // Options each pool was subscribed with; its companions are subscribed the same way
+    pool_options: HashMap<Pubkey, AccountOptions>,
+    pool_data: HashMap<Pubkey, Vec<u8>>,
+    // Companion account -> pools that need it
+    companions: HashMap<Pubkey, HashSet<Pubkey>>,
+    companion_data: AccountDataMap,
+    whirlpool_filters: HashMap<WhirlpoolFilter, AccountOptions>,
+    // Pools known only through a Whirlpool filter; they have no account subscription
+    discovered: HashSet<Pubkey>,
+}
+
+impl PoolCache {
+    fn add_pool(&mut self, pubkey: Pubkey, dex: DexKind, options: AccountOptions) {
+        self.discovered.remove(&pubkey);
+        self.pools.insert(pubkey, dex);
+        self.pool_options.insert(pubkey, options);
+    }
+
+    /// Returns companions no other pool needs any more.
+    fn remove_pool(&mut self, pubkey: &Pubkey) -> Vec<Pubkey> {
+        self.discovered.remove(pubkey);
+        self.pools.remove(pubkey);
+        self.pool_options.remove(pubkey);
+        self.pool_data.remove(pubkey);
+        self.release_companions(pubkey, &HashSet::new())
+    }
+
+    /// Stores a pool streamed by a Whirlpool filter. Returns whether it is new to the cache;
+    /// pools the caller subscribed to directly are updated but stay theirs.
+    fn discover_whirlpool(&mut self, pubkey: Pubkey, data: Vec<u8>) -> bool {
+        let is_new = !self.pools.contains_key(&pubkey);
+        if is_new {
+            self.pools.insert(pubkey, DexKind::Whirlpool);
+            self.discovered.insert(pubkey);
+        }
+        // Whirlpools have no companions
+        self.update_pool(pubkey, data);
+        is_new
+    }
+
+    /// Drops `filter` and the discovered pools no remaining filter matches.
+    fn remove_whirlpool_filter(&mut self, filter: &WhirlpoolFilter) {
+        self.whirlpool_filters.remove(filter);
+        let unmatched: Vec<Pubkey> = self
+            .discovered
+            .iter()
+            .filter(|pubkey| {
+                let data = self.pool_data.get(*pubkey).map(Vec::as_slice).unwrap_or_default();
+                !self.whirlpool_filters.keys().any(|filter| filter.matches(data))
+            })
+            .copied()
+            .collect();
+        for pubkey in unmatched {
+            self.remove_pool(&pubkey);
+        }
+    }
+
+    // Drops `pool` from every companion not in `keep`, returning the ones left unused
+    fn release_companions(&mut self, pool: &Pubkey, keep: &HashSet<Pubkey>) -> Vec<Pubkey> {
+        let mut released = Vec::new();
+        self.companions.retain(|companion, owners| {
+            if !keep.contains(companion) {
+                owners.remove(pool);
+            }
+            if owners.is_empty() {
+                released.push(*companion);
+                false
+            } else {
+                true
+            }
+        });
+        for companion in &released {
+            self.companion_data.remove(companion);
+        }
+        released
+    }
+
+    /// Every account that should have a live subscription.
+    fn subscribed_accounts(&self) -> Vec<Pubkey> {
+        self.pools
+            .keys()
+            .filter(|pubkey| !self.discovered.contains(pubkey))
+            .chain(self.companions.keys())
+            .copied()
+            .collect()
+    }
+
+    /// Options to subscribe `pubkey` with: the pool's own, or for a companion those of a pool
+    /// that needs it.
+    fn account_options(&self, pubkey: &Pubkey) -> AccountOptions {
+        let owner = match self.companions.get(pubkey) {
+            Some(owners) if !self.pools.contains_key(pubkey) => owners.iter().next(),
+            _ => Some(pubkey),
+        };
+        owner
+            .and_then(|owner| self.pool_options.get(owner))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Stores new pool data and returns `(companions to start tracking, companions released)`.
+    fn update_pool(&mut self, pubkey: Pubkey, data: Vec<u8>) -> (Vec<Pubkey>, Vec<Pubkey>) {
+        let Some(dex) = self.pools.get(&pubkey) else {
+            return (Vec::new(), Vec::new());
+        };
+        let wanted: HashSet<Pubkey> = dex.companion_accounts(&data).unwrap_or_default().into_iter().collect();
+        self.pool_data.insert(pubkey, data);
+
+        let released = self.release_companions(&pubkey, &wanted);
+        let mut added = Vec::new();
+        for companion in wanted {
+            let owners = self.companions.entry(companion).or_default();
+            if owners.is_empty() {
+                added.push(companion);
+            }
+            owners.insert(pubkey);
+        }
+        (added, released)
+    }
+
+    /// Stores companion data and returns the pools that need to be re-emitted.
+    fn update_companion(&mut self, pubkey: Pubkey, data: Vec<u8>) -> Vec<Pubkey> {
+        let Some(owners) = self.companions.get(&pubkey) else {
+            return Vec::new();
+        };
+        let owners = owners.iter().copied().collect();
+        self.companion_data.insert(pubkey, data);
+        owners
+    }
+
+    fn decode(&self, pubkey: &Pubkey) -> Option<(DexKind, Arc<dyn DexPool>)> {
+        let dex = *self.pools.get(pubkey)?;
+        let data = self.pool_data.get(pubkey)?;
+        dex.decode(data, &self.companion_data).ok().map(|pool| (dex, pool))
+    }
+}
+
+// A parsed text frame
+enum Incoming {
+    Response(ResponseOutcome),
+    Account { pubkey: Pubkey, slot: u64, data: Vec<u8> },
+    Whirlpool { filter: WhirlpoolFilter, pubkey: Pubkey, slot: u64, data: Vec<u8> },
+    Ignored,
+}
+
 #[derive(Debug, Clone)]
 pub struct TokenInfo {
     pub decimals: u8,
@@ -36,178 +613,655 @@ pub struct TokenInfo {
     pub name: String,
 }
 
+/// Outcome of a `simulateTransaction` call.
+#[derive(Debug, Clone)]
+pub struct SimulationResult {
+    /// The transaction error reported by the runtime, if it failed.
+    pub err: Option<Value>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// Data of the requested accounts after the simulation, in request order.
+    pub accounts: Vec<Option<Vec<u8>>>,
+}
+
+/// A swap as executed by the program in simulation, read from the trader's token balances.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedSwap {
+    pub a_to_b: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+}
+
+impl SimulatedSwap {
+    /// Execution price as UI token B per token A, comparable to `whirlpool_math` prices.
+    pub fn effective_price(&self, decimals_a: u8, decimals_b: u8) -> Option<f64> {
+        let (amount_a, amount_b) = if self.a_to_b {
+            (self.amount_in, self.amount_out)
+        } else {
+            (self.amount_out, self.amount_in)
+        };
+        if amount_a == 0 {
+            return None;
+        }
+        let ui_a = amount_a as f64 / 10f64.powi(decimals_a as i32);
+        let ui_b = amount_b as f64 / 10f64.powi(decimals_b as i32);
+        Some(ui_b / ui_a)
+    }
+
+    /// Relative difference of the simulated output from an `expected` output in the same
+    /// units, e.g. a `SwapQuote` or `price_utils::estimate_swap_output`. Positive when the
+    /// program paid out more than expected.
+    pub fn output_error(&self, expected: f64) -> f64 {
+        (self.amount_out as f64 - expected) / expected
+    }
+}
+
+/// Lowest fee, in micro-lamports per compute unit, paid by a landed transaction in `slot`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrioritizationFee {
+    pub slot: u64,
+    pub micro_lamports: u64,
+}
+
+/// Where a sent transaction stands, from `getSignatureStatuses`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// Not seen by the node yet.
+    Pending,
+    Processed,
+    Confirmed,
+    Finalized,
+    Failed(String),
+    /// Its blockhash expired before it landed; it can no longer be included.
+    Expired,
+}
+
+impl TransactionStatus {
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            TransactionStatus::Finalized | TransactionStatus::Failed(_) | TransactionStatus::Expired
+        )
+    }
+}
+
+/// Legacy wire format: signatures in the order of the message's signer keys, then the message.
+pub(crate) fn encode_transaction(message: &TransactionMessage, signatures: &[[u8; 64]]) -> Vec<u8> {
+    let mut transaction = Vec::new();
+    // compact-u16 signature count
+    let mut count = signatures.len();
+    loop {
+        let byte = (count & 0x7f) as u8;
+        count >>= 7;
+        if count == 0 {
+            transaction.push(byte);
+            break;
+        }
+        transaction.push(byte | 0x80);
+    }
+    for signature in signatures {
+        transaction.extend_from_slice(signature);
+    }
+    transaction.extend_from_slice(&message.serialize());
+    transaction
+}
+
+// Zeroed signatures; only valid for simulation with `sigVerify: false`
+fn unsigned_transaction(instructions: &[Instruction], payer: &Pubkey) -> Vec<u8> {
+    let message = TransactionMessage::new(instructions, Some(payer));
+    let signatures = vec![[0u8; 64]; message.header.num_required_signatures as usize];
+    encode_transaction(&message, &signatures)
+}
+
 impl SolanaWebSocketClient {
     pub async fn new(rpc_url: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let (cmd_sender, mut cmd_receiver) = mpsc::unbounded_channel();
-        let (update_sender, update_receiver) = mpsc::unbounded_channel();
-        
+        Self::with_reconnect_policy(rpc_url, ReconnectPolicy::default()).await
+    }
+
+    pub async fn with_reconnect_policy(
+        rpc_url: &str,
+        policy: ReconnectPolicy,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Self::with_http_client(rpc_url, policy, None).await
+    }
+
+    /// Like `with_reconnect_policy`, but fetches companion accounts (fee configs, vaults) over
+    /// HTTP as soon as a pool needs them instead of waiting for them to change on chain.
+    pub async fn with_http_client(
+        rpc_url: &str,
+        policy: ReconnectPolicy,
+        http_client: Option<SolanaHttpClient>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let (cmd_sender, cmd_receiver) = mpsc::unbounded_channel();
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+
         let rpc_url = rpc_url.to_string();
         tokio::spawn(async move {
-            if let Err(e) = Self::websocket_task(rpc_url, cmd_receiver, update_sender).await {
-                eprintln!("WebSocket task error: {}", e);
-            }
+            Self::websocket_task(rpc_url, policy, http_client, cmd_receiver, event_sender).await;
         });
 
         Ok(Self {
             sender: cmd_sender,
-            receiver: update_receiver,
+            receiver: event_receiver,
+            options: AccountOptions::default(),
         })
     }
 
+    // Owns the connection for the lifetime of the client: connects, runs a session until it
+    // drops, then backs off and reconnects, replaying every active subscription.
     async fn websocket_task(
         rpc_url: String,
+        policy: ReconnectPolicy,
+        http_client: Option<SolanaHttpClient>,
         mut cmd_receiver: mpsc::UnboundedReceiver<WebSocketCommand>,
-        update_sender: mpsc::UnboundedSender<WhirlpoolUpdate>,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let (ws_stream, _) = connect_async(&rpc_url).await?;
+        event_sender: mpsc::UnboundedSender<WebSocketEvent>,
+    ) {
+        let send_state = |state: ConnectionState| {
+            let _ = event_sender.send(WebSocketEvent::ConnectionState(state));
+        };
+
+        let mut cache = PoolCache::default();
+        let mut attempt = 0u32;
+        let mut last_error: Option<String> = None;
+
+        loop {
+            if last_error.is_none() {
+                send_state(ConnectionState::Connecting { attempt });
+            }
+
+            match connect_async(&rpc_url).await {
+                Ok((ws_stream, _)) => {
+                    attempt = 0;
+                    send_state(ConnectionState::Live);
+                    match Self::run_session(ws_stream, &mut cache, http_client.as_ref(), &mut cmd_receiver, &event_sender).await {
+                        SessionEnd::Shutdown => {
+                            send_state(ConnectionState::Disconnected);
+                            return;
+                        }
+                        SessionEnd::Dropped(reason) => last_error = Some(reason),
+                    }
+                }
+                Err(e) => last_error = Some(e.to_string()),
+            }
+
+            let reason = last_error.clone().unwrap_or_default();
+            if !policy.auto_reconnect || attempt >= policy.max_attempts {
+                send_state(ConnectionState::Failed { reason });
+                return;
+            }
+
+            attempt += 1;
+            let retry_in = policy.backoff_delay(attempt, random_jitter());
+            send_state(ConnectionState::Degraded { attempt, retry_in, reason });
+
+            // Keep accepting commands while waiting so nothing issued during an outage is lost
+            let sleep = tokio::time::sleep(retry_in);
+            tokio::pin!(sleep);
+            loop {
+                tokio::select! {
+                    _ = &mut sleep => break,
+                    cmd = cmd_receiver.recv() => match cmd {
+                        Some(WebSocketCommand::Subscribe(pubkey, dex, options)) => {
+                            cache.add_pool(pubkey, dex, options);
+                        }
+                        Some(WebSocketCommand::Unsubscribe(pubkey)) => {
+                            cache.remove_pool(&pubkey);
+                        }
+                        Some(WebSocketCommand::SubscribeWhirlpools(filter, options)) => {
+                            cache.whirlpool_filters.insert(filter, options);
+                        }
+                        Some(WebSocketCommand::UnsubscribeWhirlpools(filter)) => {
+                            cache.remove_whirlpool_filter(&filter);
+                        }
+                        Some(WebSocketCommand::Shutdown) | None => {
+                            send_state(ConnectionState::Disconnected);
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn run_session(
+        ws_stream: WsStream,
+        cache: &mut PoolCache,
+        http_client: Option<&SolanaHttpClient>,
+        cmd_receiver: &mut mpsc::UnboundedReceiver<WebSocketCommand>,
+        event_sender: &mpsc::UnboundedSender<WebSocketEvent>,
+    ) -> SessionEnd {
         let (mut ws_sender, mut ws_receiver) = ws_stream.split();
-        
-        let mut subscriptions: HashMap<Pubkey, u64> = HashMap::new();
-        let mut subscription_id_counter = 1u64;
+
+        let mut tracker = SubscriptionTracker::default();
+
+        // Replay every subscription that was active before the reconnect
+        let mut replay = Vec::new();
+        for pubkey in cache.subscribed_accounts() {
+            replay.extend(tracker.subscribe_request(pubkey, cache.account_options(&pubkey)));
+        }
+        for (filter, options) in &cache.whirlpool_filters {
+            replay.extend(tracker.whirlpools_subscribe_request(*filter, *options));
+        }
+        for request in replay {
+            if let Err(e) = ws_sender.send(Message::Text(request.to_string().into())).await {
+                return SessionEnd::Dropped(e.to_string());
+            }
+        }
+
+        let mut ping_interval = tokio::time::interval(PING_INTERVAL);
+        let mut last_message_at = Instant::now();
+
+        // Companion accounts read over HTTP come back here so the loop never waits on a request
+        let (seed_sender, mut seed_receiver) = mpsc::unbounded_channel::<Vec<FetchedAccount>>();
 
         loop {
+            // Requests produced by this iteration
+            let mut outgoing = Vec::new();
+
             tokio::select! {
                 // Handle commands from the main thread
                 cmd = cmd_receiver.recv() => {
                     match cmd {
-                        Some(WebSocketCommand::Subscribe(pubkey)) => {
-                            let subscription_request = json!({
-                                "jsonrpc": "2.0",
-                                "id": subscription_id_counter,
-                                "method": "accountSubscribe",
-                                "params": [
-                                    pubkey.to_string(),
-                                    {
-                                        "encoding": "base64",
-                                        "commitment": "confirmed"
-                                    }
-                                ]
-                            });
-
-                            if let Ok(msg) = serde_json::to_string(&subscription_request) {
-                                let _ = ws_sender.send(Message::Text(msg)).await;
-                                subscriptions.insert(pubkey, subscription_id_counter);
-                                subscription_id_counter += 1;
-                            }
+                        Some(WebSocketCommand::Subscribe(pubkey, dex, options)) => {
+                            cache.add_pool(pubkey, dex, options);
+                            outgoing.extend(tracker.subscribe_request(pubkey, options));
                         },
                         Some(WebSocketCommand::Unsubscribe(pubkey)) => {
-                            if let Some(&sub_id) = subscriptions.get(&pubkey) {
-                                let unsubscribe_request = json!({
-                                    "jsonrpc": "2.0",
-                                    "id": subscription_id_counter,
-                                    "method": "accountUnsubscribe",
-                                    "params": [sub_id]
-                                });
-
-                                if let Ok(msg) = serde_json::to_string(&unsubscribe_request) {
-                                    let _ = ws_sender.send(Message::Text(msg)).await;
-                                    subscriptions.remove(&pubkey);
-                                    subscription_id_counter += 1;
-                                }
+                            for account in std::iter::once(pubkey).chain(cache.remove_pool(&pubkey)) {
+                                outgoing.extend(tracker.unsubscribe_request(account));
                             }
                         },
-                        Some(WebSocketCommand::Shutdown) | None => break,
+                        Some(WebSocketCommand::SubscribeWhirlpools(filter, options)) => {
+                            cache.whirlpool_filters.insert(filter, options);
+                            outgoing.extend(tracker.whirlpools_subscribe_request(filter, options));
+                        },
+                        Some(WebSocketCommand::UnsubscribeWhirlpools(filter)) => {
+                            cache.remove_whirlpool_filter(&filter);
+                            outgoing.extend(tracker.whirlpools_unsubscribe_request(filter));
+                        },
+                        Some(WebSocketCommand::Shutdown) | None => {
+                            let _ = ws_sender.send(Message::Close(None)).await;
+                            return SessionEnd::Shutdown;
+                        }
+                    }
+                },
+
+                // Companion accounts seeded over HTTP
+                Some(seeded) = seed_receiver.recv() => {
+                    for fetched in seeded {
+                        let Some(account) = fetched.account else {
+                            continue;
+                        };
+                        // Data streamed since the request went out is at least as new
+                        if cache.companion_data.contains_key(&fetched.pubkey) {
+                            continue;
+                        }
+                        for pool in cache.update_companion(fetched.pubkey, account.data) {
+                            Self::emit_pool_update(cache, pool, fetched.slot, event_sender);
+                        }
+                    }
+                },
+
+                // Keep-alive and dead stream detection
+                _ = ping_interval.tick() => {
+                    if last_message_at.elapsed() > STALE_STREAM_TIMEOUT {
+                        return SessionEnd::Dropped("no messages received before timeout".to_string());
+                    }
+                    if let Err(e) = ws_sender.send(Message::Ping(Vec::new().into())).await {
+                        return SessionEnd::Dropped(e.to_string());
                     }
                 },
-                
+
                 // Handle incoming WebSocket messages
                 msg = ws_receiver.next() => {
+                    last_message_at = Instant::now();
                     match msg {
                         Some(Ok(Message::Text(text))) => {
-                            if let Err(e) = Self::handle_websocket_message(
-                                &text,
-                                &subscriptions,
-                                &update_sender
-                            ).await {
-                                eprintln!("Error handling WebSocket message: {}", e);
+                            match Self::handle_websocket_message(&text, &mut tracker) {
+                                Ok(Incoming::Response(ResponseOutcome::CancelSubscription(request))) => {
+                                    outgoing.push(request);
+                                },
+                                Ok(Incoming::Response(ResponseOutcome::SubscribeFailed { pubkey, error })) => {
+                                    for companion in cache.remove_pool(&pubkey) {
+                                        outgoing.extend(tracker.unsubscribe_request(companion));
+                                    }
+                                    let _ = event_sender.send(WebSocketEvent::SubscribeFailed { pubkey, error });
+                                },
+                                Ok(Incoming::Response(ResponseOutcome::WhirlpoolSubscribeFailed { filter, error })) => {
+                                    cache.remove_whirlpool_filter(&filter);
+                                    let _ = event_sender.send(WebSocketEvent::WhirlpoolSubscribeFailed { filter, error });
+                                },
+                                Ok(Incoming::Whirlpool { filter, pubkey, slot, data }) => {
+                                    // Pools subscribed to directly also arrive here; skip repeats
+                                    if cache.pool_data.get(&pubkey) != Some(&data) {
+                                        if cache.discover_whirlpool(pubkey, data) {
+                                            let _ = event_sender.send(WebSocketEvent::PoolDiscovered { pubkey, filter });
+                                        }
+                                        Self::emit_pool_update(cache, pubkey, slot, event_sender);
+                                    }
+                                },
+                                Ok(Incoming::Account { pubkey, slot, data }) => {
+                                    let changed = if cache.pools.contains_key(&pubkey) {
+                                        let (added, released) = cache.update_pool(pubkey, data);
+                                        for companion in released {
+                                            outgoing.extend(tracker.unsubscribe_request(companion));
+                                        }
+                                        if !added.is_empty() {
+                                            Self::fetch_companions(http_client, added.clone(), &seed_sender);
+                                            let options = cache.account_options(&pubkey);
+                                            for companion in added {
+                                                outgoing.extend(tracker.subscribe_request(companion, options));
+                                            }
+                                        }
+                                        vec![pubkey]
+                                    } else {
+                                        cache.update_companion(pubkey, data)
+                                    };
+
+                                    for pool in changed {
+                                        Self::emit_pool_update(cache, pool, slot, event_sender);
+                                    }
+                                },
+                                Ok(_) => {},
+                                Err(e) => eprintln!("Error handling WebSocket message: {}", e),
                             }
                         },
-                        Some(Ok(Message::Close(_))) => {
-                            println!("WebSocket connection closed");
-                            break;
+                        Some(Ok(Message::Ping(payload))) => {
+                            let _ = ws_sender.send(Message::Pong(payload)).await;
                         },
-                        Some(Err(e)) => {
-                            eprintln!("WebSocket error: {}", e);
-                            break;
+                        Some(Ok(Message::Close(frame))) => {
+                            let reason = frame
+                                .map(|f| format!("closed by server: {}", f.reason))
+                                .unwrap_or_else(|| "closed by server".to_string());
+                            return SessionEnd::Dropped(reason);
                         },
-                        None => break,
+                        Some(Ok(_)) => {},
+                        Some(Err(e)) => return SessionEnd::Dropped(e.to_string()),
+                        None => return SessionEnd::Dropped("stream ended".to_string()),
                     }
                 }
             }
+
+            for request in outgoing {
+                if let Err(e) = ws_sender.send(Message::Text(request.to_string().into())).await {
+                    return SessionEnd::Dropped(e.to_string());
+                }
+            }
         }
+    }
+
+    // Seeds newly needed companion accounts in the background, sending them to `seed_sender`;
+    // without an HTTP client they fill in on first change
+    fn fetch_companions(
+        http_client: Option<&SolanaHttpClient>,
+        companions: Vec<Pubkey>,
+        seed_sender: &mpsc::UnboundedSender<Vec<FetchedAccount>>,
+    ) {
+        let Some(http_client) = http_client.cloned() else {
+            return;
+        };
+        let seed_sender = seed_sender.clone();
+        tokio::spawn(async move {
+            match http_client.get_multiple_accounts(&companions).await {
+                Ok(accounts) => {
+                    let _ = seed_sender.send(accounts);
+                }
+                Err(e) => eprintln!("Failed to fetch companion accounts: {}", e),
+            }
+        });
+    }
+
+    fn emit_pool_update(
+        cache: &PoolCache,
+        pubkey: Pubkey,
+        slot: u64,
+        event_sender: &mpsc::UnboundedSender<WebSocketEvent>,
+    ) {
+        // Pools whose companions have not arrived yet cannot be decoded and are skipped
+        let Some((dex, pool)) = cache.decode(&pubkey) else {
+            return;
+        };
 
-        Ok(())
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let update = PoolUpdate {
+            pubkey,
+            dex,
+            pool,
+            timestamp,
+            slot,
+        };
+
+        let _ = event_sender.send(WebSocketEvent::Update(update));
     }
 
-    async fn handle_websocket_message(
+    // Parses one text frame: responses update the tracker, `accountNotification`s and
+    // `programNotification`s are resolved to what their subscription id was opened for.
+    fn handle_websocket_message(
         text: &str,
-        subscriptions: &HashMap<Pubkey, u64>,
-        update_sender: &mpsc::UnboundedSender<WhirlpoolUpdate>,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        tracker: &mut SubscriptionTracker,
+    ) -> Result<Incoming, Box<dyn std::error::Error + Send + Sync>> {
         let msg: Value = serde_json::from_str(text)?;
 
-        // Check if this is a subscription notification
-        if let Some(params) = msg.get("params") {
-            if let Some(result) = params.get("result") {
-                if let Some(value) = result.get("value") {
-                    if let (Some(data_str), Some(account_str)) = (
-                        value.get("data").and_then(|d| d.get(0)).and_then(|s| s.as_str()),
-                        value.get("pubkey").and_then(|s| s.as_str())
-                    ) {
-                        // Decode base64 data
-                        let data = base64::decode(data_str)?;
-                        let pubkey = account_str.parse::<Pubkey>()?;
-                        
-                        // Try to deserialize as Whirlpool
-                        if let Ok(whirlpool) = Whirlpool::try_deserialize(&data) {
-                            let timestamp = std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap()
-                                .as_secs();
-                            
-                            let slot = value.get("context")
-                                .and_then(|c| c.get("slot"))
-                                .and_then(|s| s.as_u64())
-                                .unwrap_or(0);
-
-                            let update = WhirlpoolUpdate {
-                                pubkey,
-                                whirlpool,
-                                timestamp,
-                                slot,
-                            };
-
-                            let _ = update_sender.send(update);
-                        }
-                    }
-                }
+        // Response to one of our requests
+        if let Some(id) = msg.get("id").and_then(|id| id.as_u64()) {
+            return Ok(Incoming::Response(tracker.handle_response(id, msg.get("result"), msg.get("error"))));
+        }
+
+        let method = msg.get("method").and_then(|m| m.as_str()).unwrap_or_default();
+        if method != "accountNotification" && method != "programNotification" {
+            return Ok(Incoming::Ignored);
+        }
+
+        let params = msg.get("params").ok_or("notification without params")?;
+        let subscription = params
+            .get("subscription")
+            .and_then(|s| s.as_u64())
+            .ok_or("notification without subscription id")?;
+        let Some(target) = tracker.target_for_subscription(subscription) else {
+            // Notifications can still arrive for a subscription that was just closed
+            return Ok(Incoming::Ignored);
+        };
+
+        let result = params.get("result").ok_or("notification without result")?;
+        let value = result.get("value").ok_or("notification without value")?;
+        let slot = result.get("context")
+            .and_then(|c| c.get("slot"))
+            .and_then(|s| s.as_u64())
+            .unwrap_or(0);
+
+        match target {
+            SubscriptionTarget::Account(pubkey) => {
+                let data = Self::decode_account_data(value)?;
+                Ok(Incoming::Account { pubkey, slot, data })
+            }
+            SubscriptionTarget::Whirlpools(filter) => {
+                let pubkey = value
+                    .get("pubkey")
+                    .and_then(|p| p.as_str())
+                    .ok_or("programNotification without pubkey")?
+                    .parse::<Pubkey>()?;
+                let account = value.get("account").ok_or("programNotification without account")?;
+                let data = Self::decode_account_data(account)?;
+                Ok(Incoming::Whirlpool { filter, pubkey, slot, data })
             }
         }
+    }
+
+    fn decode_account_data(account: &Value) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        decode_account_bytes(account.get("data").ok_or("notification without account data")?)
+    }
+
+    /// Commitment and encoding `subscribe` and `subscribe_whirlpools` use. Defaults to
+    /// `confirmed` and plain base64.
+    pub fn with_account_options(mut self, options: AccountOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn subscribe(&self, pubkey: Pubkey, dex: DexKind) -> Result<(), mpsc::error::SendError<WebSocketCommand>> {
+        self.subscribe_with_options(pubkey, dex, self.options)
+    }
+
+    /// Like `subscribe`, with its own commitment and encoding. Companion accounts the pool
+    /// decodes with are subscribed the same way.
+    pub fn subscribe_with_options(
+        &self,
+        pubkey: Pubkey,
+        dex: DexKind,
+        options: AccountOptions,
+    ) -> Result<(), mpsc::error::SendError<WebSocketCommand>> {
+        self.sender.send(WebSocketCommand::Subscribe(pubkey, dex, options))
+    }
+
+    pub fn unsubscribe(&self, pubkey: Pubkey) -> Result<(), mpsc::error::SendError<WebSocketCommand>> {
+        self.sender.send(WebSocketCommand::Unsubscribe(pubkey))
+    }
 
-        Ok(())
+    /// Streams every Whirlpool passing `filter` through one `programSubscribe`, including
+    /// pools never passed to `subscribe`. Those are announced with `PoolDiscovered`.
+    pub fn subscribe_whirlpools(&self, filter: WhirlpoolFilter) -> Result<(), mpsc::error::SendError<WebSocketCommand>> {
+        self.subscribe_whirlpools_with_options(filter, self.options)
     }
 
-    pub fn subscribe(&self, pubkey: Pubkey) -> Result<(), mpsc::error::SendError<WebSocketCommand>> {
-        self.sender.send(WebSocketCommand::Subscribe(pubkey))
+    pub fn subscribe_whirlpools_with_options(
+        &self,
+        filter: WhirlpoolFilter,
+        options: AccountOptions,
+    ) -> Result<(), mpsc::error::SendError<WebSocketCommand>> {
+        self.sender.send(WebSocketCommand::SubscribeWhirlpools(filter, options))
     }
 
-    pub fn unsubscribe(&self, pubkey: Pubkey) -> Result<(), mpsc::error::SendError<WebSocketCommand>> {
-        self.sender.send(WebSocketCommand::Unsubscribe(pubkey))
+    pub fn unsubscribe_whirlpools(&self, filter: WhirlpoolFilter) -> Result<(), mpsc::error::SendError<WebSocketCommand>> {
+        self.sender.send(WebSocketCommand::UnsubscribeWhirlpools(filter))
+    }
+
+    pub fn shutdown(&self) -> Result<(), mpsc::error::SendError<WebSocketCommand>> {
+        self.sender.send(WebSocketCommand::Shutdown)
     }
 
-    pub async fn recv(&mut self) -> Option<WhirlpoolUpdate> {
+    pub async fn recv(&mut self) -> Option<WebSocketEvent> {
         self.receiver.recv().await
     }
 
-    pub fn try_recv(&mut self) -> Result<WhirlpoolUpdate, mpsc::error::TryRecvError> {
+    pub fn try_recv(&mut self) -> Result<WebSocketEvent, mpsc::error::TryRecvError> {
         self.receiver.try_recv()
     }
 }
 
+// getMultipleAccounts accepts at most this many keys per request
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+// Chunks of one batched fetch that are in flight at once
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+// Reads of a pool and its tick arrays before giving up on a consistent snapshot
+const MAX_SNAPSHOT_ATTEMPTS: u32 = 5;
+// Backoff between retries of one HTTP request, far shorter than between reconnects
+const HTTP_RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+const HTTP_RETRY_MAX_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, thiserror::Error)]
+pub enum RpcError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("HTTP status {0}")]
+    Status(reqwest::StatusCode),
+    #[error("RPC error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+impl RpcError {
+    /// Whether the same request may succeed if sent again.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            RpcError::Http(e) => e.is_timeout() || e.is_connect(),
+            RpcError::Status(status) => {
+                *status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+            // Node is behind, or has not reached the requested minimum context slot yet
+            RpcError::Rpc { code, .. } => matches!(code, -32005 | -32016),
+            RpcError::InvalidResponse(_) => false,
+        }
+    }
+}
+
+/// An existing account as returned by the RPC node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountInfo {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+    pub executable: bool,
+}
+
+/// One account of a batched fetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchedAccount {
+    pub pubkey: Pubkey,
+    /// Slot the account was read at. Accounts fetched in different requests can differ.
+    pub slot: u64,
+    /// `None` if the account does not exist.
+    pub account: Option<AccountInfo>,
+}
+
+// Tick arrays a swap from the pool's current price could touch, current array first
+fn surrounding_tick_array_addresses(whirlpool_pubkey: &Pubkey, whirlpool: &Whirlpool) -> Vec<Pubkey> {
+    let program_id = constants::whirlpool_profram_id();
+    derive_surrounding_tick_array_starts(whirlpool.tick_current_index, whirlpool.tick_spacing)
+        .into_iter()
+        .map(|start| get_tick_array_address(whirlpool_pubkey, start, &program_id))
+        .collect()
+}
+
+fn parse_account_info(value: &Value) -> Result<Option<AccountInfo>, RpcError> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let invalid = |what: &str| RpcError::InvalidResponse(format!("account without {}", what));
+    let data = decode_account_bytes(value.get("data").ok_or_else(|| invalid("data"))?)
+        .map_err(|e| RpcError::InvalidResponse(e.to_string()))?;
+    let owner = match value.get("owner").and_then(|o| o.as_str()) {
+        Some(owner) => owner.parse().map_err(|_| invalid("a valid owner"))?,
+        None => Pubkey::default(),
+    };
+    Ok(Some(AccountInfo {
+        lamports: value.get("lamports").and_then(|l| l.as_u64()).unwrap_or(0),
+        owner,
+        data,
+        executable: value.get("executable").and_then(|e| e.as_bool()).unwrap_or(false),
+    }))
+}
+
 // HTTP client for initial data fetching and token metadata
+#[derive(Clone)]
 pub struct SolanaHttpClient {
     client: reqwest::Client,
     rpc_url: String,
+    /// Retries of a request after a transient failure, counted like
+    /// `ReconnectPolicy::max_attempts`.
+    retry_attempts: u32,
+    timeout: Duration,
+    // JSON-RPC request ids, shared by clones
+    next_id: Arc<AtomicU64>,
+    // Commitment and encoding of account reads
+    options: AccountOptions,
+}
+
+impl From<&ConnectionConfig> for SolanaHttpClient {
+    fn from(config: &ConnectionConfig) -> Self {
+        Self {
+            retry_attempts: config.retry_attempts,
+            timeout: Duration::from_secs(config.timeout_seconds),
+            options: AccountOptions::from(config),
+            ..Self::new(config.rpc_endpoint.clone())
+        }
+    }
 }
 
 impl SolanaHttpClient {
@@ -215,42 +1269,148 @@ impl SolanaHttpClient {
         Self {
             client: reqwest::Client::new(),
             rpc_url,
+            retry_attempts: 3,
+            timeout: Duration::from_secs(30),
+            next_id: Arc::new(AtomicU64::new(1)),
+            options: AccountOptions::default(),
         }
     }
 
-    pub async fn get_account_data(&self, pubkey: &Pubkey) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    /// A client sharing this one's connection pool whose account reads use `options`, for
+    /// requests that need other than the configured commitment or encoding.
+    pub fn with_account_options(&self, options: AccountOptions) -> Self {
+        Self {
+            options,
+            ..self.clone()
+        }
+    }
+
+    /// Fetches any number of accounts, in `pubkeys` order. Keys are split into requests of
+    /// up to 100 that run a few at a time; each is retried on transient failures.
+    pub async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<FetchedAccount>, RpcError> {
+        self.get_multiple_accounts_since(pubkeys, None).await
+    }
+
+    /// Like `get_multiple_accounts`, but the node must have reached `min_context_slot`.
+    /// Nodes that lag behind answer with a transient error, so the request is retried.
+    pub async fn get_multiple_accounts_since(
+        &self,
+        pubkeys: &[Pubkey],
+        min_context_slot: Option<u64>,
+    ) -> Result<Vec<FetchedAccount>, RpcError> {
+        // Futures are built up front; `buffered` keeps at most a few running and yields in order
+        let requests: Vec<_> = pubkeys
+            .chunks(MAX_ACCOUNTS_PER_REQUEST)
+            .map(|chunk| self.get_multiple_accounts_chunk(chunk, min_context_slot))
+            .collect();
+        let chunks = futures_util::stream::iter(requests)
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut accounts = Vec::with_capacity(pubkeys.len());
+        for chunk in chunks {
+            accounts.extend(chunk?);
+        }
+        Ok(accounts)
+    }
+
+    async fn get_multiple_accounts_chunk(
+        &self,
+        pubkeys: &[Pubkey],
+        min_context_slot: Option<u64>,
+    ) -> Result<Vec<FetchedAccount>, RpcError> {
+        let keys: Vec<String> = pubkeys.iter().map(|pk| pk.to_string()).collect();
+        let mut config = self.options.rpc_config();
+        if let Some(slot) = min_context_slot {
+            config["minContextSlot"] = json!(slot);
+        }
+        let result = self.call("getMultipleAccounts", json!([keys, config])).await?;
+        let slot = result
+            .get("context")
+            .and_then(|c| c.get("slot"))
+            .and_then(|s| s.as_u64())
+            .unwrap_or(0);
+        let values = result
+            .get("value")
+            .and_then(|v| v.as_array())
+            .filter(|values| values.len() == pubkeys.len())
+            .ok_or_else(|| RpcError::InvalidResponse("expected one value per account".to_string()))?;
+
+        pubkeys
+            .iter()
+            .zip(values)
+            .map(|(pubkey, value)| {
+                Ok(FetchedAccount {
+                    pubkey: *pubkey,
+                    slot,
+                    account: parse_account_info(value)?,
+                })
+            })
+            .collect()
+    }
+
+    // Sends `method` under a fresh request id and returns its `result`
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
         let request = json!({
             "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getAccountInfo",
-            "params": [
-                pubkey.to_string(),
-                {
-                    "encoding": "base64",
-                    "commitment": "confirmed"
-                }
-            ]
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params
         });
+        self.send_with_retries(&request).await
+    }
 
+    // Posts a JSON-RPC request until it succeeds, fails permanently or runs out of retries,
+    // and returns its `result`
+    async fn send_with_retries(&self, request: &Value) -> Result<Value, RpcError> {
+        let mut retries = 0;
+        loop {
+            match self.send_once(request).await {
+                Err(e) if e.is_transient() && retries < self.retry_attempts => {
+                    retries += 1;
+                    let delay = jittered_backoff(HTTP_RETRY_BASE_DELAY, HTTP_RETRY_MAX_DELAY, retries, random_jitter());
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send_once(&self, request: &Value) -> Result<Value, RpcError> {
         let response = self.client
             .post(&self.rpc_url)
-            .json(&request)
+            .timeout(self.timeout)
+            .json(request)
             .send()
             .await?;
+        if !response.status().is_success() {
+            return Err(RpcError::Status(response.status()));
+        }
 
-        let json: Value = response.json().await?;
-        
-        if let Some(result) = json.get("result") {
-            if let Some(value) = result.get("value") {
-                if let Some(data_array) = value.get("data").and_then(|d| d.as_array()) {
-                    if let Some(data_str) = data_array.get(0).and_then(|s| s.as_str()) {
-                        return Ok(base64::decode(data_str)?);
-                    }
-                }
-            }
+        let mut json: Value = response.json().await?;
+
+        if let Some(error) = json.get("error") {
+            return Err(RpcError::Rpc {
+                code: error.get("code").and_then(|c| c.as_i64()).unwrap_or(0),
+                message: error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error").to_string(),
+            });
+        }
+        match json.get_mut("result") {
+            Some(result) => Ok(result.take()),
+            None => Err(RpcError::InvalidResponse("response without result".to_string())),
         }
+    }
 
-        Err("Failed to get account data".into())
+    pub async fn get_account_data(&self, pubkey: &Pubkey) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let result = self
+            .call("getAccountInfo", json!([pubkey.to_string(), self.options.rpc_config()]))
+            .await?;
+        let value = result.get("value").ok_or("Failed to get account data")?;
+        match parse_account_info(value)? {
+            Some(account) => Ok(account.data),
+            None => Err(format!("Account {} not found", pubkey).into()),
+        }
     }
 
     pub async fn get_whirlpool(&self, pubkey: &Pubkey) -> Result<Whirlpool, Box<dyn std::error::Error + Send + Sync>> {
@@ -258,6 +1418,386 @@ impl SolanaHttpClient {
         Ok(Whirlpool::try_deserialize(&data)?)
     }
 
+    /// Data of several accounts, in `pubkeys` order. Missing accounts are `None`.
+    pub async fn get_multiple_accounts_data(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<Option<Vec<u8>>>, Box<dyn std::error::Error + Send + Sync>> {
+        let accounts = self.get_multiple_accounts(pubkeys).await?;
+        Ok(accounts
+            .into_iter()
+            .map(|fetched| fetched.account.map(|account| account.data))
+            .collect())
+    }
+
+    /// Decimals of each mint in `mints` that exists, read from its mint account.
+    pub async fn get_mint_decimals(
+        &self,
+        mints: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut decimals = HashMap::new();
+        for (mint, data) in mints.iter().zip(self.get_multiple_accounts_data(mints).await?) {
+            if let Some(d) = data.as_deref().and_then(spl_token::mint_decimals) {
+                decimals.insert(*mint, d);
+            }
+        }
+        Ok(decimals)
+    }
+
+    /// Every account owned by `program_id` passing `filters` (`dataSize` / `memcmp` objects),
+    /// as `(address, account data)`.
+    pub async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<Value>,
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut config = self.options.rpc_config();
+        config["filters"] = json!(filters);
+        let result = self.call("getProgramAccounts", json!([program_id.to_string(), config])).await?;
+        let values = result.as_array().ok_or("Failed to get program accounts")?;
+
+        let mut accounts = Vec::with_capacity(values.len());
+        for value in values {
+            let address = value
+                .get("pubkey")
+                .and_then(|p| p.as_str())
+                .ok_or("Program account without pubkey")?
+                .parse::<Pubkey>()?;
+            let data = value
+                .get("account")
+                .and_then(|a| a.get("data"))
+                .ok_or("Program account without data")?;
+            accounts.push((address, decode_account_bytes(data)?));
+        }
+
+        Ok(accounts)
+    }
+
+    /// Every Whirlpool trading `mint_x` against `mint_y`, whichever is token A, most liquid
+    /// first.
+    pub async fn find_whirlpools(
+        &self,
+        mint_x: &Pubkey,
+        mint_y: &Pubkey,
+    ) -> Result<Vec<(Pubkey, Whirlpool)>, Box<dyn std::error::Error + Send + Sync>> {
+        let program_id = constants::whirlpool_profram_id();
+        let mut whirlpools = Vec::new();
+        for filter in WhirlpoolFilter::pair(*mint_x, *mint_y) {
+            for (address, data) in self.get_program_accounts(&program_id, filter.rpc_filters()).await? {
+                whirlpools.push((address, Whirlpool::try_deserialize(&data)?));
+            }
+            if mint_x == mint_y {
+                break;
+            }
+        }
+        whirlpools.sort_by_key(|(_, whirlpool)| std::cmp::Reverse(whirlpool.liquidity));
+        Ok(whirlpools)
+    }
+
+    /// Fetches every token account `owner` holds under the given token program, as
+    /// `(token account address, account data)`.
+    pub async fn get_token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        token_program_id: &Pubkey,
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, Box<dyn std::error::Error + Send + Sync>> {
+        let params = json!([
+            owner.to_string(),
+            { "programId": token_program_id.to_string() },
+            self.options.rpc_config()
+        ]);
+        let result = self.call("getTokenAccountsByOwner", params).await?;
+
+        let values = result
+            .get("value")
+            .and_then(|v| v.as_array())
+            .ok_or("Failed to get token accounts")?;
+
+        let mut accounts = Vec::with_capacity(values.len());
+        for value in values {
+            let pubkey = value
+                .get("pubkey")
+                .and_then(|p| p.as_str())
+                .ok_or("Token account without a pubkey")?
+                .parse::<Pubkey>()?;
+            let data = value
+                .get("account")
+                .and_then(|a| a.get("data"))
+                .ok_or("Token account without data")?;
+            accounts.push((pubkey, decode_account_bytes(data)?));
+        }
+
+        Ok(accounts)
+    }
+
+    /// Fetches the tick arrays around `tick_current_index` that swaps in either direction
+    /// would use. Arrays that have not been initialized on-chain are skipped.
+    pub async fn get_tick_arrays(
+        &self,
+        whirlpool_pubkey: &Pubkey,
+        whirlpool: &Whirlpool,
+    ) -> Result<Vec<TickArray>, Box<dyn std::error::Error + Send + Sync>> {
+        let addresses = surrounding_tick_array_addresses(whirlpool_pubkey, whirlpool);
+        let mut tick_arrays = Vec::with_capacity(addresses.len());
+        for data in self.get_multiple_accounts_data(&addresses).await?.into_iter().flatten() {
+            tick_arrays.push(TickArray::try_deserialize(&data)?);
+        }
+
+        Ok(tick_arrays)
+    }
+
+    /// Reads a Whirlpool and the tick arrays around its price at one slot. The pool is read
+    /// first to find its tick arrays, then read again with them in a single request no older
+    /// than the first read. That repeats while the returned slots disagree or the price has
+    /// moved onto other tick arrays.
+    pub async fn get_pool_snapshot(
+        &self,
+        whirlpool_pubkey: &Pubkey,
+    ) -> Result<PoolSnapshot, Box<dyn std::error::Error + Send + Sync>> {
+        self.get_pool_snapshot_since(whirlpool_pubkey, None).await
+    }
+
+    /// Like `get_pool_snapshot`, but the snapshot is read at `min_context_slot` or later.
+    pub async fn get_pool_snapshot_since(
+        &self,
+        whirlpool_pubkey: &Pubkey,
+        min_context_slot: Option<u64>,
+    ) -> Result<PoolSnapshot, Box<dyn std::error::Error + Send + Sync>> {
+        let read_whirlpool = |fetched: &FetchedAccount| -> Result<Whirlpool, Box<dyn std::error::Error + Send + Sync>> {
+            let account = fetched
+                .account
+                .as_ref()
+                .ok_or_else(|| format!("Whirlpool {} not found", whirlpool_pubkey))?;
+            Ok(Whirlpool::try_deserialize(&account.data)?)
+        };
+
+        let first = self.get_multiple_accounts_since(&[*whirlpool_pubkey], min_context_slot).await?;
+        let mut whirlpool = read_whirlpool(&first[0])?;
+        let mut min_context_slot = first[0].slot;
+
+        for _ in 0..MAX_SNAPSHOT_ATTEMPTS {
+            let addresses = surrounding_tick_array_addresses(whirlpool_pubkey, &whirlpool);
+            let mut keys = vec![*whirlpool_pubkey];
+            keys.extend_from_slice(&addresses);
+            let fetched = self.get_multiple_accounts_since(&keys, Some(min_context_slot)).await?;
+
+            let slot = fetched[0].slot;
+            whirlpool = read_whirlpool(&fetched[0])?;
+            min_context_slot = fetched.iter().map(|f| f.slot).max().unwrap_or(slot);
+            let same_slot = fetched.iter().all(|f| f.slot == slot);
+            if !same_slot || surrounding_tick_array_addresses(whirlpool_pubkey, &whirlpool) != addresses {
+                continue;
+            }
+
+            let mut tick_arrays = Vec::with_capacity(addresses.len());
+            for account in fetched[1..].iter().filter_map(|f| f.account.as_ref()) {
+                tick_arrays.push(TickArray::try_deserialize(&account.data)?);
+            }
+            return Ok(PoolSnapshot::new(*whirlpool_pubkey, slot, whirlpool, tick_arrays));
+        }
+
+        Err(format!(
+            "No consistent snapshot of {} after {} reads",
+            whirlpool_pubkey, MAX_SNAPSHOT_ATTEMPTS
+        )
+        .into())
+    }
+
+    /// Runs `instructions` through `simulateTransaction` without signatures and returns the
+    /// state of `addresses` afterwards. The node substitutes a recent blockhash.
+    pub async fn simulate_transaction(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        addresses: &[Pubkey],
+    ) -> Result<SimulationResult, Box<dyn std::error::Error + Send + Sync>> {
+        let transaction = BASE64.encode(unsigned_transaction(instructions, payer));
+        let addresses: Vec<String> = addresses.iter().map(|pk| pk.to_string()).collect();
+        let params = json!([
+            transaction,
+            {
+                "encoding": "base64",
+                "commitment": self.options.commitment.as_str(),
+                "sigVerify": false,
+                "replaceRecentBlockhash": true,
+                "accounts": {
+                    "encoding": self.options.encoding.as_str(),
+                    "addresses": addresses
+                }
+            }
+        ]);
+        let result = self.call("simulateTransaction", params).await?;
+        let value = result.get("value").ok_or("Failed to simulate transaction")?;
+
+        let logs = value
+            .get("logs")
+            .and_then(|l| l.as_array())
+            .map(|logs| logs.iter().filter_map(|log| log.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for account in value.get("accounts").and_then(|a| a.as_array()).into_iter().flatten() {
+            accounts.push(match account.get("data") {
+                Some(data) => Some(decode_account_bytes(data)?),
+                None => None,
+            });
+        }
+
+        Ok(SimulationResult {
+            err: value.get("err").filter(|err| !err.is_null()).cloned(),
+            logs,
+            units_consumed: value.get("unitsConsumed").and_then(|u| u.as_u64()),
+            accounts,
+        })
+    }
+
+    /// Simulates a `swapV2` against `whirlpool` and reads the amounts actually moved from the
+    /// trader's token accounts, which must already exist. Balances are read just before the
+    /// simulation, so a transfer landing in between skews the result.
+    pub async fn simulate_whirlpool_swap(
+        &self,
+        whirlpool_pubkey: &Pubkey,
+        whirlpool: &Whirlpool,
+        accounts: SwapAccounts,
+        args: &SwapInstructionArgs,
+    ) -> Result<SimulatedSwap, Box<dyn std::error::Error + Send + Sync>> {
+        let instruction = SwapInstructionBuilder::new(whirlpool, *whirlpool_pubkey, accounts).swap_v2(args);
+        let token_accounts = [accounts.token_owner_account_a, accounts.token_owner_account_b];
+
+        let balance = |data: Option<&Vec<u8>>| {
+            data.and_then(|data| spl_token::token_account_amount(data))
+                .ok_or("Token account missing from simulation")
+        };
+
+        let before = self.get_multiple_accounts_data(&token_accounts).await?;
+        let simulation = self
+            .simulate_transaction(&[instruction], &accounts.token_authority, &token_accounts)
+            .await?;
+        if let Some(err) = simulation.err {
+            let last_log = simulation.logs.last().map(String::as_str).unwrap_or("");
+            return Err(format!("Swap simulation failed: {} {}", err, last_log).into());
+        }
+
+        let (before_a, before_b) = (
+            balance(before.first().and_then(Option::as_ref))?,
+            balance(before.get(1).and_then(Option::as_ref))?,
+        );
+        let (after_a, after_b) = (
+            balance(simulation.accounts.first().and_then(Option::as_ref))?,
+            balance(simulation.accounts.get(1).and_then(Option::as_ref))?,
+        );
+        let (amount_in, amount_out) = if args.a_to_b {
+            (before_a.saturating_sub(after_a), after_b.saturating_sub(before_b))
+        } else {
+            (before_b.saturating_sub(after_b), after_a.saturating_sub(before_a))
+        };
+
+        Ok(SimulatedSwap {
+            a_to_b: args.a_to_b,
+            amount_in,
+            amount_out,
+            units_consumed: simulation.units_consumed,
+            logs: simulation.logs,
+        })
+    }
+
+    /// Latest blockhash and the last block height at which transactions using it can land.
+    pub async fn get_latest_blockhash(&self) -> Result<(Hash, u64), Box<dyn std::error::Error + Send + Sync>> {
+        let result = self
+            .call("getLatestBlockhash", json!([{ "commitment": self.options.commitment.as_str() }]))
+            .await?;
+        let value = result.get("value").ok_or("Failed to get latest blockhash")?;
+        let blockhash = value
+            .get("blockhash")
+            .and_then(|b| b.as_str())
+            .ok_or("Response without a blockhash")?
+            .parse::<Hash>()?;
+        let last_valid_block_height = value
+            .get("lastValidBlockHeight")
+            .and_then(|h| h.as_u64())
+            .ok_or("Response without lastValidBlockHeight")?;
+
+        Ok((blockhash, last_valid_block_height))
+    }
+
+    pub async fn get_block_height(&self) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let result = self
+            .call("getBlockHeight", json!([{ "commitment": self.options.commitment.as_str() }]))
+            .await?;
+        result.as_u64().ok_or_else(|| "Failed to get block height".into())
+    }
+
+    /// Sends a signed, wire-encoded transaction and returns its signature. Preflight runs at
+    /// the client's commitment so a failing swap is rejected before it costs fees; the node
+    /// then rebroadcasts it until it lands or its blockhash expires.
+    pub async fn send_transaction(&self, transaction: &[u8]) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let params = json!([
+            BASE64.encode(transaction),
+            {
+                "encoding": "base64",
+                "preflightCommitment": self.options.commitment.as_str()
+            }
+        ]);
+        let result = self.call("sendTransaction", params).await?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Failed to send transaction".into())
+    }
+
+    /// Statuses of sent transactions, in `signatures` order.
+    pub async fn get_signature_statuses(
+        &self,
+        signatures: &[String],
+    ) -> Result<Vec<TransactionStatus>, Box<dyn std::error::Error + Send + Sync>> {
+        let result = self.call("getSignatureStatuses", json!([signatures])).await?;
+
+        let values = result
+            .get("value")
+            .and_then(|v| v.as_array())
+            .ok_or("Failed to get signature statuses")?;
+
+        Ok(values
+            .iter()
+            .map(|value| {
+                if value.is_null() {
+                    return TransactionStatus::Pending;
+                }
+                if let Some(err) = value.get("err").filter(|err| !err.is_null()) {
+                    return TransactionStatus::Failed(err.to_string());
+                }
+                match value.get("confirmationStatus").and_then(|c| c.as_str()) {
+                    Some("finalized") => TransactionStatus::Finalized,
+                    Some("confirmed") => TransactionStatus::Confirmed,
+                    _ => TransactionStatus::Processed,
+                }
+            })
+            .collect())
+    }
+
+    /// Prioritization fees paid in recent slots (up to 150) by transactions that write-lock
+    /// any of `accounts`, oldest first. At most 128 accounts can be given.
+    pub async fn get_recent_prioritization_fees(
+        &self,
+        accounts: &[Pubkey],
+    ) -> Result<Vec<PrioritizationFee>, Box<dyn std::error::Error + Send + Sync>> {
+        let keys: Vec<String> = accounts.iter().map(|pk| pk.to_string()).collect();
+        let result = self.call("getRecentPrioritizationFees", json!([keys])).await?;
+        let values = result.as_array().ok_or("Failed to get recent prioritization fees")?;
+
+        let mut fees: Vec<PrioritizationFee> = values
+            .iter()
+            .filter_map(|value| {
+                Some(PrioritizationFee {
+                    slot: value.get("slot")?.as_u64()?,
+                    micro_lamports: value.get("prioritizationFee")?.as_u64()?,
+                })
+            })
+            .collect();
+        fees.sort_by_key(|fee| fee.slot);
+        Ok(fees)
+    }
+
     pub async fn get_token_metadata(&self, mint: &Pubkey) -> Result<TokenInfo, Box<dyn std::error::Error + Send + Sync>> {
         // This would typically fetch from a token registry or mint account
         // For now, return defaults with common token info
@@ -295,4 +1835,534 @@ futures-util = "0.3"
 serde_json = "1.0"
 reqwest = { version = "0.11", features = ["json"] }
 base64 = "0.21"
-*/
\ No newline at end of file
+*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let policy = ReconnectPolicy {
+            auto_reconnect: true,
+            max_attempts: 10,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(4),
+        };
+
+        assert_eq!(policy.backoff_delay(1, 0.0), Duration::from_millis(250));
+        assert_eq!(policy.backoff_delay(1, 1.0), Duration::from_millis(500));
+        assert_eq!(policy.backoff_delay(3, 1.0), Duration::from_secs(2));
+        assert_eq!(policy.backoff_delay(10, 1.0), Duration::from_secs(4));
+        assert_eq!(policy.backoff_delay(u32::MAX, 0.0), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_subscription_ids_come_from_server() {
+        let pubkey = Pubkey::new_unique();
+        let mut tracker = SubscriptionTracker::default();
+
+        let request = tracker.subscribe_request(pubkey, AccountOptions::default()).unwrap();
+        let id = request["id"].as_u64().unwrap();
+        assert!(tracker.subscribe_request(pubkey, AccountOptions::default()).is_none());
+
+        let outcome = tracker.handle_response(id, Some(&json!(4242)), None);
+        assert_eq!(outcome, ResponseOutcome::Subscribed(pubkey));
+        assert_eq!(tracker.pubkey_for_subscription(4242), Some(pubkey));
+        assert_eq!(tracker.pubkey_for_subscription(id), None);
+
+        let unsubscribe = tracker.unsubscribe_request(pubkey).unwrap();
+        assert_eq!(unsubscribe["params"][0], json!(4242));
+        assert_eq!(tracker.pubkey_for_subscription(4242), None);
+    }
+
+    #[test]
+    fn test_subscribe_failure_and_cancel_in_flight() {
+        let failing = Pubkey::new_unique();
+        let cancelled = Pubkey::new_unique();
+        let mut tracker = SubscriptionTracker::default();
+
+        let failing_id = tracker.subscribe_request(failing, AccountOptions::default()).unwrap()["id"].as_u64().unwrap();
+        let error = json!({ "code": -32602, "message": "Invalid param" });
+        assert_eq!(
+            tracker.handle_response(failing_id, None, Some(&error)),
+            ResponseOutcome::SubscribeFailed { pubkey: failing, error: "Invalid param".to_string() }
+        );
+
+        // Unsubscribing before the server answers closes the subscription once it is confirmed
+        let cancelled_id = tracker.subscribe_request(cancelled, AccountOptions::default()).unwrap()["id"].as_u64().unwrap();
+        assert!(tracker.unsubscribe_request(cancelled).is_none());
+        match tracker.handle_response(cancelled_id, Some(&json!(7)), None) {
+            ResponseOutcome::CancelSubscription(request) => assert_eq!(request["params"][0], json!(7)),
+            other => panic!("unexpected outcome {:?}", other),
+        }
+        assert_eq!(tracker.pubkey_for_subscription(7), None);
+    }
+
+    #[test]
+    fn test_pool_cache_tracks_companions() {
+        use crate::dex::raydium_clmm::state::{AmmConfig, PoolState};
+
+        let pool = Pubkey::new_unique();
+        let amm_config = Pubkey::new_unique();
+        let mut pool_data = vec![0u8; PoolState::LEN];
+        pool_data[9..41].copy_from_slice(amm_config.as_ref());
+
+        let options = AccountOptions {
+            commitment: Commitment::Processed,
+            ..AccountOptions::default()
+        };
+        let mut cache = PoolCache::default();
+        cache.add_pool(pool, DexKind::RaydiumClmm, options);
+
+        let (added, released) = cache.update_pool(pool, pool_data.clone());
+        assert_eq!((added, released), (vec![amm_config], vec![]));
+        // The config is streamed at the pool's commitment
+        assert_eq!(cache.account_options(&amm_config), options);
+        assert!(cache.decode(&pool).is_none());
+        assert_eq!(cache.subscribed_accounts().len(), 2);
+
+        assert_eq!(cache.update_companion(amm_config, vec![0u8; AmmConfig::LEN]), vec![pool]);
+        assert_eq!(cache.decode(&pool).unwrap().0, DexKind::RaydiumClmm);
+
+        // A second update with the same config tracks nothing new
+        assert_eq!(cache.update_pool(pool, pool_data), (vec![], vec![]));
+
+        assert_eq!(cache.remove_pool(&pool), vec![amm_config]);
+        assert!(cache.subscribed_accounts().is_empty());
+    }
+
+    fn whirlpool_data(mint_a: &Pubkey, mint_b: &Pubkey) -> Vec<u8> {
+        let mut data = vec![0u8; Whirlpool::LEN];
+        data[Whirlpool::TOKEN_MINT_A_OFFSET..Whirlpool::TOKEN_MINT_A_OFFSET + 32].copy_from_slice(mint_a.as_ref());
+        data[Whirlpool::TOKEN_MINT_B_OFFSET..Whirlpool::TOKEN_MINT_B_OFFSET + 32].copy_from_slice(mint_b.as_ref());
+        data
+    }
+
+    #[test]
+    fn test_whirlpool_program_subscription() {
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let [filter, reversed] = WhirlpoolFilter::pair(sol, usdc);
+        let data = whirlpool_data(&sol, &usdc);
+        assert!(filter.matches(&data) && !reversed.matches(&data));
+        assert!(WhirlpoolFilter::default().matches(&data));
+        assert!(!filter.matches(&data[..Whirlpool::LEN - 1]));
+
+        let mut tracker = SubscriptionTracker::default();
+        let options = AccountOptions {
+            commitment: Commitment::Processed,
+            encoding: AccountEncoding::Base64Zstd,
+        };
+        let request = tracker.whirlpools_subscribe_request(filter, options).unwrap();
+        assert_eq!(request["method"], json!("programSubscribe"));
+        assert_eq!(request["params"][0], json!(constants::WHIRLPOOL_PROGRAM_ID));
+        assert_eq!(request["params"][1]["commitment"], json!("processed"));
+        assert_eq!(request["params"][1]["encoding"], json!("base64+zstd"));
+        let filters = &request["params"][1]["filters"];
+        assert_eq!(filters[0], json!({ "dataSize": Whirlpool::LEN }));
+        assert_eq!(filters[1]["memcmp"], json!({ "offset": 101, "bytes": sol.to_string() }));
+        assert_eq!(filters[2]["memcmp"], json!({ "offset": 181, "bytes": usdc.to_string() }));
+
+        let id = request["id"].as_u64().unwrap();
+        assert_eq!(tracker.handle_response(id, Some(&json!(99)), None), ResponseOutcome::WhirlpoolsSubscribed(filter));
+        assert_eq!(tracker.pubkey_for_subscription(99), None);
+
+        let pool = Pubkey::new_unique();
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "programNotification",
+            "params": {
+                "subscription": 99,
+                "result": {
+                    "context": { "slot": 5_150 },
+                    "value": {
+                        "pubkey": pool.to_string(),
+                        "account": { "data": [BASE64.encode(&data), "base64"] }
+                    }
+                }
+            }
+        });
+        match SolanaWebSocketClient::handle_websocket_message(&notification.to_string(), &mut tracker).unwrap() {
+            Incoming::Whirlpool { filter: f, pubkey, slot, data: d } => {
+                assert_eq!((f, pubkey, slot), (filter, pool, 5_150));
+                assert_eq!(d, data);
+            }
+            _ => panic!("expected a Whirlpool notification"),
+        }
+
+        let unsubscribe = tracker.whirlpools_unsubscribe_request(filter).unwrap();
+        assert_eq!(unsubscribe["method"], json!("programUnsubscribe"));
+        assert_eq!(unsubscribe["params"][0], json!(99));
+    }
+
+    #[test]
+    fn test_pool_cache_discovers_whirlpools() {
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let [filter, reversed] = WhirlpoolFilter::pair(sol, usdc);
+        let (configured, discovered, discovered_reversed) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        let mut cache = PoolCache::default();
+        cache.add_pool(configured, DexKind::Whirlpool, AccountOptions::default());
+        cache.whirlpool_filters.extend([filter, reversed].map(|f| (f, AccountOptions::default())));
+
+        assert!(!cache.discover_whirlpool(configured, whirlpool_data(&sol, &usdc)));
+        assert!(cache.discover_whirlpool(discovered, whirlpool_data(&sol, &usdc)));
+        assert!(cache.discover_whirlpool(discovered_reversed, whirlpool_data(&usdc, &sol)));
+        assert!(cache.decode(&discovered).is_some());
+
+        // Discovered pools are streamed by the filter, not by their own subscription
+        assert_eq!(cache.subscribed_accounts(), vec![configured]);
+
+        cache.remove_whirlpool_filter(&filter);
+        assert!(!cache.pools.contains_key(&discovered));
+        assert!(cache.pools.contains_key(&discovered_reversed));
+        assert!(cache.pools.contains_key(&configured));
+    }
+
+    // Minimal JSON-RPC server on localhost answering each request body with `handler`
+    async fn mock_rpc(handler: fn(&Value) -> Value) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buffer = Vec::new();
+                    loop {
+                        let mut chunk = [0u8; 4096];
+                        match socket.read(&mut chunk).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                        }
+                        let Some(header_end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") else {
+                            continue;
+                        };
+                        let headers = String::from_utf8_lossy(&buffer[..header_end]).to_lowercase();
+                        let content_length: usize = headers
+                            .lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .and_then(|len| len.trim().parse().ok())
+                            .unwrap_or(0);
+                        let body_start = header_end + 4;
+                        if buffer.len() < body_start + content_length {
+                            continue;
+                        }
+
+                        let request: Value =
+                            serde_json::from_slice(&buffer[body_start..body_start + content_length]).unwrap();
+                        buffer.drain(..body_start + content_length);
+                        let body = handler(&request).to_string();
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                            body.len(),
+                            body
+                        );
+                        if socket.write_all(response.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        url
+    }
+
+    fn token_account(amount: u64) -> Value {
+        let mut data = vec![0u8; spl_token::TOKEN_ACCOUNT_LEN];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        json!({ "data": [BASE64.encode(data), "base64"] })
+    }
+
+    #[tokio::test]
+    async fn test_simulated_swap_from_balance_changes() {
+        let url = mock_rpc(|request| {
+            let result = match request["method"].as_str().unwrap() {
+                "getMultipleAccounts" => json!({ "value": [token_account(5_000), token_account(100)] }),
+                "simulateTransaction" => {
+                    let transaction = BASE64.decode(request["params"][0].as_str().unwrap()).unwrap();
+                    // One zeroed signature slot for the token authority
+                    assert_eq!(transaction[0], 1);
+                    assert!(transaction[1..65].iter().all(|&b| b == 0));
+                    assert_eq!(request["params"][1]["sigVerify"], json!(false));
+                    json!({
+                        "value": {
+                            "err": null,
+                            "logs": ["Program log: Instruction: SwapV2"],
+                            "accounts": [token_account(4_000), token_account(2_080)],
+                            "unitsConsumed": 61_234
+                        }
+                    })
+                }
+                method => panic!("unexpected method {}", method),
+            };
+            json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+        })
+        .await;
+
+        let mut whirlpool = Whirlpool::try_deserialize(&vec![0u8; Whirlpool::LEN]).unwrap();
+        whirlpool.tick_spacing = 64;
+        let accounts = SwapAccounts::new(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let args = SwapInstructionArgs {
+            amount: 1_000,
+            other_amount_threshold: 1_900,
+            sqrt_price_limit: constants::MIN_SQRT_PRICE_X64,
+            amount_specified_is_input: true,
+            a_to_b: true,
+        };
+
+        let client = SolanaHttpClient::new(url);
+        let swap = client
+            .simulate_whirlpool_swap(&Pubkey::new_unique(), &whirlpool, accounts, &args)
+            .await
+            .unwrap();
+        assert_eq!((swap.amount_in, swap.amount_out), (1_000, 1_980));
+        assert_eq!(swap.units_consumed, Some(61_234));
+        assert_eq!(swap.logs.len(), 1);
+
+        // 1.98 B per A with equal decimals, and 1% short of a 2_000 estimate
+        assert!((swap.effective_price(6, 6).unwrap() - 1.98).abs() < 1e-12);
+        assert!((swap.output_error(2_000.0) + 0.01).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_find_whirlpools_ranks_both_orderings() {
+        let url = mock_rpc(|request| {
+            assert_eq!(request["method"], json!("getProgramAccounts"));
+            let filters = &request["params"][1]["filters"];
+            assert_eq!(filters[0], json!({ "dataSize": Whirlpool::LEN }));
+
+            // One pool per ordering, with liquidity keyed off token A
+            let mint_a: Pubkey = filters[1]["memcmp"]["bytes"].as_str().unwrap().parse().unwrap();
+            let mint_b: Pubkey = filters[2]["memcmp"]["bytes"].as_str().unwrap().parse().unwrap();
+            let mut data = whirlpool_data(&mint_a, &mint_b);
+            let liquidity: u128 = if mint_a.to_bytes() < mint_b.to_bytes() { 5 } else { 9 };
+            data[49..65].copy_from_slice(&liquidity.to_le_bytes());
+            let result = json!([{
+                "pubkey": Pubkey::new_unique().to_string(),
+                "account": { "data": [BASE64.encode(&data), "base64"] }
+            }]);
+            json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+        })
+        .await;
+
+        let (x, y) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let client = SolanaHttpClient::new(url);
+        let pools = client.find_whirlpools(&x, &y).await.unwrap();
+        assert_eq!(pools.len(), 2);
+        assert_eq!((pools[0].1.liquidity, pools[1].1.liquidity), (9, 5));
+        assert_ne!(pools[0].1.token_mint_a, pools[1].1.token_mint_a);
+    }
+
+    #[tokio::test]
+    async fn test_recent_prioritization_fees() {
+        let url = mock_rpc(|request| {
+            assert_eq!(request["method"], json!("getRecentPrioritizationFees"));
+            assert_eq!(request["params"][0].as_array().unwrap().len(), 2);
+            let result = json!([
+                { "slot": 302, "prioritizationFee": 5_000 },
+                { "slot": 300, "prioritizationFee": 0 },
+                { "slot": 301, "prioritizationFee": 1_200 }
+            ]);
+            json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+        })
+        .await;
+
+        let client = SolanaHttpClient::new(url);
+        let fees = client
+            .get_recent_prioritization_fees(&[Pubkey::new_unique(), Pubkey::new_unique()])
+            .await
+            .unwrap();
+        let slots: Vec<u64> = fees.iter().map(|fee| fee.slot).collect();
+        assert_eq!(slots, vec![300, 301, 302]);
+        assert_eq!(fees[2].micro_lamports, 5_000);
+    }
+
+    #[tokio::test]
+    async fn test_get_multiple_accounts_chunks_and_retries() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static SECOND_CHUNK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let url = mock_rpc(|request| {
+            assert_eq!(request["method"], json!("getMultipleAccounts"));
+            let id = request["id"].as_u64().unwrap();
+            let keys = request["params"][0].as_array().unwrap();
+            assert!(keys.len() <= MAX_ACCOUNTS_PER_REQUEST);
+
+            // The node is briefly behind on the second chunk
+            if id == 2 && SECOND_CHUNK_CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32005, "message": "Node is behind by 12 slots" }
+                });
+            }
+            // First key of each chunk does not exist; the others hold the chunk id as balance
+            let value: Vec<Value> = (0..keys.len())
+                .map(|i| if i == 0 { Value::Null } else { token_account(id) })
+                .collect();
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": { "context": { "slot": 1_000 + id }, "value": value }
+            })
+        })
+        .await;
+
+        let pubkeys: Vec<Pubkey> = (0..250).map(|_| Pubkey::new_unique()).collect();
+        let client = SolanaHttpClient::new(url);
+        let accounts = client.get_multiple_accounts(&pubkeys).await.unwrap();
+        assert_eq!(SECOND_CHUNK_CALLS.load(Ordering::SeqCst), 2);
+
+        assert_eq!(accounts.len(), 250);
+        for (i, (fetched, pubkey)) in accounts.iter().zip(&pubkeys).enumerate() {
+            let chunk = (i / MAX_ACCOUNTS_PER_REQUEST) as u64 + 1;
+            assert_eq!(fetched.pubkey, *pubkey);
+            assert_eq!(fetched.slot, 1_000 + chunk);
+            match &fetched.account {
+                None => assert_eq!(i % MAX_ACCOUNTS_PER_REQUEST, 0),
+                Some(account) => assert_eq!(spl_token::token_account_amount(&account.data), Some(chunk)),
+            }
+        }
+
+        let data = client.get_multiple_accounts_data(&pubkeys[..2]).await.unwrap();
+        assert_eq!(data[0], None);
+        assert!(data[1].is_some());
+
+        assert!(RpcError::Rpc { code: -32005, message: String::new() }.is_transient());
+        assert!(RpcError::Status(reqwest::StatusCode::TOO_MANY_REQUESTS).is_transient());
+        assert!(!RpcError::Rpc { code: -32602, message: String::new() }.is_transient());
+    }
+
+    #[tokio::test]
+    async fn test_http_retries_count_like_reconnects() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let url = mock_rpc(|request| {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -32005, "message": "Node is behind by 12 slots" }
+            })
+        })
+        .await;
+
+        let client = SolanaHttpClient {
+            retry_attempts: 2,
+            ..SolanaHttpClient::new(url)
+        };
+        assert!(client.get_block_height().await.is_err());
+        // The first attempt and two retries, as `max_attempts: 2` allows two reconnects
+        assert_eq!(CALLS.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_pool_snapshot_refetches_after_price_moves() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        fn whirlpool_at(tick: i32) -> Value {
+            let mut data = vec![0u8; Whirlpool::LEN];
+            data[41..43].copy_from_slice(&64u16.to_le_bytes());
+            data[81..85].copy_from_slice(&tick.to_le_bytes());
+            json!({ "data": [BASE64.encode(data), "base64"] })
+        }
+
+        let url = mock_rpc(|request| {
+            let keys: Vec<Pubkey> = request["params"][0]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|key| key.as_str().unwrap().parse().unwrap())
+                .collect();
+            let min_context_slot = &request["params"][1]["minContextSlot"];
+            let (slot, value) = match CALLS.fetch_add(1, Ordering::SeqCst) {
+                // Pool alone, to locate its tick arrays
+                0 => {
+                    assert_eq!(keys.len(), 1);
+                    assert!(min_context_slot.is_null());
+                    (100, vec![whirlpool_at(0)])
+                }
+                // By the next read the price has crossed onto other tick arrays
+                1 => {
+                    assert_eq!(*min_context_slot, json!(100));
+                    let mut value = vec![whirlpool_at(100_000)];
+                    value.extend(keys[1..].iter().map(|_| Value::Null));
+                    (105, value)
+                }
+                _ => {
+                    assert_eq!(*min_context_slot, json!(105));
+                    let starts = derive_surrounding_tick_array_starts(100_000, 64);
+                    let program_id = constants::whirlpool_profram_id();
+                    let mut value = vec![whirlpool_at(100_000)];
+                    for (key, start) in keys[1..].iter().zip(starts) {
+                        assert_eq!(*key, get_tick_array_address(&keys[0], start, &program_id));
+                        let mut data = vec![0u8; TickArray::LEN];
+                        data[8..12].copy_from_slice(&start.to_le_bytes());
+                        value.push(json!({ "data": [BASE64.encode(data), "base64"] }));
+                    }
+                    (106, value)
+                }
+            };
+            json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": { "context": { "slot": slot }, "value": value }
+            })
+        })
+        .await;
+
+        let address = Pubkey::new_unique();
+        let snapshot = SolanaHttpClient::new(url).get_pool_snapshot(&address).await.unwrap();
+        assert_eq!(CALLS.load(Ordering::SeqCst), 3);
+        assert_eq!(snapshot.address(), address);
+        assert_eq!(snapshot.slot(), 106);
+        assert_eq!(snapshot.whirlpool().tick_current_index, 100_000);
+
+        let starts: Vec<i32> = snapshot.tick_arrays().iter().map(|array| array.start_tick_index).collect();
+        assert_eq!(starts, derive_surrounding_tick_array_starts(100_000, 64));
+        assert_eq!(starts[0], 95_744);
+    }
+
+    #[tokio::test]
+    async fn test_account_reads_with_zstd_encoding() {
+        let url = mock_rpc(|request| {
+            let config = &request["params"][1];
+            assert_eq!(config["commitment"], json!("finalized"));
+            assert_eq!(config["encoding"], json!("base64+zstd"));
+            let data = zstd::encode_all(&[7u8; 512][..], 0).unwrap();
+            json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "context": { "slot": 10 },
+                    "value": [{
+                        "data": [BASE64.encode(data), "base64+zstd"],
+                        "owner": constants::WHIRLPOOL_PROGRAM_ID,
+                        "lamports": 2_039_280,
+                        "executable": false
+                    }]
+                }
+            })
+        })
+        .await;
+
+        let options = AccountOptions {
+            commitment: Commitment::Finalized,
+            encoding: AccountEncoding::Base64Zstd,
+        };
+        let client = SolanaHttpClient::new(url).with_account_options(options);
+        let fetched = client.get_multiple_accounts(&[Pubkey::new_unique()]).await.unwrap();
+        let account = fetched[0].account.as_ref().unwrap();
+        assert_eq!(account.data, vec![7u8; 512]);
+        assert_eq!(account.owner, constants::whirlpool_profram_id());
+        assert_eq!(account.lamports, 2_039_280);
+
+        // Plain base64 is passed through, and config names match the RPC's
+        assert_eq!(decode_account_bytes(&json!([BASE64.encode([1, 2, 3]), "base64"])).unwrap(), vec![1, 2, 3]);
+        assert_eq!(serde_json::from_value::<AccountEncoding>(json!("base64+zstd")).unwrap(), AccountEncoding::Base64Zstd);
+        assert_eq!(serde_json::from_value::<Commitment>(json!("processed")).unwrap(), Commitment::Processed);
+    }
+}
\ No newline at end of file
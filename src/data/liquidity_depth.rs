@@ -0,0 +1,185 @@
+// src/data/liquidity_depth.rs
+
+use std::collections::BTreeMap;
+use crate::dex::whirlpool::constants::FEE_RATE_MUL_VALUE;
+use crate::dex::whirlpool::state::{TickArray, Whirlpool, TICK_ARRAY_SIZE};
+use crate::dex::whirlpool::tick_math::sqrt_price_from_tick_index;
+use crate::data::price_data::whirlpool_math;
+
+const Q64: f64 = 18446744073709551616.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepthLevel {
+    pub price: f64,
+    /// Token A (UI units) that moves the price from the previous level to this one.
+    pub amount: f64,
+    pub cumulative_amount: f64,
+}
+
+/// Liquidity available on each side of a Whirlpool's current price, derived from its ticks.
+#[derive(Clone, Debug, Default)]
+pub struct LiquidityDepth {
+    pub mid_price: f64,
+    pub fee_rate: f64,
+    /// Levels below the current price, nearest first.
+    pub bids: Vec<DepthLevel>,
+    /// Levels above the current price, nearest first.
+    pub asks: Vec<DepthLevel>,
+}
+
+impl LiquidityDepth {
+    /// Price at which the pool effectively sells token A, fees included.
+    pub fn best_ask(&self) -> f64 {
+        self.mid_price / (1.0 - self.fee_rate)
+    }
+
+    /// Price at which the pool effectively buys token A, fees included.
+    pub fn best_bid(&self) -> f64 {
+        self.mid_price * (1.0 - self.fee_rate)
+    }
+
+    pub fn spread(&self) -> f64 {
+        self.best_ask() - self.best_bid()
+    }
+}
+
+// Token A between two sqrt prices for a constant liquidity: L * (1/sqrt_lower - 1/sqrt_upper)
+fn amount_a_between(sqrt_price_lower: u128, sqrt_price_upper: u128, liquidity: u128) -> f64 {
+    let lower = sqrt_price_lower as f64 / Q64;
+    let upper = sqrt_price_upper as f64 / Q64;
+    liquidity as f64 * (1.0 / lower - 1.0 / upper)
+}
+
+fn push_level(levels: &mut Vec<DepthLevel>, price: f64, amount: f64) {
+    let cumulative_amount = levels.last().map_or(0.0, |l| l.cumulative_amount) + amount;
+    levels.push(DepthLevel {
+        price,
+        amount,
+        cumulative_amount,
+    });
+}
+
+/// Walks the initialized ticks of the loaded tick arrays outward from the current price,
+/// tracking active liquidity the same way a swap would as it crosses each tick.
+pub fn build_liquidity_depth(
+    whirlpool: &Whirlpool,
+    tick_arrays: &[TickArray],
+    decimals_a: u8,
+    decimals_b: u8,
+) -> LiquidityDepth {
+    let spacing = whirlpool.tick_spacing as i32;
+    let mut liquidity_net_by_tick = BTreeMap::new();
+    let mut covered_lower = whirlpool.tick_current_index;
+    let mut covered_upper = whirlpool.tick_current_index;
+
+    for tick_array in tick_arrays {
+        covered_lower = covered_lower.min(tick_array.start_tick_index);
+        covered_upper = covered_upper.max(tick_array.start_tick_index + TICK_ARRAY_SIZE as i32 * spacing);
+        for (offset, tick) in tick_array.ticks.iter().enumerate() {
+            if tick.initialized {
+                let tick_index = tick_array.start_tick_index + offset as i32 * spacing;
+                liquidity_net_by_tick.insert(tick_index, tick.liquidity_net);
+            }
+        }
+    }
+
+    let amount_scale = 10f64.powi(decimals_a as i32);
+    let mut depth = LiquidityDepth {
+        mid_price: whirlpool_math::calculate_price_from_whirlpool(whirlpool, decimals_a, decimals_b),
+        fee_rate: whirlpool.fee_rate as f64 / FEE_RATE_MUL_VALUE as f64,
+        bids: Vec::new(),
+        asks: Vec::new(),
+    };
+
+    // Asks: price moves up, liquidity_net is added when a tick is crossed
+    let mut liquidity = whirlpool.liquidity;
+    let mut sqrt_price = whirlpool.sqrt_price;
+    for (&tick_index, &liquidity_net) in liquidity_net_by_tick.range(whirlpool.tick_current_index + 1..) {
+        let next_sqrt_price = sqrt_price_from_tick_index(tick_index);
+        let amount = amount_a_between(sqrt_price, next_sqrt_price, liquidity) / amount_scale;
+        push_level(
+            &mut depth.asks,
+            whirlpool_math::tick_to_price(tick_index, decimals_a, decimals_b),
+            amount,
+        );
+        liquidity = liquidity.saturating_add_signed(liquidity_net);
+        sqrt_price = next_sqrt_price;
+    }
+    if liquidity > 0 && covered_upper > whirlpool.tick_current_index {
+        let edge_sqrt_price = sqrt_price_from_tick_index(covered_upper);
+        if edge_sqrt_price > sqrt_price {
+            push_level(
+                &mut depth.asks,
+                whirlpool_math::tick_to_price(covered_upper, decimals_a, decimals_b),
+                amount_a_between(sqrt_price, edge_sqrt_price, liquidity) / amount_scale,
+            );
+        }
+    }
+
+    // Bids: price moves down, liquidity_net is subtracted when a tick is crossed
+    let mut liquidity = whirlpool.liquidity;
+    let mut sqrt_price = whirlpool.sqrt_price;
+    for (&tick_index, &liquidity_net) in liquidity_net_by_tick.range(..=whirlpool.tick_current_index).rev() {
+        let next_sqrt_price = sqrt_price_from_tick_index(tick_index);
+        let amount = amount_a_between(next_sqrt_price, sqrt_price, liquidity) / amount_scale;
+        push_level(
+            &mut depth.bids,
+            whirlpool_math::tick_to_price(tick_index, decimals_a, decimals_b),
+            amount,
+        );
+        liquidity = liquidity.saturating_add_signed(-liquidity_net);
+        sqrt_price = next_sqrt_price;
+    }
+    if liquidity > 0 && covered_lower < whirlpool.tick_current_index {
+        let edge_sqrt_price = sqrt_price_from_tick_index(covered_lower);
+        if edge_sqrt_price < sqrt_price {
+            push_level(
+                &mut depth.bids,
+                whirlpool_math::tick_to_price(covered_lower, decimals_a, decimals_b),
+                amount_a_between(edge_sqrt_price, sqrt_price, liquidity) / amount_scale,
+            );
+        }
+    }
+
+    depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::whirlpool::state::{whirlpool_at_tick_zero, Tick};
+    use solana_program::pubkey::Pubkey;
+
+    #[test]
+    fn test_depth_from_single_range() {
+        let liquidity = 1_000_000_000u128;
+        let whirlpool = whirlpool_at_tick_zero(liquidity);
+
+        // Position from -640 to 640, both ticks inside the array starting at -5632 / 0
+        let mut lower_array = TickArray {
+            start_tick_index: -5632,
+            ticks: [Tick::default(); TICK_ARRAY_SIZE],
+            whirlpool: Pubkey::default(),
+        };
+        let mut upper_array = lower_array.clone();
+        upper_array.start_tick_index = 0;
+        lower_array.ticks[78].initialized = true;
+        lower_array.ticks[78].liquidity_net = liquidity as i128;
+        upper_array.ticks[10].initialized = true;
+        upper_array.ticks[10].liquidity_net = -(liquidity as i128);
+
+        let depth = build_liquidity_depth(&whirlpool, &[lower_array, upper_array], 6, 6);
+
+        assert_eq!(depth.asks.len(), 1);
+        assert_eq!(depth.bids.len(), 1);
+        assert!((depth.asks[0].price - 1.0001f64.powi(640)).abs() < 1e-9);
+        assert!((depth.bids[0].price - 1.0001f64.powi(-640)).abs() < 1e-9);
+
+        // L * (1 - 1/sqrt(1.0001^640)) token A on the ask side
+        let expected_ask = liquidity as f64 * (1.0 - 1.0001f64.powf(-320.0)) / 1e6;
+        assert!((depth.asks[0].amount - expected_ask).abs() / expected_ask < 1e-9);
+        assert!(depth.bids[0].amount > depth.asks[0].amount);
+
+        assert!((depth.spread() - (1.0 / 0.997 - 0.997)).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,207 @@
+// src/data/lp_analytics.rs - Impermanent loss and fee APR for concentrated liquidity ranges
+
+use crate::data::price_data::PriceHistory;
+use crate::dex::whirlpool::tick_math::sqrt_price_from_tick_index;
+use crate::dex::whirlpool::u256_math::mul_shift;
+
+const Q64: f64 = 18446744073709551616.0;
+const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+
+/// Token amounts held by `liquidity` over `[sqrt_lower, sqrt_upper]` at `sqrt_price`. Square
+/// roots are of raw prices (token B atoms per token A atom), amounts are raw.
+pub fn amounts_for_liquidity(liquidity: f64, sqrt_price: f64, sqrt_lower: f64, sqrt_upper: f64) -> (f64, f64) {
+    if sqrt_price <= sqrt_lower {
+        (liquidity * (1.0 / sqrt_lower - 1.0 / sqrt_upper), 0.0)
+    } else if sqrt_price >= sqrt_upper {
+        (0.0, liquidity * (sqrt_upper - sqrt_lower))
+    } else {
+        (
+            liquidity * (1.0 / sqrt_price - 1.0 / sqrt_upper),
+            liquidity * (sqrt_price - sqrt_lower),
+        )
+    }
+}
+
+fn raw_price(ui_price: f64, decimals_a: u8, decimals_b: u8) -> f64 {
+    ui_price * 10f64.powi(decimals_b as i32 - decimals_a as i32)
+}
+
+fn tick_sqrt_bounds(tick_lower: i32, tick_upper: i32) -> (f64, f64) {
+    (
+        sqrt_price_from_tick_index(tick_lower) as f64 / Q64,
+        sqrt_price_from_tick_index(tick_upper) as f64 / Q64,
+    )
+}
+
+// Value in raw token B of one unit of liquidity at `price`
+fn value_per_liquidity(price: f64, sqrt_lower: f64, sqrt_upper: f64) -> f64 {
+    let (a, b) = amounts_for_liquidity(1.0, price.sqrt(), sqrt_lower, sqrt_upper);
+    a * price + b
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImpermanentLoss {
+    /// Value of the position now, per unit of token B value deposited at entry.
+    pub position_value: f64,
+    /// Value of the entry amounts held outside the pool, on the same scale.
+    pub hodl_value: f64,
+    /// `position_value / hodl_value - 1`; zero or negative.
+    pub loss: f64,
+}
+
+/// Impermanent loss of a `[tick_lower, tick_upper)` position opened at `entry_price`, valued
+/// at `current_price`. Prices are UI token B per token A. Fees are not included.
+pub fn impermanent_loss(
+    tick_lower: i32,
+    tick_upper: i32,
+    entry_price: f64,
+    current_price: f64,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Option<ImpermanentLoss> {
+    if tick_lower >= tick_upper || !(entry_price > 0.0 && current_price > 0.0) {
+        return None;
+    }
+    let (sqrt_lower, sqrt_upper) = tick_sqrt_bounds(tick_lower, tick_upper);
+    let entry = raw_price(entry_price, decimals_a, decimals_b);
+    let current = raw_price(current_price, decimals_a, decimals_b);
+
+    let (entry_a, entry_b) = amounts_for_liquidity(1.0, entry.sqrt(), sqrt_lower, sqrt_upper);
+    let entry_value = entry_a * entry + entry_b;
+    if entry_value <= 0.0 {
+        return None;
+    }
+
+    let position_value = value_per_liquidity(current, sqrt_lower, sqrt_upper) / entry_value;
+    let hodl_value = (entry_a * current + entry_b) / entry_value;
+    Some(ImpermanentLoss {
+        position_value,
+        hodl_value,
+        loss: position_value / hodl_value - 1.0,
+    })
+}
+
+/// Fees earned by `liquidity` that stayed in range while the fee growth moved from `start` to
+/// `end`. Growths are the pool's `fee_growth_global_a/b`; amounts are raw `(token_a, token_b)`.
+pub fn fees_from_growth(liquidity: u128, start: (u128, u128), end: (u128, u128)) -> (u64, u64) {
+    let earned = |from: u128, to: u128| {
+        mul_shift(liquidity, to.wrapping_sub(from), 64)
+            .and_then(|fees| u64::try_from(fees).ok())
+            .unwrap_or(u64::MAX)
+    };
+    (earned(start.0, end.0), earned(start.1, end.1))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeeApr {
+    pub window_seconds: u64,
+    /// Share of the observed points where the pool's tick was inside the range.
+    pub time_in_range: f64,
+    /// Fees earned over the window per unit of token B value at the latest price.
+    pub fee_return: f64,
+    /// `fee_return` annualized, without compounding.
+    pub apr: f64,
+}
+
+/// Estimates the fee APR of a `[tick_lower, tick_upper)` position from the fee growth recorded
+/// in `history`. The growth is shared by all in-range liquidity, so the range only earns it for
+/// the share of the window the price spent inside it.
+pub fn estimate_fee_apr(
+    history: &PriceHistory,
+    tick_lower: i32,
+    tick_upper: i32,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Option<FeeApr> {
+    if tick_lower >= tick_upper {
+        return None;
+    }
+    let points: Vec<_> = history
+        .points
+        .iter()
+        .filter(|p| p.fee_growth_global.is_some())
+        .collect();
+    let (first, last) = (points.first()?, points.last()?);
+    let window_seconds = last.timestamp.checked_sub(first.timestamp).filter(|&s| s > 0)?;
+
+    let (start_a, start_b) = first.fee_growth_global?;
+    let (end_a, end_b) = last.fee_growth_global?;
+    let growth_a = end_a.wrapping_sub(start_a) as f64 / Q64;
+    let growth_b = end_b.wrapping_sub(start_b) as f64 / Q64;
+
+    let price = raw_price(last.price, decimals_a, decimals_b);
+    let (sqrt_lower, sqrt_upper) = tick_sqrt_bounds(tick_lower, tick_upper);
+    let position_value = value_per_liquidity(price, sqrt_lower, sqrt_upper);
+    if position_value <= 0.0 {
+        return None;
+    }
+
+    let in_range = points
+        .iter()
+        .filter(|p| p.tick >= tick_lower && p.tick < tick_upper)
+        .count();
+    let time_in_range = in_range as f64 / points.len() as f64;
+
+    let fee_return = (growth_a * price + growth_b) * time_in_range / position_value;
+    Some(FeeApr {
+        window_seconds,
+        time_in_range,
+        fee_return,
+        apr: fee_return * SECONDS_PER_YEAR / window_seconds as f64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::price_data::PricePoint;
+    use crate::dex::whirlpool::constants::{MAX_TICK_INDEX, MIN_TICK_INDEX};
+
+    #[test]
+    fn test_impermanent_loss() {
+        // Full range matches the constant-product formula 2 * sqrt(k) / (1 + k) - 1
+        let full = impermanent_loss(MIN_TICK_INDEX, MAX_TICK_INDEX, 100.0, 400.0, 9, 6).unwrap();
+        assert!((full.loss - (2.0 * 2.0 / 5.0 - 1.0)).abs() < 1e-6);
+        assert!((full.hodl_value - 2.5).abs() < 1e-6);
+
+        // A narrower range around the entry loses more for the same move
+        let narrow = impermanent_loss(-6_932, 6_932, 1.0, 1.5, 6, 6).unwrap();
+        let wide = impermanent_loss(-23_028, 23_028, 1.0, 1.5, 6, 6).unwrap();
+        assert!(narrow.loss < wide.loss && wide.loss < 0.0);
+
+        let unchanged = impermanent_loss(-6_932, 6_932, 1.0, 1.0, 6, 6).unwrap();
+        assert!(unchanged.loss.abs() < 1e-12);
+        assert!(impermanent_loss(10, 10, 1.0, 1.0, 6, 6).is_none());
+    }
+
+    #[test]
+    fn test_fee_apr_from_history() {
+        let liquidity = 1_000_000_000u128;
+        assert_eq!(fees_from_growth(liquidity, (0, 5), (1u128 << 60, 5)), (liquidity as u64 / 16, 0));
+
+        let mut history = PriceHistory::new(100, 60);
+        let growth_b = (Q64 * 1e-3) as u128;
+        for (i, (tick, growth)) in [(0, 0), (1_000, growth_b / 2), (0, growth_b)].into_iter().enumerate() {
+            history.add_price_point(PricePoint {
+                timestamp: 1_000 + i as u64 * 43_200,
+                price: 1.0,
+                volume: 0.0,
+                liquidity: liquidity as f64,
+                tick,
+                fee_growth_global: Some((0, growth)),
+            });
+        }
+
+        let apr = estimate_fee_apr(&history, -640, 640, 6, 6).unwrap();
+        assert_eq!(apr.window_seconds, 86_400);
+        assert!((apr.time_in_range - 2.0 / 3.0).abs() < 1e-12);
+
+        // 0.001 token B per unit of liquidity for 2/3 of a day, over the range's value per unit
+        let (sqrt_lower, sqrt_upper) = tick_sqrt_bounds(-640, 640);
+        let expected = 1e-3 * (2.0 / 3.0) / value_per_liquidity(1.0, sqrt_lower, sqrt_upper);
+        assert!((apr.fee_return - expected).abs() / expected < 1e-9);
+        assert!((apr.apr - expected * 365.0).abs() / apr.apr < 1e-9);
+
+        assert!(estimate_fee_apr(&PriceHistory::new(10, 60), -640, 640, 6, 6).is_none());
+    }
+}
@@ -0,0 +1,405 @@
+// src/data/portfolio.rs - Token balances and Whirlpool positions held by a wallet
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use solana_program::pubkey::Pubkey;
+use crate::connection::websocket::SolanaHttpClient;
+use crate::data::price_data::whirlpool_math;
+use crate::dex::whirlpool::position::{
+    find_position_ticks, position_tick_array_starts, position_token_amounts, position_value, PositionValue,
+};
+use crate::dex::whirlpool::state::{Position, TickArray, Whirlpool};
+use crate::dex::whirlpool::{constants, get_position_address, get_tick_array_address};
+use crate::utils::spl_token;
+
+// Mints valued at exactly one dollar; every other price is derived from pools against them
+const USD_STABLECOINS: [&str; 2] = [
+    "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", // USDC
+    "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", // USDT
+];
+
+#[derive(Clone, Debug)]
+pub struct TokenBalance {
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+impl TokenBalance {
+    pub fn ui_amount(&self) -> f64 {
+        self.amount as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PositionSummary {
+    pub address: Pubkey,
+    pub position: Position,
+    pub whirlpool_address: Pubkey,
+    pub whirlpool: Whirlpool,
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+    pub value: PositionValue,
+}
+
+impl PositionSummary {
+    /// Whether the pool's current tick is inside the position's range, i.e. it is earning fees.
+    pub fn in_range(&self) -> bool {
+        self.whirlpool.tick_current_index >= self.position.tick_lower_index
+            && self.whirlpool.tick_current_index < self.position.tick_upper_index
+    }
+
+    /// Price of token A in token B, in UI units.
+    pub fn price(&self) -> f64 {
+        whirlpool_math::calculate_price_from_whirlpool(&self.whirlpool, self.decimals_a, self.decimals_b)
+    }
+
+    pub fn ui_amounts(&self) -> (f64, f64) {
+        (
+            self.value.amount_a as f64 / 10f64.powi(self.decimals_a as i32),
+            self.value.amount_b as f64 / 10f64.powi(self.decimals_b as i32),
+        )
+    }
+
+    pub fn ui_fees(&self) -> (f64, f64) {
+        (
+            self.value.fee_owed_a as f64 / 10f64.powi(self.decimals_a as i32),
+            self.value.fee_owed_b as f64 / 10f64.powi(self.decimals_b as i32),
+        )
+    }
+
+    /// Liquidity value in token B.
+    pub fn value_in_b(&self) -> f64 {
+        let (a, b) = self.ui_amounts();
+        a * self.price() + b
+    }
+
+    /// Unclaimed fees in token B.
+    pub fn fees_in_b(&self) -> f64 {
+        let (a, b) = self.ui_fees();
+        a * self.price() + b
+    }
+}
+
+/// USD prices per mint, in UI units, derived from pool prices.
+#[derive(Clone, Debug, Default)]
+pub struct PriceBook {
+    pub prices: HashMap<Pubkey, f64>,
+}
+
+impl PriceBook {
+    /// Seeds the book with the stablecoins and walks `pools` until no new price can be derived.
+    /// `decimals` must hold both mints of every pool that should be priced.
+    pub fn from_whirlpools(pools: &[Whirlpool], decimals: &HashMap<Pubkey, u8>) -> Self {
+        let mut prices: HashMap<Pubkey, f64> = USD_STABLECOINS
+            .iter()
+            .filter_map(|mint| Pubkey::from_str(mint).ok())
+            .map(|mint| (mint, 1.0))
+            .collect();
+
+        loop {
+            let mut changed = false;
+            for pool in pools {
+                let (Some(&decimals_a), Some(&decimals_b)) =
+                    (decimals.get(&pool.token_mint_a), decimals.get(&pool.token_mint_b))
+                else {
+                    continue;
+                };
+                let price = whirlpool_math::calculate_price_from_whirlpool(pool, decimals_a, decimals_b);
+                if !price.is_finite() || price <= 0.0 {
+                    continue;
+                }
+
+                match (prices.get(&pool.token_mint_a).copied(), prices.get(&pool.token_mint_b).copied()) {
+                    (None, Some(price_b)) => {
+                        prices.insert(pool.token_mint_a, price * price_b);
+                        changed = true;
+                    }
+                    (Some(price_a), None) => {
+                        prices.insert(pool.token_mint_b, price_a / price);
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        Self { prices }
+    }
+
+    pub fn usd_price(&self, mint: &Pubkey) -> Option<f64> {
+        self.prices.get(mint).copied()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Portfolio {
+    pub owner: Pubkey,
+    /// Fungible balances, largest USD value first. Position NFTs are listed under `positions`.
+    pub balances: Vec<TokenBalance>,
+    pub positions: Vec<PositionSummary>,
+    pub prices: PriceBook,
+    pub fetched_at: u64,
+}
+
+impl Portfolio {
+    pub fn balance_usd(&self, balance: &TokenBalance) -> Option<f64> {
+        Some(balance.ui_amount() * self.prices.usd_price(&balance.mint)?)
+    }
+
+    /// USD value of a position's liquidity plus unclaimed fees.
+    pub fn position_usd(&self, position: &PositionSummary) -> Option<f64> {
+        let price_b = self.prices.usd_price(&position.whirlpool.token_mint_b)?;
+        Some((position.value_in_b() + position.fees_in_b()) * price_b)
+    }
+
+    /// USD total of everything that could be priced.
+    pub fn total_usd(&self) -> f64 {
+        let balances: f64 = self.balances.iter().filter_map(|b| self.balance_usd(b)).sum();
+        let positions: f64 = self.positions.iter().filter_map(|p| self.position_usd(p)).sum();
+        balances + positions
+    }
+}
+
+/// Values a position from its pool and, when loaded, its boundary ticks. Without the ticks
+/// only fees and rewards already credited to the position are counted.
+pub fn summarize_position(
+    address: Pubkey,
+    position: Position,
+    whirlpool: Whirlpool,
+    tick_arrays: &[TickArray],
+    decimals: (u8, u8),
+    now: u64,
+) -> Option<PositionSummary> {
+    let value = match find_position_ticks(&position, whirlpool.tick_spacing, tick_arrays) {
+        Some((lower, upper)) => position_value(&whirlpool, &position, lower, upper, now).ok()?,
+        None => {
+            let (amount_a, amount_b) = position_token_amounts(&whirlpool, &position).ok()?;
+            PositionValue {
+                amount_a,
+                amount_b,
+                fee_owed_a: position.fee_owed_a,
+                fee_owed_b: position.fee_owed_b,
+                reward_owed: position.reward_infos.map(|reward| reward.amount_owed),
+            }
+        }
+    };
+
+    Some(PositionSummary {
+        address,
+        position,
+        whirlpool_address: position.whirlpool,
+        whirlpool,
+        decimals_a: decimals.0,
+        decimals_b: decimals.1,
+        value,
+    })
+}
+
+// Whirlpool position NFTs are single-token, zero-decimal mints
+fn is_nft_candidate(balance: &TokenBalance) -> bool {
+    balance.amount == 1 && balance.decimals == 0
+}
+
+/// Loads `owner`'s token balances and Whirlpool positions. `known_pools` are extra pools used
+/// only for pricing, e.g. the ones the terminal is already streaming.
+pub async fn fetch_portfolio(
+    http_client: &SolanaHttpClient,
+    owner: Pubkey,
+    known_pools: &[Whirlpool],
+) -> Result<Portfolio, Box<dyn std::error::Error + Send + Sync>> {
+    let program_id = constants::whirlpool_profram_id();
+
+    // Token accounts under both token programs
+    let mut token_accounts = http_client
+        .get_token_accounts_by_owner(&owner, &spl_token::token_program_id())
+        .await?;
+    token_accounts.extend(
+        http_client
+            .get_token_accounts_by_owner(&owner, &spl_token::token_2022_program_id())
+            .await?,
+    );
+    let holdings: Vec<(Pubkey, Pubkey, u64)> = token_accounts
+        .iter()
+        .filter_map(|(address, data)| {
+            let mint = spl_token::token_account_mint(data)?;
+            let amount = spl_token::token_account_amount(data)?;
+            (amount > 0).then_some((*address, mint, amount))
+        })
+        .collect();
+
+    let held_mints: Vec<Pubkey> = holdings
+        .iter()
+        .map(|(_, mint, _)| *mint)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let mut decimals: HashMap<Pubkey, u8> = HashMap::new();
+    for (mint, data) in held_mints.iter().zip(http_client.get_multiple_accounts_data(&held_mints).await?) {
+        if let Some(d) = data.as_deref().and_then(spl_token::mint_decimals) {
+            decimals.insert(*mint, d);
+        }
+    }
+    let mut balances: Vec<TokenBalance> = holdings
+        .into_iter()
+        .filter_map(|(token_account, mint, amount)| {
+            Some(TokenBalance { token_account, mint, amount, decimals: *decimals.get(&mint)? })
+        })
+        .collect();
+
+    // Position accounts exist only for NFTs minted by the Whirlpool program
+    let candidates: Vec<Pubkey> = balances.iter().filter(|b| is_nft_candidate(b)).map(|b| b.mint).collect();
+    let position_addresses: Vec<Pubkey> = candidates
+        .iter()
+        .map(|mint| get_position_address(mint, &program_id))
+        .collect();
+    let mut positions = Vec::new();
+    for (address, data) in position_addresses
+        .iter()
+        .zip(http_client.get_multiple_accounts_data(&position_addresses).await?)
+    {
+        if let Some(position) = data.and_then(|d| Position::try_deserialize(&d).ok()) {
+            positions.push((*address, position));
+        }
+    }
+    let position_mints: HashSet<Pubkey> = positions.iter().map(|(_, p)| p.position_mint).collect();
+    balances.retain(|b| !position_mints.contains(&b.mint));
+
+    // Pools of those positions, then the tick arrays holding each position's bounds
+    let pool_addresses: Vec<Pubkey> = positions
+        .iter()
+        .map(|(_, p)| p.whirlpool)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let mut whirlpools = HashMap::new();
+    for (address, data) in pool_addresses.iter().zip(http_client.get_multiple_accounts_data(&pool_addresses).await?) {
+        if let Some(whirlpool) = data.and_then(|d| Whirlpool::try_deserialize(&d).ok()) {
+            whirlpools.insert(*address, whirlpool);
+        }
+    }
+
+    let mut tick_array_addresses = HashSet::new();
+    for (_, position) in &positions {
+        if let Some(whirlpool) = whirlpools.get(&position.whirlpool) {
+            let (lower, upper) = position_tick_array_starts(position, whirlpool.tick_spacing);
+            tick_array_addresses.insert(get_tick_array_address(&position.whirlpool, lower, &program_id));
+            tick_array_addresses.insert(get_tick_array_address(&position.whirlpool, upper, &program_id));
+        }
+    }
+    let tick_array_addresses: Vec<Pubkey> = tick_array_addresses.into_iter().collect();
+    let mut tick_arrays_by_pool: HashMap<Pubkey, Vec<TickArray>> = HashMap::new();
+    for data in http_client.get_multiple_accounts_data(&tick_array_addresses).await?.into_iter().flatten() {
+        if let Ok(tick_array) = TickArray::try_deserialize(&data) {
+            tick_arrays_by_pool.entry(tick_array.whirlpool).or_default().push(tick_array);
+        }
+    }
+
+    // Decimals for the pool mints the wallet doesn't hold itself
+    let pricing_pools: Vec<Whirlpool> = whirlpools.values().chain(known_pools).copied().collect();
+    let missing_mints: Vec<Pubkey> = pricing_pools
+        .iter()
+        .flat_map(|pool| [pool.token_mint_a, pool.token_mint_b])
+        .filter(|mint| !decimals.contains_key(mint))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    for (mint, data) in missing_mints.iter().zip(http_client.get_multiple_accounts_data(&missing_mints).await?) {
+        if let Some(d) = data.as_deref().and_then(spl_token::mint_decimals) {
+            decimals.insert(*mint, d);
+        }
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let positions = positions
+        .into_iter()
+        .filter_map(|(address, position)| {
+            let whirlpool = *whirlpools.get(&position.whirlpool)?;
+            let pool_decimals = (*decimals.get(&whirlpool.token_mint_a)?, *decimals.get(&whirlpool.token_mint_b)?);
+            let tick_arrays = tick_arrays_by_pool.get(&position.whirlpool).map_or(&[][..], |v| v.as_slice());
+            summarize_position(address, position, whirlpool, tick_arrays, pool_decimals, now)
+        })
+        .collect();
+
+    let prices = PriceBook::from_whirlpools(&pricing_pools, &decimals);
+    let usd = |balance: &TokenBalance| prices.usd_price(&balance.mint).map_or(0.0, |p| p * balance.ui_amount());
+    balances.sort_by(|a, b| usd(b).total_cmp(&usd(a)));
+
+    Ok(Portfolio {
+        owner,
+        balances,
+        positions,
+        prices,
+        fetched_at: now,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::whirlpool::state::{whirlpool_at_tick_zero, PositionRewardInfo, NUM_REWARDS};
+
+    fn whirlpool(token_mint_a: Pubkey, token_mint_b: Pubkey, sqrt_price: u128, tick_current_index: i32) -> Whirlpool {
+        Whirlpool {
+            token_mint_a,
+            token_mint_b,
+            sqrt_price,
+            tick_current_index,
+            ..whirlpool_at_tick_zero(1_000_000_000)
+        }
+    }
+
+    #[test]
+    fn test_prices_chain_through_pools() {
+        let usdc = Pubkey::from_str(USD_STABLECOINS[0]).unwrap();
+        let (sol, bonk) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let decimals = HashMap::from([(usdc, 6), (sol, 9), (bonk, 5)]);
+
+        // SOL/USDC at 144 and BONK/SOL at 1e-7 SOL; the BONK pool only prices after SOL is known
+        let sol_usdc = whirlpool_math::price_to_sqrt_price_x64(144.0, 9, 6);
+        let bonk_sol = whirlpool_math::price_to_sqrt_price_x64(1e-7, 5, 9);
+        let pools = [whirlpool(bonk, sol, bonk_sol, 0), whirlpool(sol, usdc, sol_usdc, 0)];
+        let prices = PriceBook::from_whirlpools(&pools, &decimals);
+
+        assert!((prices.usd_price(&sol).unwrap() - 144.0).abs() < 1e-6);
+        assert!((prices.usd_price(&bonk).unwrap() - 144e-7).abs() / 144e-7 < 1e-6);
+        assert_eq!(prices.usd_price(&Pubkey::new_unique()), None);
+    }
+
+    #[test]
+    fn test_position_summary_without_ticks() {
+        let pool = whirlpool(Pubkey::new_unique(), Pubkey::new_unique(), 1u128 << 64, 0);
+        let position = Position {
+            whirlpool: Pubkey::new_unique(),
+            position_mint: Pubkey::new_unique(),
+            liquidity: 1_000_000_000,
+            tick_lower_index: -640,
+            tick_upper_index: 640,
+            fee_growth_checkpoint_a: 0,
+            fee_owed_a: 2_000_000,
+            fee_growth_checkpoint_b: 0,
+            fee_owed_b: 1_000_000,
+            reward_infos: [PositionRewardInfo::default(); NUM_REWARDS],
+        };
+
+        let summary = summarize_position(Pubkey::new_unique(), position, pool, &[], (6, 6), 0).unwrap();
+        assert!(summary.in_range());
+        assert_eq!(summary.value.fee_owed_a, 2_000_000);
+        assert!((summary.fees_in_b() - 3.0).abs() < 1e-9);
+
+        let (a, b) = summary.ui_amounts();
+        assert!((summary.value_in_b() - (a + b)).abs() < 1e-9);
+
+        let mut out_of_range = summary.clone();
+        out_of_range.whirlpool.tick_current_index = 640;
+        assert!(!out_of_range.in_range());
+    }
+}
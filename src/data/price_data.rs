@@ -2,6 +2,7 @@
 
 use std::collections::VecDeque;
 use serde::{Deserialize, Serialize};
+use crate::dex::pool::DexPool;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PricePoint {
@@ -10,6 +11,23 @@ pub struct PricePoint {
     pub volume: f64,
     pub liquidity: f64,
     pub tick: i32,
+    /// The pool's cumulative fee growth at this point, for concentrated liquidity venues.
+    #[serde(default)]
+    pub fee_growth_global: Option<(u128, u128)>,
+}
+
+impl PricePoint {
+    /// Snapshot of a pool's mid price at `timestamp`.
+    pub fn from_pool(pool: &dyn DexPool, timestamp: u64, decimals_a: u8, decimals_b: u8) -> Self {
+        Self {
+            timestamp,
+            price: pool.mid_price(decimals_a, decimals_b),
+            volume: 0.0, // Would need to calculate from recent trades
+            liquidity: pool.liquidity(),
+            tick: pool.tick_index().unwrap_or(0),
+            fee_growth_global: pool.fee_growth_global(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -117,12 +135,14 @@ impl PriceHistory {
 
 // Price calculation utilities for Whirlpool
 pub mod whirlpool_math {
+    use crate::dex::whirlpool::constants::{MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64};
     use crate::dex::whirlpool::state::Whirlpool;
+    use crate::dex::whirlpool::tick_math::{sqrt_price_from_tick_index, tick_index_from_sqrt_price};
 
     const Q64: u128 = 1u128 << 64;
 
     pub fn sqrt_price_x64_to_price(sqrt_price_x64: u128, decimals_a: u8, decimals_b: u8) -> f64 {
-        // Convert sqrt_price from Q64.64 fixed point to f64
+        // Convert sqrt_price from Q64.64 fixed point to f64 (u128 -> f64 rounds once, exactly)
         let sqrt_price = sqrt_price_x64 as f64 / Q64 as f64;
         
         // Square to get the actual price ratio
@@ -162,10 +182,24 @@ pub mod whirlpool_math {
     }
 
     pub fn tick_to_price(tick: i32, decimals_a: u8, decimals_b: u8) -> f64 {
-        // Price = 1.0001^tick adjusted for decimals
-        let base_price = 1.0001f64.powi(tick);
-        let decimal_adjustment = 10f64.powi(decimals_a as i32 - decimals_b as i32);
-        base_price * decimal_adjustment
+        // Go through the on-chain sqrt price so tick boundaries match the program exactly
+        sqrt_price_x64_to_price(sqrt_price_from_tick_index(tick), decimals_a, decimals_b)
+    }
+
+    /// Inverse of `sqrt_price_x64_to_price`, clamped to the program's sqrt price range.
+    pub fn price_to_sqrt_price_x64(price: f64, decimals_a: u8, decimals_b: u8) -> u128 {
+        let decimal_adjustment = 10f64.powi(decimals_b as i32 - decimals_a as i32);
+        let sqrt_price = (price * decimal_adjustment).sqrt() * Q64 as f64;
+
+        if !sqrt_price.is_finite() || sqrt_price <= MIN_SQRT_PRICE_X64 as f64 {
+            return MIN_SQRT_PRICE_X64;
+        }
+        (sqrt_price as u128).min(MAX_SQRT_PRICE_X64)
+    }
+
+    /// Returns the tick whose price range contains `price`.
+    pub fn price_to_tick_index(price: f64, decimals_a: u8, decimals_b: u8) -> i32 {
+        tick_index_from_sqrt_price(price_to_sqrt_price_x64(price, decimals_a, decimals_b))
     }
 }
 
@@ -184,6 +218,7 @@ mod tests {
             volume: 1000.0,
             liquidity: 50000.0,
             tick: 0,
+            fee_growth_global: None,
         };
         
         history.add_price_point(point1);
@@ -194,8 +229,20 @@ mod tests {
     #[test]
     fn test_sqrt_price_conversion() {
         // Test with known values
-        let sqrt_price_x64 = 79228162514264337593543950336u128; // sqrt(1) in Q64.64
+        let sqrt_price_x64 = 18446744073709551616u128; // sqrt(1) in Q64.64
         let price = sqrt_price_x64_to_price(sqrt_price_x64, 6, 6);
         assert!((price - 1.0).abs() < 0.0001);
     }
+
+    #[test]
+    fn test_tick_price_round_trip() {
+        for tick in [-443636, -100_000, -1, 0, 1, 12_345, 443_636] {
+            let price = tick_to_price(tick, 9, 6);
+            assert_eq!(price_to_tick_index(price * (1.0 + 1e-12), 9, 6), tick);
+        }
+
+        // The exact conversion stays within float tolerance of the closed form
+        let exact = tick_to_price(400_000, 6, 6);
+        assert!((exact - 1.0001f64.powf(400_000.0)).abs() / exact < 1e-9);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,142 @@
+// src/data/priority_fees.rs - Priority fee suggestions from recent prioritization fees
+
+use solana_program::pubkey::Pubkey;
+use crate::connection::websocket::{PrioritizationFee, SolanaHttpClient};
+use crate::dex::whirlpool::constants::whirlpool_profram_id;
+use crate::dex::whirlpool::state::Whirlpool;
+use crate::dex::whirlpool::update_tick_array_accounts_for_onchain;
+
+/// How hard to compete for the pool's write locks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityLevel {
+    Low,
+    Medium,
+    High,
+    VeryHigh,
+}
+
+impl PriorityLevel {
+    pub const ALL: [PriorityLevel; 4] = [
+        PriorityLevel::Low,
+        PriorityLevel::Medium,
+        PriorityLevel::High,
+        PriorityLevel::VeryHigh,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PriorityLevel::Low => "Low",
+            PriorityLevel::Medium => "Medium",
+            PriorityLevel::High => "High",
+            PriorityLevel::VeryHigh => "Very high",
+        }
+    }
+
+    /// Percentile of recent slot fees this level pays.
+    pub fn percentile(self) -> f64 {
+        match self {
+            PriorityLevel::Low => 25.0,
+            PriorityLevel::Medium => 50.0,
+            PriorityLevel::High => 75.0,
+            PriorityLevel::VeryHigh => 95.0,
+        }
+    }
+}
+
+/// Fee suggestions, in micro-lamports per compute unit, from the fees that landed
+/// transactions touching a pool's accounts in recent slots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorityFeeEstimate {
+    /// Suggested fee per level, in `PriorityLevel::ALL` order.
+    pub fees: [u64; 4],
+    pub max: u64,
+    pub slots: usize,
+    /// Share of slots in which some transaction paid a non-zero fee.
+    pub contested_share: f64,
+    pub latest_slot: u64,
+}
+
+/// Nearest-rank percentile of sorted `values`.
+fn percentile(sorted: &[u64], percentile: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+impl PriorityFeeEstimate {
+    pub fn from_fees(fees: &[PrioritizationFee]) -> Option<Self> {
+        if fees.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<u64> = fees.iter().map(|fee| fee.micro_lamports).collect();
+        sorted.sort_unstable();
+
+        Some(Self {
+            fees: PriorityLevel::ALL.map(|level| percentile(&sorted, level.percentile())),
+            max: *sorted.last().expect("fees are not empty"),
+            slots: sorted.len(),
+            contested_share: sorted.iter().filter(|&&fee| fee > 0).count() as f64 / sorted.len() as f64,
+            latest_slot: fees.iter().map(|fee| fee.slot).max().unwrap_or(0),
+        })
+    }
+
+    pub fn fee(&self, level: PriorityLevel) -> u64 {
+        self.fees[level as usize]
+    }
+}
+
+/// Accounts a swap through `whirlpool` write-locks and competes for: the pool, its vaults
+/// and the tick arrays around the current price.
+pub fn whirlpool_writable_accounts(whirlpool_address: &Pubkey, whirlpool: &Whirlpool) -> Vec<Pubkey> {
+    let mut accounts = vec![*whirlpool_address, whirlpool.token_vault_a, whirlpool.token_vault_b];
+    for meta in update_tick_array_accounts_for_onchain(whirlpool, whirlpool_address, &whirlpool_profram_id()) {
+        if !accounts.contains(&meta.pubkey) {
+            accounts.push(meta.pubkey);
+        }
+    }
+    accounts
+}
+
+/// Fee suggestions for swapping through `whirlpool`. `None` when the node reports no slots.
+pub async fn estimate_whirlpool_priority_fees(
+    http: &SolanaHttpClient,
+    whirlpool_address: &Pubkey,
+    whirlpool: &Whirlpool,
+) -> Result<Option<PriorityFeeEstimate>, Box<dyn std::error::Error + Send + Sync>> {
+    let accounts = whirlpool_writable_accounts(whirlpool_address, whirlpool);
+    let fees = http.get_recent_prioritization_fees(&accounts).await?;
+    Ok(PriorityFeeEstimate::from_fees(&fees))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_suggestions() {
+        // 150 slots: 100 uncontested, then fees 1..=50 in shuffled slot order
+        let fees: Vec<PrioritizationFee> = (0..150u64)
+            .map(|slot| PrioritizationFee {
+                slot: 1_000 + slot,
+                micro_lamports: if slot < 100 { 0 } else { (slot * 7) % 50 + 1 },
+            })
+            .collect();
+
+        let estimate = PriorityFeeEstimate::from_fees(&fees).unwrap();
+        assert_eq!(estimate.slots, 150);
+        assert_eq!(estimate.latest_slot, 1_149);
+        assert_eq!(estimate.max, 50);
+        assert!((estimate.contested_share - 1.0 / 3.0).abs() < 1e-12);
+
+        // Ranks 38, 75, 113 and 143 of 150
+        assert_eq!(estimate.fee(PriorityLevel::Low), 0);
+        assert_eq!(estimate.fee(PriorityLevel::Medium), 0);
+        assert_eq!(estimate.fee(PriorityLevel::High), 13);
+        assert_eq!(estimate.fee(PriorityLevel::VeryHigh), 43);
+
+        assert!(PriorityFeeEstimate::from_fees(&[]).is_none());
+        assert_eq!(percentile(&[7], 95.0), 7);
+    }
+}
@@ -0,0 +1,31 @@
+// src/dex/bytes.rs - Little-endian field readers for venue account layouts
+//
+// Callers check the account length up front, so these index without bounds checks of their own.
+
+use solana_program::pubkey::Pubkey;
+
+pub(crate) fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&data[offset..offset+32]);
+    Pubkey::new_from_array(bytes)
+}
+
+pub(crate) fn read_u128(data: &[u8], offset: usize) -> u128 {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&data[offset..offset+16]);
+    u128::from_le_bytes(bytes)
+}
+
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset+8]);
+    u64::from_le_bytes(bytes)
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset+1], data[offset+2], data[offset+3]])
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset+1]])
+}
@@ -0,0 +1,16 @@
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const METEORA_DLMM_PROGRAM_ID: &str = "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo";
+
+pub const BIN_ARRAY_SEED: &[u8] = b"bin_array";
+
+pub const BASIS_POINT_MAX: i32 = 10_000;
+
+// Fee rates are expressed over 1e9; the program caps the total at 10%
+pub const FEE_PRECISION: u128 = 1_000_000_000;
+pub const MAX_FEE_RATE: u128 = 100_000_000;
+
+pub fn meteora_dlmm_program_id() -> Pubkey {
+    Pubkey::from_str(METEORA_DLMM_PROGRAM_ID).expect("Invalid Meteora DLMM program ID")
+}
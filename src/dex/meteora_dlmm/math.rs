@@ -0,0 +1,150 @@
+// Q64.64 bin price and fee math following the rounding of the DLMM program.
+
+use crate::dex::meteora_dlmm::constants::{BASIS_POINT_MAX, FEE_PRECISION, MAX_FEE_RATE};
+use crate::dex::meteora_dlmm::state::LbPair;
+
+const SCALE_OFFSET: u32 = 64;
+const ONE: u128 = 1u128 << SCALE_OFFSET;
+const MAX_EXPONENTIAL: u32 = 0x80000;
+
+/// `base ^ exp` in Q64.64. Works on the inverse of bases above one so every square fits in a u128.
+pub fn pow(base: u128, exp: i32) -> Option<u128> {
+    if exp == 0 {
+        return Some(ONE);
+    }
+    let mut invert = exp.is_negative();
+    let exp = exp.unsigned_abs();
+    if exp >= MAX_EXPONENTIAL {
+        return None;
+    }
+
+    let mut squared_base = base;
+    if squared_base >= ONE {
+        squared_base = u128::MAX.checked_div(squared_base)?;
+        invert = !invert;
+    }
+
+    let mut result = ONE;
+    for bit in 0..19 {
+        if exp & (1 << bit) != 0 {
+            result = result.checked_mul(squared_base)? >> SCALE_OFFSET;
+        }
+        squared_base = squared_base.checked_mul(squared_base)? >> SCALE_OFFSET;
+    }
+
+    if result == 0 {
+        return None;
+    }
+    if invert {
+        result = u128::MAX.checked_div(result)?;
+    }
+    Some(result)
+}
+
+/// Q64.64 price of bin `bin_id`: `(1 + bin_step / 10_000) ^ bin_id`.
+pub fn get_price_from_id(bin_id: i32, bin_step: u16) -> Option<u128> {
+    let bps = ((bin_step as u128) << SCALE_OFFSET) / BASIS_POINT_MAX as u128;
+    pow(ONE + bps, bin_id)
+}
+
+/// UI price of bin `bin_id`, token Y per token X.
+pub fn bin_id_to_price(bin_id: i32, bin_step: u16, decimals_x: u8, decimals_y: u8) -> f64 {
+    let ratio = (1.0 + bin_step as f64 / BASIS_POINT_MAX as f64).powi(bin_id);
+    ratio * 10f64.powi(decimals_x as i32 - decimals_y as i32)
+}
+
+/// Fee state of a pair that evolves bin by bin during a swap.
+#[derive(Clone, Copy, Debug)]
+pub struct FeeState {
+    pub bin_step: u16,
+    pub base_factor: u16,
+    pub base_fee_power_factor: u8,
+    pub variable_fee_control: u32,
+    pub max_volatility_accumulator: u32,
+    pub volatility_accumulator: u32,
+    pub volatility_reference: u32,
+    pub index_reference: i32,
+}
+
+impl FeeState {
+    /// Fee state at `now`, with the references decayed the way the program does before a swap.
+    pub fn at(lb_pair: &LbPair, now: i64) -> Self {
+        let params = &lb_pair.parameters;
+        let v_params = &lb_pair.v_parameters;
+        let mut state = Self {
+            bin_step: lb_pair.bin_step,
+            base_factor: params.base_factor,
+            base_fee_power_factor: params.base_fee_power_factor,
+            variable_fee_control: params.variable_fee_control,
+            max_volatility_accumulator: params.max_volatility_accumulator,
+            volatility_accumulator: v_params.volatility_accumulator,
+            volatility_reference: v_params.volatility_reference,
+            index_reference: v_params.index_reference,
+        };
+
+        let elapsed = now.saturating_sub(v_params.last_update_timestamp);
+        if elapsed >= params.filter_period as i64 {
+            state.index_reference = lb_pair.active_id;
+            state.volatility_reference = if elapsed < params.decay_period as i64 {
+                (v_params.volatility_accumulator as u64 * params.reduction_factor as u64
+                    / BASIS_POINT_MAX as u64) as u32
+            } else {
+                0
+            };
+        }
+        state
+    }
+
+    pub fn update_volatility_accumulator(&mut self, active_id: i32) {
+        let delta_id = (self.index_reference as i64 - active_id as i64).unsigned_abs();
+        let accumulator = self.volatility_reference as u64 + delta_id * BASIS_POINT_MAX as u64;
+        self.volatility_accumulator = accumulator.min(self.max_volatility_accumulator as u64) as u32;
+    }
+
+    pub fn base_fee(&self) -> u128 {
+        self.base_factor as u128
+            * self.bin_step as u128
+            * 10
+            * 10u128.pow(self.base_fee_power_factor as u32)
+    }
+
+    pub fn variable_fee(&self) -> u128 {
+        if self.variable_fee_control == 0 {
+            return 0;
+        }
+        let square_vfa_bin = (self.volatility_accumulator as u128 * self.bin_step as u128).pow(2);
+        (self.variable_fee_control as u128 * square_vfa_bin).div_ceil(100_000_000_000)
+    }
+
+    /// Total fee rate over `FEE_PRECISION`.
+    pub fn total_fee(&self) -> u128 {
+        (self.base_fee() + self.variable_fee()).min(MAX_FEE_RATE)
+    }
+
+    /// Fee to add on top of a fee-less amount.
+    pub fn compute_fee(&self, amount: u64) -> u64 {
+        let fee_rate = self.total_fee();
+        (amount as u128 * fee_rate).div_ceil(FEE_PRECISION - fee_rate) as u64
+    }
+
+    /// Fee contained in an amount that already includes it.
+    pub fn compute_fee_from_amount(&self, amount_with_fees: u64) -> u64 {
+        (amount_with_fees as u128 * self.total_fee()).div_ceil(FEE_PRECISION) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_from_id_matches_float() {
+        assert_eq!(get_price_from_id(0, 25), Some(ONE));
+        for (bin_id, bin_step) in [(1, 25), (-1, 25), (100, 10), (-5000, 1), (3000, 100)] {
+            let price = get_price_from_id(bin_id, bin_step).unwrap() as f64 / ONE as f64;
+            let expected = bin_id_to_price(bin_id, bin_step, 0, 0);
+            // Large exponents go through a small inverse, which keeps only ~20 significant bits
+            assert!((price - expected).abs() / expected < 1e-5, "bin {} step {}", bin_id, bin_step);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+pub mod constants;
+pub mod math;
+pub mod pool;
+pub mod state;
+pub mod swap;
+
+use crate::dex::meteora_dlmm::constants::BIN_ARRAY_SEED;
+use crate::dex::meteora_dlmm::state::MAX_BIN_PER_ARRAY;
+use solana_program::pubkey::Pubkey;
+
+/// Index of the bin array holding `bin_id`.
+pub fn bin_id_to_bin_array_index(bin_id: i32) -> i64 {
+    (bin_id as i64).div_euclid(MAX_BIN_PER_ARRAY as i64)
+}
+
+pub fn get_bin_array_address(lb_pair: &Pubkey, index: i64, program_id: &Pubkey) -> Pubkey {
+    let seeds = &[
+        BIN_ARRAY_SEED,
+        lb_pair.as_ref(),
+        &index.to_le_bytes(),
+    ];
+
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bin_array_index() {
+        assert_eq!(bin_id_to_bin_array_index(0), 0);
+        assert_eq!(bin_id_to_bin_array_index(69), 0);
+        assert_eq!(bin_id_to_bin_array_index(70), 1);
+        assert_eq!(bin_id_to_bin_array_index(-1), -1);
+        assert_eq!(bin_id_to_bin_array_index(-70), -1);
+        assert_eq!(bin_id_to_bin_array_index(-71), -2);
+    }
+}
@@ -0,0 +1,108 @@
+// src/dex/meteora_dlmm/pool.rs - DexPool implementation for Meteora DLMM
+
+use std::any::Any;
+use std::time::{SystemTime, UNIX_EPOCH};
+use solana_program::pubkey::Pubkey;
+use crate::dex::meteora_dlmm::constants::{self, FEE_PRECISION};
+use crate::dex::meteora_dlmm::math::{bin_id_to_price, FeeState};
+use crate::dex::meteora_dlmm::state::{BinArray, LbPair};
+use crate::dex::meteora_dlmm::swap::swap_quote;
+use crate::dex::meteora_dlmm::{bin_id_to_bin_array_index, get_bin_array_address};
+use crate::dex::pool::{AccountDataMap, DexError, DexKind, DexPool, DexQuote};
+
+// Each bin array covers 70 bins, so two either side reach well past most quotes
+const BIN_ARRAYS_EACH_SIDE: i64 = 2;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MeteoraDlmmPool {
+    pub lb_pair: LbPair,
+}
+
+impl MeteoraDlmmPool {
+    pub fn decode(data: &[u8]) -> Result<Self, DexError> {
+        Ok(Self {
+            lb_pair: LbPair::try_deserialize(data)?,
+        })
+    }
+
+    fn fee_state_now(&self) -> FeeState {
+        FeeState::at(&self.lb_pair, unix_now())
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+impl DexPool for MeteoraDlmmPool {
+    fn kind(&self) -> DexKind {
+        DexKind::MeteoraDlmm
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn token_mints(&self) -> (Pubkey, Pubkey) {
+        (self.lb_pair.token_x_mint, self.lb_pair.token_y_mint)
+    }
+
+    fn mid_price(&self, decimals_a: u8, decimals_b: u8) -> f64 {
+        bin_id_to_price(self.lb_pair.active_id, self.lb_pair.bin_step, decimals_a, decimals_b)
+    }
+
+    // Base fee plus the variable fee left over from recent volatility
+    fn fee_rate(&self) -> f64 {
+        self.fee_state_now().total_fee() as f64 / FEE_PRECISION as f64
+    }
+
+    // Bin reserves live in the bin arrays, not the pair account
+    fn liquidity(&self) -> f64 {
+        0.0
+    }
+
+    fn tick_index(&self) -> Option<i32> {
+        Some(self.lb_pair.active_id)
+    }
+
+    fn required_accounts(&self, pool_address: &Pubkey) -> Vec<Pubkey> {
+        let program_id = constants::meteora_dlmm_program_id();
+        let active_index = bin_id_to_bin_array_index(self.lb_pair.active_id);
+        (active_index - BIN_ARRAYS_EACH_SIDE..=active_index + BIN_ARRAYS_EACH_SIDE)
+            .map(|index| get_bin_array_address(pool_address, index, &program_id))
+            .collect()
+    }
+
+    fn quote_swap(
+        &self,
+        pool_address: &Pubkey,
+        amount: u64,
+        a_to_b: bool,
+        amount_specified_is_input: bool,
+        accounts: &AccountDataMap,
+    ) -> Result<DexQuote, DexError> {
+        let bin_arrays: Vec<BinArray> = accounts
+            .values()
+            .filter_map(|data| BinArray::try_deserialize(data).ok())
+            .filter(|bin_array| bin_array.lb_pair == *pool_address)
+            .collect();
+
+        let quote = swap_quote(
+            &self.lb_pair,
+            &bin_arrays,
+            amount,
+            a_to_b,
+            amount_specified_is_input,
+            unix_now(),
+        )?;
+
+        Ok(DexQuote {
+            amount_in: quote.amount_in,
+            amount_out: quote.amount_out,
+            fee_amount: quote.fee_amount,
+        })
+    }
+}
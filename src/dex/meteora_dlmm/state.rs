@@ -0,0 +1,192 @@
+use solana_program::pubkey::Pubkey;
+use std::io::{Error, ErrorKind, Result};
+use crate::dex::bytes::{read_pubkey, read_u128, read_u64, read_u32, read_u16};
+
+pub const MAX_BIN_PER_ARRAY: usize = 70;
+
+/// Fee and bin range settings fixed at pool creation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StaticParameters {
+    pub base_factor: u16,
+    pub filter_period: u16,
+    pub decay_period: u16,
+    pub reduction_factor: u16,
+    pub variable_fee_control: u32,
+    pub max_volatility_accumulator: u32,
+    pub min_bin_id: i32,
+    pub max_bin_id: i32,
+    pub protocol_share: u16,
+    pub base_fee_power_factor: u8,
+}
+
+/// Volatility state that drives the variable fee.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct VariableParameters {
+    pub volatility_accumulator: u32,
+    pub volatility_reference: u32,
+    pub index_reference: i32,
+    pub last_update_timestamp: i64,
+}
+
+/// The fields of Meteora's `LbPair` the terminal uses. Token X is A, token Y is B.
+#[derive(Clone, Copy, Debug)]
+pub struct LbPair {
+    pub parameters: StaticParameters,
+    pub v_parameters: VariableParameters,
+    pub pair_type: u8,
+    pub active_id: i32,
+    pub bin_step: u16,
+    pub status: u8,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub oracle: Pubkey,
+    pub bin_array_bitmap: [u64; 16],
+    pub last_updated_at: i64,
+    pub activation_point: u64,
+}
+
+impl LbPair {
+    pub const LEN: usize = 904;
+}
+
+#[derive(Clone, Debug)]
+pub struct BinArray {
+    pub index: i64,
+    pub version: u8,
+    pub lb_pair: Pubkey,
+    pub bins: [Bin; MAX_BIN_PER_ARRAY],
+}
+
+impl BinArray {
+    pub const LEN: usize = 8 + 8 + 1 + 7 + 32 + MAX_BIN_PER_ARRAY * Bin::LEN;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Bin {
+    pub amount_x: u64,
+    pub amount_y: u64,
+    /// Q64.64 price of token X in token Y, in raw units.
+    pub price: u128,
+    pub liquidity_supply: u128,
+}
+
+impl Bin {
+    pub const LEN: usize = 144;
+}
+
+// Offsets below include the 8 byte account discriminator
+impl LbPair {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for Meteora LbPair"));
+        }
+
+        let parameters = StaticParameters {
+            base_factor: read_u16(data, 8),
+            filter_period: read_u16(data, 10),
+            decay_period: read_u16(data, 12),
+            reduction_factor: read_u16(data, 14),
+            variable_fee_control: read_u32(data, 16),
+            max_volatility_accumulator: read_u32(data, 20),
+            min_bin_id: read_u32(data, 24) as i32,
+            max_bin_id: read_u32(data, 28) as i32,
+            protocol_share: read_u16(data, 32),
+            base_fee_power_factor: data[34],
+        };
+
+        let v_parameters = VariableParameters {
+            volatility_accumulator: read_u32(data, 40),
+            volatility_reference: read_u32(data, 44),
+            index_reference: read_u32(data, 48) as i32,
+            last_update_timestamp: read_u64(data, 56) as i64,
+        };
+
+        let mut bin_array_bitmap = [0u64; 16];
+        for (i, word) in bin_array_bitmap.iter_mut().enumerate() {
+            *word = read_u64(data, 584 + i * 8);
+        }
+
+        Ok(LbPair {
+            parameters,
+            v_parameters,
+            pair_type: data[75],
+            active_id: read_u32(data, 76) as i32,
+            bin_step: read_u16(data, 80),
+            status: data[82],
+            token_x_mint: read_pubkey(data, 88),
+            token_y_mint: read_pubkey(data, 120),
+            reserve_x: read_pubkey(data, 152),
+            reserve_y: read_pubkey(data, 184),
+            oracle: read_pubkey(data, 552),
+            bin_array_bitmap,
+            last_updated_at: read_u64(data, 712) as i64,
+            activation_point: read_u64(data, 816),
+        })
+    }
+}
+
+impl BinArray {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for Meteora BinArray"));
+        }
+
+        let mut bins = [Bin::default(); MAX_BIN_PER_ARRAY];
+        for (i, bin) in bins.iter_mut().enumerate() {
+            let offset = 56 + i * Bin::LEN;
+            *bin = Bin {
+                amount_x: read_u64(data, offset),
+                amount_y: read_u64(data, offset + 8),
+                price: read_u128(data, offset + 16),
+                liquidity_supply: read_u128(data, offset + 32),
+            };
+        }
+
+        Ok(BinArray {
+            index: read_u64(data, 8) as i64,
+            version: data[16],
+            lb_pair: read_pubkey(data, 24),
+            bins,
+        })
+    }
+
+    pub fn lower_bin_id(&self) -> i32 {
+        (self.index * MAX_BIN_PER_ARRAY as i64) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lb_pair_and_bin_array_deserialize() {
+        let mut data = vec![0u8; LbPair::LEN];
+        data[8..10].copy_from_slice(&10_000u16.to_le_bytes());
+        data[76..80].copy_from_slice(&(-1234i32).to_le_bytes());
+        data[80..82].copy_from_slice(&25u16.to_le_bytes());
+        let token_y = Pubkey::new_unique();
+        data[120..152].copy_from_slice(token_y.as_ref());
+
+        let lb_pair = LbPair::try_deserialize(&data).unwrap();
+        assert_eq!(lb_pair.parameters.base_factor, 10_000);
+        assert_eq!(lb_pair.active_id, -1234);
+        assert_eq!(lb_pair.bin_step, 25);
+        assert_eq!(lb_pair.token_y_mint, token_y);
+
+        let mut data = vec![0u8; BinArray::LEN];
+        data[8..16].copy_from_slice(&(-18i64).to_le_bytes());
+        let bin_offset = 56 + 4 * Bin::LEN;
+        data[bin_offset..bin_offset+8].copy_from_slice(&500u64.to_le_bytes());
+        data[bin_offset+16..bin_offset+32].copy_from_slice(&(1u128 << 64).to_le_bytes());
+
+        let bin_array = BinArray::try_deserialize(&data).unwrap();
+        assert_eq!(BinArray::LEN, 10136);
+        assert_eq!(bin_array.lower_bin_id(), -1260);
+        assert_eq!(bin_array.bins[4].amount_x, 500);
+        assert_eq!(bin_array.bins[4].price, 1u128 << 64);
+        assert!(LbPair::try_deserialize(&data[..100]).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+// Swap simulation across DLMM bins. Each bin is a constant-price pool, so a swap drains the
+// active bin and moves one bin at a time until the amount is filled.
+
+use crate::dex::meteora_dlmm::bin_id_to_bin_array_index;
+use crate::dex::meteora_dlmm::math::{get_price_from_id, FeeState};
+use crate::dex::meteora_dlmm::state::{Bin, BinArray, LbPair};
+use crate::dex::whirlpool::u256_math::mul_div;
+
+const ONE: u128 = 1u128 << 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DlmmSwapError {
+    #[error("Swap amount must be greater than zero")]
+    ZeroAmount,
+
+    #[error("Bin array {0} is not loaded")]
+    MissingBinArray(i64),
+
+    #[error("Swap ran past the pair's bin range")]
+    BinRangeExhausted,
+
+    #[error("Arithmetic overflow in swap math")]
+    MathOverflow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DlmmSwapQuote {
+    /// Input including fees.
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    pub end_active_id: i32,
+    pub bins_crossed: u32,
+}
+
+fn find_bin(bin_arrays: &[BinArray], bin_id: i32) -> Result<&Bin, DlmmSwapError> {
+    let index = bin_id_to_bin_array_index(bin_id);
+    let bin_array = bin_arrays
+        .iter()
+        .find(|ba| ba.index == index)
+        .ok_or(DlmmSwapError::MissingBinArray(index))?;
+    Ok(&bin_array.bins[(bin_id - bin_array.lower_bin_id()) as usize])
+}
+
+// Token amount in for `amount_out` of the other token at Q64.64 `price`, fees excluded
+fn amount_in_for(amount_out: u64, price: u128, swap_for_y: bool) -> Result<u64, DlmmSwapError> {
+    let amount_in = if swap_for_y {
+        mul_div(amount_out as u128, ONE, price, true)
+    } else {
+        mul_div(amount_out as u128, price, ONE, true)
+    };
+    amount_in
+        .and_then(|amount| u64::try_from(amount).ok())
+        .ok_or(DlmmSwapError::MathOverflow)
+}
+
+fn amount_out_for(amount_in: u64, price: u128, swap_for_y: bool) -> Result<u64, DlmmSwapError> {
+    let amount_out = if swap_for_y {
+        mul_div(amount_in as u128, price, ONE, false)
+    } else {
+        mul_div(amount_in as u128, ONE, price, false)
+    };
+    amount_out
+        .and_then(|amount| u64::try_from(amount).ok())
+        .ok_or(DlmmSwapError::MathOverflow)
+}
+
+/// Simulates a swap from the pair's active bin using the loaded `bin_arrays`. `a_to_b` swaps
+/// token X for token Y. `now` (unix seconds) decides how far the volatility references decay.
+pub fn swap_quote(
+    lb_pair: &LbPair,
+    bin_arrays: &[BinArray],
+    amount: u64,
+    a_to_b: bool,
+    amount_specified_is_input: bool,
+    now: i64,
+) -> Result<DlmmSwapQuote, DlmmSwapError> {
+    if amount == 0 {
+        return Err(DlmmSwapError::ZeroAmount);
+    }
+
+    let swap_for_y = a_to_b;
+    let mut fee_state = FeeState::at(lb_pair, now);
+    let mut active_id = lb_pair.active_id;
+    let mut amount_left = amount;
+    let mut amount_in = 0u64;
+    let mut amount_out = 0u64;
+    let mut fee_amount = 0u64;
+    let mut bins_crossed = 0u32;
+
+    loop {
+        fee_state.update_volatility_accumulator(active_id);
+        let bin = find_bin(bin_arrays, active_id)?;
+        let max_amount_out = if swap_for_y { bin.amount_y } else { bin.amount_x };
+
+        if max_amount_out > 0 {
+            let price = if bin.price != 0 {
+                bin.price
+            } else {
+                get_price_from_id(active_id, lb_pair.bin_step).ok_or(DlmmSwapError::MathOverflow)?
+            };
+            let max_amount_in = amount_in_for(max_amount_out, price, swap_for_y)?;
+            let max_fee = fee_state.compute_fee(max_amount_in);
+
+            let (step_in, step_out, step_fee) = if amount_specified_is_input {
+                if amount_left >= max_amount_in + max_fee {
+                    (max_amount_in + max_fee, max_amount_out, max_fee)
+                } else {
+                    let fee = fee_state.compute_fee_from_amount(amount_left);
+                    let out = amount_out_for(amount_left - fee, price, swap_for_y)?.min(max_amount_out);
+                    (amount_left, out, fee)
+                }
+            } else if amount_left >= max_amount_out {
+                (max_amount_in + max_fee, max_amount_out, max_fee)
+            } else {
+                let in_without_fee = amount_in_for(amount_left, price, swap_for_y)?;
+                let fee = fee_state.compute_fee(in_without_fee);
+                (in_without_fee + fee, amount_left, fee)
+            };
+
+            amount_in = amount_in.checked_add(step_in).ok_or(DlmmSwapError::MathOverflow)?;
+            amount_out = amount_out.checked_add(step_out).ok_or(DlmmSwapError::MathOverflow)?;
+            fee_amount += step_fee;
+            amount_left -= if amount_specified_is_input { step_in } else { step_out };
+        }
+
+        if amount_left == 0 {
+            break;
+        }
+
+        active_id = if swap_for_y { active_id - 1 } else { active_id + 1 };
+        if active_id < lb_pair.parameters.min_bin_id || active_id > lb_pair.parameters.max_bin_id {
+            return Err(DlmmSwapError::BinRangeExhausted);
+        }
+        bins_crossed += 1;
+    }
+
+    Ok(DlmmSwapQuote {
+        amount_in,
+        amount_out,
+        fee_amount,
+        end_active_id: active_id,
+        bins_crossed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::meteora_dlmm::state::MAX_BIN_PER_ARRAY;
+    use solana_program::pubkey::Pubkey;
+
+    // Pair at bin 0 with 10 bps bins, a flat 0.1% fee and no variable fee
+    fn test_pair() -> (LbPair, Vec<BinArray>) {
+        let mut lb_pair = LbPair::try_deserialize(&vec![0u8; LbPair::LEN]).unwrap();
+        lb_pair.bin_step = 10;
+        lb_pair.parameters.base_factor = 10_000;
+        lb_pair.parameters.min_bin_id = -443_636;
+        lb_pair.parameters.max_bin_id = 443_636;
+
+        // Bins -2..=-1 hold Y, bin 0 holds both, bins 1..=2 hold X
+        let mut arrays: Vec<BinArray> = [-1i64, 0]
+            .into_iter()
+            .map(|index| BinArray {
+                index,
+                version: 1,
+                lb_pair: Pubkey::default(),
+                bins: [Bin::default(); MAX_BIN_PER_ARRAY],
+            })
+            .collect();
+        arrays[0].bins[68].amount_y = 1_000_000;
+        arrays[0].bins[69].amount_y = 1_000_000;
+        arrays[1].bins[0].amount_x = 1_000_000;
+        arrays[1].bins[0].amount_y = 1_000_000;
+        arrays[1].bins[1].amount_x = 1_000_000;
+        arrays[1].bins[2].amount_x = 1_000_000;
+        (lb_pair, arrays)
+    }
+
+    #[test]
+    fn test_swap_within_active_bin() {
+        let (lb_pair, arrays) = test_pair();
+        assert_eq!(FeeState::at(&lb_pair, 0).total_fee(), 1_000_000);
+
+        let quote = swap_quote(&lb_pair, &arrays, 100_000, true, true, 0).unwrap();
+        assert_eq!(quote.fee_amount, 100);
+        // Price 1.0 in bin 0
+        assert_eq!(quote.amount_out, 99_900);
+        assert_eq!(quote.bins_crossed, 0);
+    }
+
+    #[test]
+    fn test_swap_crosses_bins() {
+        let (lb_pair, arrays) = test_pair();
+
+        let quote = swap_quote(&lb_pair, &arrays, 2_500_000, true, false, 0).unwrap();
+        assert_eq!(quote.amount_out, 2_500_000);
+        assert_eq!(quote.end_active_id, -2);
+        assert_eq!(quote.bins_crossed, 2);
+        // Lower bins price X lower, so each unit of Y costs more X: over 2.5M X (plus fees) goes in
+        assert!(quote.amount_in > 2_500_000 + quote.fee_amount);
+
+        let exact_in = swap_quote(&lb_pair, &arrays, quote.amount_in, true, true, 0).unwrap();
+        assert!(exact_in.amount_out >= quote.amount_out - 1);
+
+        let result = swap_quote(&lb_pair, &arrays, 3_500_000, true, false, 0);
+        assert_eq!(result, Err(DlmmSwapError::MissingBinArray(-2)));
+    }
+}
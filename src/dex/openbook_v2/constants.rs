@@ -0,0 +1,11 @@
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const OPENBOOK_V2_PROGRAM_ID: &str = "opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb";
+
+// Maker and taker fees are expressed over 1e6
+pub const FEES_SCALE_FACTOR: i64 = 1_000_000;
+
+pub fn openbook_v2_program_id() -> Pubkey {
+    Pubkey::from_str(OPENBOOK_V2_PROGRAM_ID).expect("Invalid OpenBook v2 program ID")
+}
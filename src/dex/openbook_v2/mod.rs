@@ -0,0 +1,3 @@
+pub mod constants;
+pub mod pool;
+pub mod state;
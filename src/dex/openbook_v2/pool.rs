@@ -0,0 +1,144 @@
+// src/dex/openbook_v2/pool.rs - DexPool implementation for OpenBook v2 markets
+
+use std::any::Any;
+use std::time::{SystemTime, UNIX_EPOCH};
+use solana_program::pubkey::Pubkey;
+use crate::dex::openbook_v2::constants::FEES_SCALE_FACTOR;
+use crate::dex::openbook_v2::state::{BookSide, Market};
+use crate::dex::order_book::OrderBook;
+use crate::dex::pool::{AccountDataMap, DexError, DexKind, DexPool, DexQuote};
+
+/// A market together with its book, which lives in the separate bids and asks accounts.
+#[derive(Clone, Debug)]
+pub struct OpenBookMarket {
+    pub market: Market,
+    pub book: OrderBook,
+}
+
+impl OpenBookMarket {
+    pub fn decode(data: &[u8], companions: &AccountDataMap) -> Result<Self, DexError> {
+        let market = Market::try_deserialize(data)?;
+        let book_side = |address: Pubkey| -> Result<BookSide, DexError> {
+            let data = companions.get(&address).ok_or(DexError::MissingAccount(address))?;
+            Ok(BookSide::try_deserialize(data)?)
+        };
+        let bids = book_side(market.bids)?;
+        let asks = book_side(market.asks)?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let quote_lot_size = market.quote_lot_size.max(0) as u64;
+        let levels = |side: BookSide, is_bid: bool| {
+            let orders = side
+                .orders
+                .into_iter()
+                .filter(|leaf| !leaf.is_expired(now) && leaf.price_lots() > 0)
+                .map(|leaf| (leaf.price_lots() as u64 * quote_lot_size, leaf.quantity.max(0) as u64));
+            OrderBook::aggregate_levels(orders, is_bid)
+        };
+
+        let book = OrderBook {
+            base_lot_size: market.base_lot_size.max(0) as u64,
+            base_decimals: market.base_decimals,
+            quote_decimals: market.quote_decimals,
+            // Negative taker fees don't exist on OpenBook v2 markets; treat one as free
+            taker_fee_ppm: market.taker_fee.max(0) as u64 * 1_000_000 / FEES_SCALE_FACTOR as u64,
+            bids: levels(bids, true),
+            asks: levels(asks, false),
+        };
+
+        Ok(Self { market, book })
+    }
+
+    pub fn companion_accounts(data: &[u8]) -> Result<Vec<Pubkey>, DexError> {
+        let market = Market::try_deserialize(data)?;
+        Ok(vec![market.bids, market.asks])
+    }
+}
+
+impl DexPool for OpenBookMarket {
+    fn kind(&self) -> DexKind {
+        DexKind::OpenBookV2
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn token_mints(&self) -> (Pubkey, Pubkey) {
+        (self.market.base_mint, self.market.quote_mint)
+    }
+
+    // The book knows its own decimals
+    fn mid_price(&self, _decimals_a: u8, _decimals_b: u8) -> f64 {
+        self.book.mid_price().unwrap_or(0.0)
+    }
+
+    fn fee_rate(&self) -> f64 {
+        self.market.taker_fee.max(0) as f64 / FEES_SCALE_FACTOR as f64
+    }
+
+    // Resting base lots on both sides
+    fn liquidity(&self) -> f64 {
+        self.book.total_base_lots() as f64
+    }
+
+    fn order_book(&self) -> Option<&OrderBook> {
+        Some(&self.book)
+    }
+
+    fn required_accounts(&self, _pool_address: &Pubkey) -> Vec<Pubkey> {
+        Vec::new()
+    }
+
+    fn quote_swap(
+        &self,
+        _pool_address: &Pubkey,
+        amount: u64,
+        a_to_b: bool,
+        amount_specified_is_input: bool,
+        _accounts: &AccountDataMap,
+    ) -> Result<DexQuote, DexError> {
+        Ok(self.book.taker_quote(amount, a_to_b, amount_specified_is_input)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::openbook_v2::state::test_utils::book_side_data;
+
+    #[test]
+    fn test_book_from_slabs() {
+        let (bids, asks) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; Market::LEN];
+        data[9] = 9;
+        data[10] = 6;
+        data[200..232].copy_from_slice(bids.as_ref());
+        data[232..264].copy_from_slice(asks.as_ref());
+        // 0.001 SOL lots priced in 10 atom quote lots; 0.04% taker fee
+        data[448..456].copy_from_slice(&10i64.to_le_bytes());
+        data[456..464].copy_from_slice(&1_000_000i64.to_le_bytes());
+        data[488..496].copy_from_slice(&400i64.to_le_bytes());
+
+        let mut companions = AccountDataMap::new();
+        assert_eq!(OpenBookMarket::companion_accounts(&data).unwrap(), vec![bids, asks]);
+        assert!(OpenBookMarket::decode(&data, &companions).is_err());
+
+        companions.insert(bids, book_side_data(&[(14_990, 5), (14_980, 7), (14_990, 1)]));
+        companions.insert(asks, book_side_data(&[(15_010, 3)]));
+        let market = OpenBookMarket::decode(&data, &companions).unwrap();
+
+        assert_eq!(market.book.bids[0].quote_per_lot, 149_900);
+        assert_eq!(market.book.bids[0].base_lots, 6);
+        assert_eq!(market.book.asks.len(), 1);
+        assert!((market.mid_price(9, 6) - 150.0).abs() < 1e-9);
+        assert_eq!(market.book.taker_fee_ppm, 400);
+
+        // Selling 0.008 SOL takes 6 lots at 149.9 and 2 at 149.8
+        let quote = market.quote_swap(&Pubkey::new_unique(), 8_000_000, true, true, &companions).unwrap();
+        assert_eq!(quote.amount_out, 1_199_000 - 480);
+    }
+}
@@ -0,0 +1,199 @@
+use solana_program::pubkey::Pubkey;
+use std::io::{Error, ErrorKind, Result};
+use crate::dex::bytes::{read_pubkey, read_u128, read_u64, read_u32, read_u16};
+
+/// The fields of OpenBook v2's `Market` the terminal uses. Base is token A, quote is token B.
+#[derive(Clone, Copy, Debug)]
+pub struct Market {
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_heap: Pubkey,
+    pub quote_lot_size: i64,
+    pub base_lot_size: i64,
+    pub maker_fee: i64,
+    pub taker_fee: i64,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub market_base_vault: Pubkey,
+    pub market_quote_vault: Pubkey,
+}
+
+impl Market {
+    pub const LEN: usize = 848;
+}
+
+pub const MAX_ORDERTREE_NODES: usize = 1024;
+
+const NODE_TAG_INNER: u8 = 1;
+const NODE_TAG_LEAF: u8 = 2;
+
+/// A resting order leaf from a `BookSide`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeafNode {
+    pub key: u128,
+    pub owner: Pubkey,
+    /// Size in base lots.
+    pub quantity: i64,
+    pub timestamp: u64,
+    pub time_in_force: u16,
+}
+
+impl LeafNode {
+    pub const LEN: usize = 88;
+
+    /// Limit price in quote lots per base lot. Only meaningful for the fixed-price tree.
+    pub fn price_lots(&self) -> i64 {
+        (self.key >> 64) as i64
+    }
+
+    /// Expired orders stay in the book until someone cranks them out.
+    pub fn is_expired(&self, now_ts: u64) -> bool {
+        self.time_in_force > 0 && now_ts >= self.timestamp + self.time_in_force as u64
+    }
+}
+
+/// One side of the book. Only the fixed-price tree is read; oracle-pegged orders need the
+/// oracle price and are skipped.
+#[derive(Clone, Debug)]
+pub struct BookSide {
+    pub fixed_leaf_count: u32,
+    pub orders: Vec<LeafNode>,
+}
+
+impl BookSide {
+    pub const LEN: usize = 8 + 8 * 2 + 8 * 4 + 256 + 528 + MAX_ORDERTREE_NODES * LeafNode::LEN;
+    const NODES_OFFSET: usize = 8 + 8 * 2 + 8 * 4 + 256 + 528;
+}
+
+// Offsets below include the 8 byte account discriminator
+impl Market {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for OpenBook Market"));
+        }
+
+        Ok(Market {
+            base_decimals: data[9],
+            quote_decimals: data[10],
+            bids: read_pubkey(data, 200),
+            asks: read_pubkey(data, 232),
+            event_heap: read_pubkey(data, 264),
+            quote_lot_size: read_u64(data, 448) as i64,
+            base_lot_size: read_u64(data, 456) as i64,
+            maker_fee: read_u64(data, 480) as i64,
+            taker_fee: read_u64(data, 488) as i64,
+            base_mint: read_pubkey(data, 576),
+            quote_mint: read_pubkey(data, 608),
+            market_base_vault: read_pubkey(data, 640),
+            market_quote_vault: read_pubkey(data, 680),
+        })
+    }
+}
+
+impl BookSide {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for OpenBook BookSide"));
+        }
+        let invalid = || Error::new(ErrorKind::InvalidData, "corrupt OpenBook order tree");
+
+        // roots[0] is the fixed-price tree: { maybe_node: u32, leaf_count: u32 }
+        let root = read_u32(data, 8);
+        let fixed_leaf_count = read_u32(data, 12);
+
+        let mut orders = Vec::with_capacity(fixed_leaf_count as usize);
+        let mut stack = if fixed_leaf_count > 0 { vec![root] } else { Vec::new() };
+        let mut visited = 0;
+        while let Some(node) = stack.pop() {
+            visited += 1;
+            if node as usize >= MAX_ORDERTREE_NODES || visited > MAX_ORDERTREE_NODES {
+                return Err(invalid());
+            }
+            let offset = Self::NODES_OFFSET + node as usize * LeafNode::LEN;
+            match data[offset] {
+                NODE_TAG_INNER => {
+                    stack.push(read_u32(data, offset + 24));
+                    stack.push(read_u32(data, offset + 28));
+                }
+                NODE_TAG_LEAF => orders.push(LeafNode {
+                    key: read_u128(data, offset + 8),
+                    owner: read_pubkey(data, offset + 24),
+                    quantity: read_u64(data, offset + 56) as i64,
+                    timestamp: read_u64(data, offset + 64),
+                    time_in_force: read_u16(data, offset + 2),
+                }),
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(BookSide {
+            fixed_leaf_count,
+            orders,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use super::*;
+
+    /// A fixed-price book side holding `(price_lots, quantity)` leaves.
+    pub fn book_side_data(leaves: &[(i64, i64)]) -> Vec<u8> {
+        let mut data = vec![0u8; BookSide::LEN];
+        let node_offset = |node: usize| BookSide::NODES_OFFSET + node * LeafNode::LEN;
+        let n = leaves.len();
+
+        for (i, &(price_lots, quantity)) in leaves.iter().enumerate() {
+            let leaf = node_offset(i);
+            data[leaf] = NODE_TAG_LEAF;
+            let key = ((price_lots as u128) << 64) | i as u128;
+            data[leaf+8..leaf+24].copy_from_slice(&key.to_le_bytes());
+            data[leaf+56..leaf+64].copy_from_slice(&quantity.to_le_bytes());
+        }
+
+        // Inner node n + i joins leaf i with the rest of the chain
+        for i in 0..n.saturating_sub(1) {
+            let inner = node_offset(n + i);
+            let right = if i + 2 == n { n - 1 } else { n + i + 1 };
+            data[inner] = NODE_TAG_INNER;
+            data[inner+24..inner+28].copy_from_slice(&(i as u32).to_le_bytes());
+            data[inner+28..inner+32].copy_from_slice(&(right as u32).to_le_bytes());
+        }
+
+        let root = if n > 1 { n } else { 0 };
+        data[8..12].copy_from_slice(&(root as u32).to_le_bytes());
+        data[12..16].copy_from_slice(&(n as u32).to_le_bytes());
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_and_book_side_deserialize() {
+        let mut data = vec![0u8; Market::LEN];
+        data[9] = 9;
+        data[10] = 6;
+        let bids = Pubkey::new_unique();
+        data[200..232].copy_from_slice(bids.as_ref());
+        data[448..456].copy_from_slice(&1i64.to_le_bytes());
+        data[456..464].copy_from_slice(&1_000_000i64.to_le_bytes());
+        data[488..496].copy_from_slice(&400i64.to_le_bytes());
+
+        let market = Market::try_deserialize(&data).unwrap();
+        assert_eq!(market.base_decimals, 9);
+        assert_eq!(market.bids, bids);
+        assert_eq!(market.base_lot_size, 1_000_000);
+        assert_eq!(market.taker_fee, 400);
+
+        assert_eq!(BookSide::LEN, 90952);
+        let book_side = BookSide::try_deserialize(&test_utils::book_side_data(&[(149_900, 5), (149_800, 7)])).unwrap();
+        assert_eq!(book_side.orders.len(), 2);
+        assert!(book_side.orders.iter().any(|o| o.price_lots() == 149_800 && o.quantity == 7));
+        assert!(BookSide::try_deserialize(&data).is_err());
+    }
+}
@@ -0,0 +1,277 @@
+// src/dex/order_book.rs - Aggregated L2 book shared by the limit order book venues
+
+use crate::dex::pool::DexQuote;
+
+// Taker fees are kept in parts per million of the quote amount
+pub const FEE_PPM_DENOMINATOR: u64 = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum OrderBookError {
+    #[error("Swap amount must be greater than zero")]
+    ZeroAmount,
+
+    #[error("Not enough resting orders to fill the swap")]
+    InsufficientLiquidity,
+
+    #[error("Token amount exceeds u64")]
+    TokenMaxExceeded,
+
+    #[error("Arithmetic overflow in fill math")]
+    MathOverflow,
+}
+
+/// Resting size at one price. Prices and sizes stay in lots so quotes are exact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BookLevel {
+    /// Quote atoms per base lot.
+    pub quote_per_lot: u64,
+    pub base_lots: u64,
+}
+
+/// Both sides of a market's book, best level first. Token A is the base, token B the quote.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrderBook {
+    /// Base atoms per lot.
+    pub base_lot_size: u64,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    pub taker_fee_ppm: u64,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+// How much of the book a fill should take
+enum FillLimit {
+    BaseLots(u64),
+    /// Spend up to this much quote, never more.
+    QuoteBudget(u64),
+    /// Take at least this much quote.
+    QuoteTarget(u64),
+}
+
+impl OrderBook {
+    /// Sums orders per price and sorts them best first. `orders` are `(quote_per_lot, base_lots)`.
+    pub fn aggregate_levels(orders: impl IntoIterator<Item = (u64, u64)>, is_bid: bool) -> Vec<BookLevel> {
+        let mut by_price = std::collections::BTreeMap::new();
+        for (quote_per_lot, base_lots) in orders {
+            if base_lots > 0 {
+                *by_price.entry(quote_per_lot).or_insert(0u64) += base_lots;
+            }
+        }
+
+        let levels = by_price
+            .into_iter()
+            .map(|(quote_per_lot, base_lots)| BookLevel { quote_per_lot, base_lots });
+        if is_bid {
+            levels.rev().collect()
+        } else {
+            levels.collect()
+        }
+    }
+
+    /// UI price (quote per base token) of a level.
+    pub fn level_price(&self, level: &BookLevel) -> f64 {
+        let decimal_adjustment = 10f64.powi(self.base_decimals as i32 - self.quote_decimals as i32);
+        level.quote_per_lot as f64 / self.base_lot_size as f64 * decimal_adjustment
+    }
+
+    /// UI size (base tokens) of a level.
+    pub fn level_size(&self, level: &BookLevel) -> f64 {
+        (level.base_lots as f64 * self.base_lot_size as f64) / 10f64.powi(self.base_decimals as i32)
+    }
+
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids.first().map(|level| self.level_price(level))
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks.first().map(|level| self.level_price(level))
+    }
+
+    /// Midpoint of the best bid and ask, or the one side that has orders.
+    pub fn mid_price(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some((bid + ask) / 2.0),
+            (bid, ask) => bid.or(ask),
+        }
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()? - self.best_bid()?)
+    }
+
+    /// `(price, size)` pairs in UI units, best first.
+    pub fn ui_levels(&self, is_bid: bool) -> Vec<(f64, f64)> {
+        let levels = if is_bid { &self.bids } else { &self.asks };
+        levels
+            .iter()
+            .map(|level| (self.level_price(level), self.level_size(level)))
+            .collect()
+    }
+
+    pub fn total_base_lots(&self) -> u64 {
+        self.bids.iter().chain(&self.asks).map(|level| level.base_lots).sum()
+    }
+
+    fn fee_on(&self, quote_amount: u64) -> u64 {
+        (quote_amount as u128 * self.taker_fee_ppm as u128).div_ceil(FEE_PPM_DENOMINATOR as u128) as u64
+    }
+
+    // Walks `levels` until `limit` is reached. Returns the lots and gross quote filled.
+    fn fill(levels: &[BookLevel], limit: FillLimit) -> Result<(u64, u64), OrderBookError> {
+        let mut lots = 0u64;
+        let mut quote = 0u64;
+
+        for level in levels {
+            let take = match limit {
+                FillLimit::BaseLots(wanted) => (wanted - lots).min(level.base_lots),
+                FillLimit::QuoteBudget(budget) => ((budget - quote) / level.quote_per_lot).min(level.base_lots),
+                FillLimit::QuoteTarget(target) => {
+                    (target - quote).div_ceil(level.quote_per_lot).min(level.base_lots)
+                }
+            };
+            lots += take;
+            quote = take
+                .checked_mul(level.quote_per_lot)
+                .and_then(|level_quote| quote.checked_add(level_quote))
+                .ok_or(OrderBookError::MathOverflow)?;
+
+            let done = match limit {
+                FillLimit::BaseLots(wanted) => lots == wanted,
+                // Stop once the next lot at this price no longer fits
+                FillLimit::QuoteBudget(budget) => take < level.base_lots || budget - quote < level.quote_per_lot,
+                FillLimit::QuoteTarget(target) => quote >= target,
+            };
+            if done {
+                return Ok((lots, quote));
+            }
+        }
+
+        match limit {
+            FillLimit::BaseLots(0) | FillLimit::QuoteTarget(0) => Ok((0, 0)),
+            _ => Err(OrderBookError::InsufficientLiquidity),
+        }
+    }
+
+    /// Quotes a market order in raw token units. `a_to_b` sells the base into the bids. Input
+    /// base amounts are rounded down to whole lots, and the fee is charged in the quote token.
+    pub fn taker_quote(
+        &self,
+        amount: u64,
+        a_to_b: bool,
+        amount_specified_is_input: bool,
+    ) -> Result<DexQuote, OrderBookError> {
+        if amount == 0 {
+            return Err(OrderBookError::ZeroAmount);
+        }
+        if self.base_lot_size == 0 {
+            return Err(OrderBookError::InsufficientLiquidity);
+        }
+        let fee_denominator = FEE_PPM_DENOMINATOR as u128;
+
+        match (a_to_b, amount_specified_is_input) {
+            // Sell exactly `amount` base
+            (true, true) => {
+                let (lots, gross) = Self::fill(&self.bids, FillLimit::BaseLots(amount / self.base_lot_size))?;
+                let fee_amount = self.fee_on(gross);
+                Ok(DexQuote {
+                    amount_in: lots * self.base_lot_size,
+                    amount_out: gross - fee_amount,
+                    fee_amount,
+                })
+            }
+            // Sell base until `amount` quote is received after fees
+            (true, false) => {
+                let target = (amount as u128 * fee_denominator)
+                    .div_ceil(fee_denominator.saturating_sub(self.taker_fee_ppm as u128).max(1));
+                let target = u64::try_from(target).map_err(|_| OrderBookError::TokenMaxExceeded)?;
+                let (lots, gross) = Self::fill(&self.bids, FillLimit::QuoteTarget(target))?;
+                let fee_amount = self.fee_on(gross);
+                Ok(DexQuote {
+                    amount_in: lots * self.base_lot_size,
+                    amount_out: gross - fee_amount,
+                    fee_amount,
+                })
+            }
+            // Spend at most `amount` quote, fees included
+            (false, true) => {
+                let budget = (amount as u128 * fee_denominator) / (fee_denominator + self.taker_fee_ppm as u128);
+                let (lots, mut gross) = Self::fill(&self.asks, FillLimit::QuoteBudget(budget as u64))?;
+                let mut fee_amount = self.fee_on(gross);
+                // Rounding the fee up can push the total a unit over the budget
+                if gross + fee_amount > amount {
+                    gross = 0;
+                    fee_amount = 0;
+                }
+                let lots = if gross == 0 { 0 } else { lots };
+                Ok(DexQuote {
+                    amount_in: gross + fee_amount,
+                    amount_out: lots * self.base_lot_size,
+                    fee_amount,
+                })
+            }
+            // Buy at least `amount` base
+            (false, false) => {
+                let (lots, gross) = Self::fill(&self.asks, FillLimit::BaseLots(amount.div_ceil(self.base_lot_size)))?;
+                let fee_amount = self.fee_on(gross);
+                Ok(DexQuote {
+                    amount_in: gross + fee_amount,
+                    amount_out: lots * self.base_lot_size,
+                    fee_amount,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SOL/USDC with 0.001 SOL lots; 1 lot at $150 costs 150_000 USDC atoms
+    fn test_book() -> OrderBook {
+        OrderBook {
+            base_lot_size: 1_000_000,
+            base_decimals: 9,
+            quote_decimals: 6,
+            taker_fee_ppm: 1_000,
+            bids: OrderBook::aggregate_levels([(149_900, 500), (149_800, 1_000), (149_900, 500)], true),
+            asks: OrderBook::aggregate_levels([(150_200, 1_000), (150_100, 1_000)], false),
+        }
+    }
+
+    #[test]
+    fn test_levels_and_mid_price() {
+        let book = test_book();
+        assert_eq!(book.bids[0], BookLevel { quote_per_lot: 149_900, base_lots: 1_000 });
+        assert_eq!(book.asks[0].quote_per_lot, 150_100);
+        assert!((book.best_bid().unwrap() - 149.9).abs() < 1e-9);
+        assert!((book.mid_price().unwrap() - 150.0).abs() < 1e-9);
+        assert!((book.spread().unwrap() - 0.2).abs() < 1e-9);
+        assert_eq!(book.ui_levels(false)[1], (150.2, 1.0));
+    }
+
+    #[test]
+    fn test_taker_quote_walks_levels() {
+        let book = test_book();
+
+        // Sell 1.5 SOL: 1 SOL at 149.9 and 0.5 at 149.8, less 0.1%
+        let sell = book.taker_quote(1_500_000_000, true, true).unwrap();
+        assert_eq!(sell.amount_in, 1_500_000_000);
+        assert_eq!(sell.fee_amount, 224_800);
+        assert_eq!(sell.amount_out, 224_800_000 - 224_800);
+
+        let buy = book.taker_quote(1_500_000_000, false, false).unwrap();
+        assert_eq!(buy.amount_out, 1_500_000_000);
+        assert_eq!(buy.amount_in, 225_200_000 + 225_200);
+
+        // Spending what the exact-out buy cost gets the same base back
+        let spend = book.taker_quote(buy.amount_in, false, true).unwrap();
+        assert_eq!(spend, buy);
+
+        let receive = book.taker_quote(sell.amount_out, true, false).unwrap();
+        assert_eq!(receive, sell);
+
+        assert_eq!(book.taker_quote(3_000_000_000, true, true), Err(OrderBookError::InsufficientLiquidity));
+    }
+}
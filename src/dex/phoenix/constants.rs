@@ -0,0 +1,8 @@
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const PHOENIX_PROGRAM_ID: &str = "PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY";
+
+pub fn phoenix_program_id() -> Pubkey {
+    Pubkey::from_str(PHOENIX_PROGRAM_ID).expect("Invalid Phoenix program ID")
+}
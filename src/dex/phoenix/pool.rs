@@ -0,0 +1,135 @@
+// src/dex/phoenix/pool.rs - DexPool implementation for Phoenix markets
+
+use std::any::Any;
+use std::time::{SystemTime, UNIX_EPOCH};
+use solana_program::pubkey::Pubkey;
+use crate::dex::order_book::OrderBook;
+use crate::dex::phoenix::state::{read_orders, MarketHeader, MarketParams, RestingOrder};
+use crate::dex::pool::{AccountDataMap, DexError, DexKind, DexPool, DexQuote};
+
+/// A Phoenix market. The whole book lives in the market account, so every account update
+/// carries a fresh book.
+#[derive(Clone, Debug)]
+pub struct PhoenixMarket {
+    pub header: MarketHeader,
+    pub params: MarketParams,
+    pub book: OrderBook,
+}
+
+impl PhoenixMarket {
+    pub fn decode(data: &[u8]) -> Result<Self, DexError> {
+        let header = MarketHeader::try_deserialize(data)?;
+        let params = MarketParams::try_deserialize(data)?;
+        let (bids, asks) = read_orders(data, &header)?;
+
+        if params.base_lots_per_base_unit == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Phoenix market has no lot size").into());
+        }
+        // The program requires ticks to divide evenly into lots, so per-lot prices are exact
+        let quote_atoms_per_tick_per_lot = params.tick_size_in_quote_lots_per_base_unit
+            / params.base_lots_per_base_unit
+            * header.quote_lot_size;
+
+        // Slot-based expiry can't be checked here, but most expiring orders use a timestamp
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let live = |order: &RestingOrder| {
+            order.last_valid_unix_timestamp_in_seconds == 0 || order.last_valid_unix_timestamp_in_seconds >= now
+        };
+        let levels = |orders: Vec<RestingOrder>, is_bid: bool| {
+            let orders = orders
+                .into_iter()
+                .filter(live)
+                .map(|order| (order.price_in_ticks.saturating_mul(quote_atoms_per_tick_per_lot), order.num_base_lots));
+            OrderBook::aggregate_levels(orders, is_bid)
+        };
+
+        let book = OrderBook {
+            base_lot_size: header.base_lot_size,
+            base_decimals: header.base_decimals as u8,
+            quote_decimals: header.quote_decimals as u8,
+            taker_fee_ppm: params.taker_fee_bps * 100,
+            bids: levels(bids, true),
+            asks: levels(asks, false),
+        };
+
+        Ok(Self { header, params, book })
+    }
+}
+
+impl DexPool for PhoenixMarket {
+    fn kind(&self) -> DexKind {
+        DexKind::Phoenix
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn token_mints(&self) -> (Pubkey, Pubkey) {
+        (self.header.base_mint, self.header.quote_mint)
+    }
+
+    // The book knows its own decimals
+    fn mid_price(&self, _decimals_a: u8, _decimals_b: u8) -> f64 {
+        self.book.mid_price().unwrap_or(0.0)
+    }
+
+    fn fee_rate(&self) -> f64 {
+        self.params.taker_fee_bps as f64 / 10_000.0
+    }
+
+    // Resting base lots on both sides
+    fn liquidity(&self) -> f64 {
+        self.book.total_base_lots() as f64
+    }
+
+    fn order_book(&self) -> Option<&OrderBook> {
+        Some(&self.book)
+    }
+
+    fn required_accounts(&self, _pool_address: &Pubkey) -> Vec<Pubkey> {
+        Vec::new()
+    }
+
+    fn quote_swap(
+        &self,
+        _pool_address: &Pubkey,
+        amount: u64,
+        a_to_b: bool,
+        amount_specified_is_input: bool,
+        _accounts: &AccountDataMap,
+    ) -> Result<DexQuote, DexError> {
+        Ok(self.book.taker_quote(amount, a_to_b, amount_specified_is_input)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::order_book::BookLevel;
+    use crate::dex::phoenix::state::test_utils::market_data;
+
+    #[test]
+    fn test_book_from_market() {
+        let data = market_data(&[(true, 149_900, 5), (true, 149_800, 7), (true, 149_900, 2), (false, 150_100, 3)]);
+        let market = PhoenixMarket::decode(&data).unwrap();
+
+        assert_eq!(
+            market.book.bids,
+            vec![
+                BookLevel { quote_per_lot: 149_900, base_lots: 7 },
+                BookLevel { quote_per_lot: 149_800, base_lots: 7 },
+            ]
+        );
+        assert!((market.mid_price(9, 6) - 150.0).abs() < 1e-9);
+        assert_eq!(market.book.taker_fee_ppm, 200);
+
+        // Buying 0.003 SOL takes the whole ask level
+        let quote = market.quote_swap(&Pubkey::new_unique(), 3_000_000, false, false, &AccountDataMap::new()).unwrap();
+        assert_eq!(quote.amount_out, 3_000_000);
+        assert_eq!(quote.amount_in, 450_300 + 91);
+    }
+}
@@ -0,0 +1,230 @@
+use solana_program::pubkey::Pubkey;
+use std::io::{Error, ErrorKind, Result};
+use crate::dex::bytes::{read_pubkey, read_u64, read_u32};
+
+/// The fields of Phoenix's `MarketHeader` the terminal uses.
+#[derive(Clone, Copy, Debug)]
+pub struct MarketHeader {
+    pub status: u64,
+    pub bids_size: u64,
+    pub asks_size: u64,
+    pub num_seats: u64,
+    pub base_decimals: u32,
+    pub base_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub base_lot_size: u64,
+    pub quote_decimals: u32,
+    pub quote_mint: Pubkey,
+    pub quote_vault: Pubkey,
+    pub quote_lot_size: u64,
+    pub tick_size_in_quote_atoms_per_base_unit: u64,
+    pub market_sequence_number: u64,
+    pub raw_base_units_per_base_unit: u32,
+}
+
+impl MarketHeader {
+    pub const LEN: usize = 576;
+}
+
+/// The fixed part of the `FIFOMarket` that follows the header, before the order trees.
+#[derive(Clone, Copy, Debug)]
+pub struct MarketParams {
+    pub base_lots_per_base_unit: u64,
+    pub tick_size_in_quote_lots_per_base_unit: u64,
+    pub order_sequence_number: u64,
+    pub taker_fee_bps: u64,
+}
+
+impl MarketParams {
+    pub const OFFSET: usize = MarketHeader::LEN + 256;
+    pub const LEN: usize = 48;
+}
+
+/// A resting order as stored in the bids or asks tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RestingOrder {
+    pub price_in_ticks: u64,
+    pub order_sequence_number: u64,
+    pub trader_index: u64,
+    pub num_base_lots: u64,
+    pub last_valid_slot: u64,
+    pub last_valid_unix_timestamp_in_seconds: u64,
+}
+
+// Order trees are sokoban red-black trees: a 32 byte header, then nodes of four u32
+// registers (left, right, parent, color) followed by a 16 byte key and 32 byte value
+const TREE_HEADER_LEN: usize = 32;
+const ORDER_NODE_LEN: usize = 64;
+const TRADER_NODE_LEN: usize = 144;
+
+fn tree_len(capacity: u64, node_len: usize) -> usize {
+    TREE_HEADER_LEN + capacity as usize * node_len
+}
+
+impl MarketHeader {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for Phoenix MarketHeader"));
+        }
+
+        Ok(MarketHeader {
+            status: read_u64(data, 8),
+            bids_size: read_u64(data, 16),
+            asks_size: read_u64(data, 24),
+            num_seats: read_u64(data, 32),
+            base_decimals: read_u32(data, 40),
+            base_mint: read_pubkey(data, 48),
+            base_vault: read_pubkey(data, 80),
+            base_lot_size: read_u64(data, 112),
+            quote_decimals: read_u32(data, 120),
+            quote_mint: read_pubkey(data, 128),
+            quote_vault: read_pubkey(data, 160),
+            quote_lot_size: read_u64(data, 192),
+            tick_size_in_quote_atoms_per_base_unit: read_u64(data, 200),
+            market_sequence_number: read_u64(data, 272),
+            raw_base_units_per_base_unit: read_u32(data, 312),
+        })
+    }
+
+    /// Full account size implied by the tree capacities.
+    pub fn market_len(&self) -> usize {
+        MarketParams::OFFSET
+            + MarketParams::LEN
+            + tree_len(self.bids_size, ORDER_NODE_LEN)
+            + tree_len(self.asks_size, ORDER_NODE_LEN)
+            + tree_len(self.num_seats, TRADER_NODE_LEN)
+    }
+}
+
+impl MarketParams {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::OFFSET + Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for Phoenix market"));
+        }
+
+        Ok(MarketParams {
+            base_lots_per_base_unit: read_u64(data, Self::OFFSET),
+            tick_size_in_quote_lots_per_base_unit: read_u64(data, Self::OFFSET + 8),
+            order_sequence_number: read_u64(data, Self::OFFSET + 16),
+            taker_fee_bps: read_u64(data, Self::OFFSET + 24),
+        })
+    }
+}
+
+// Collects the nodes reachable from the root. Free nodes are never linked from it.
+fn read_order_tree(data: &[u8], tree_offset: usize, capacity: u64) -> Result<Vec<RestingOrder>> {
+    let invalid = || Error::new(ErrorKind::InvalidData, "corrupt Phoenix order tree");
+    let root = read_u32(data, tree_offset);
+    let nodes_offset = tree_offset + TREE_HEADER_LEN;
+
+    let mut orders = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        // Node addresses are 1-based; 0 is the sentinel
+        if node == 0 {
+            continue;
+        }
+        if node as u64 > capacity || orders.len() as u64 >= capacity {
+            return Err(invalid());
+        }
+        let offset = nodes_offset + (node as usize - 1) * ORDER_NODE_LEN;
+        stack.push(read_u32(data, offset));
+        stack.push(read_u32(data, offset + 4));
+
+        orders.push(RestingOrder {
+            price_in_ticks: read_u64(data, offset + 16),
+            order_sequence_number: read_u64(data, offset + 24),
+            trader_index: read_u64(data, offset + 32),
+            num_base_lots: read_u64(data, offset + 40),
+            last_valid_slot: read_u64(data, offset + 48),
+            last_valid_unix_timestamp_in_seconds: read_u64(data, offset + 56),
+        });
+    }
+    Ok(orders)
+}
+
+/// Reads the resting bids and asks of a Phoenix market account, in tree order.
+pub fn read_orders(data: &[u8], header: &MarketHeader) -> Result<(Vec<RestingOrder>, Vec<RestingOrder>)> {
+    if data.len() < header.market_len() {
+        return Err(Error::new(ErrorKind::InvalidData, "data too short for Phoenix market"));
+    }
+
+    let bids_offset = MarketParams::OFFSET + MarketParams::LEN;
+    let asks_offset = bids_offset + tree_len(header.bids_size, ORDER_NODE_LEN);
+    let bids = read_order_tree(data, bids_offset, header.bids_size)?;
+    let asks = read_order_tree(data, asks_offset, header.asks_size)?;
+    Ok((bids, asks))
+}
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use super::*;
+
+    /// A market account with room for four orders per side and one seat.
+    pub fn market_data(orders: &[(bool, u64, u64)]) -> Vec<u8> {
+        let mut data = vec![0u8; MarketHeader::LEN];
+        data[16..24].copy_from_slice(&4u64.to_le_bytes());
+        data[24..32].copy_from_slice(&4u64.to_le_bytes());
+        data[32..40].copy_from_slice(&1u64.to_le_bytes());
+        data[40..44].copy_from_slice(&9u32.to_le_bytes());
+        data[112..120].copy_from_slice(&1_000_000u64.to_le_bytes());
+        data[120..124].copy_from_slice(&6u32.to_le_bytes());
+        data[192..200].copy_from_slice(&1u64.to_le_bytes());
+        data[312..316].copy_from_slice(&1u32.to_le_bytes());
+        let header = MarketHeader::try_deserialize(&data).unwrap();
+        data.resize(header.market_len(), 0);
+
+        // 1000 lots per SOL, ticks of 0.001 USDC; 2 bps taker fee
+        let params = MarketParams::OFFSET;
+        data[params..params+8].copy_from_slice(&1_000u64.to_le_bytes());
+        data[params+8..params+16].copy_from_slice(&1_000u64.to_le_bytes());
+        data[params+24..params+32].copy_from_slice(&2u64.to_le_bytes());
+
+        let bids_offset = MarketParams::OFFSET + MarketParams::LEN;
+        let asks_offset = bids_offset + tree_len(4, ORDER_NODE_LEN);
+        let mut next_node = [0u32; 2];
+        for &(is_bid, price_in_ticks, num_base_lots) in orders {
+            let side = if is_bid { 0 } else { 1 };
+            let tree = if is_bid { bids_offset } else { asks_offset };
+            next_node[side] += 1;
+            let node = next_node[side];
+            let offset = tree + TREE_HEADER_LEN + (node as usize - 1) * ORDER_NODE_LEN;
+
+            // Chain each node as the right child of the previous one
+            if node == 1 {
+                data[tree..tree+4].copy_from_slice(&1u32.to_le_bytes());
+            } else {
+                let parent = offset - ORDER_NODE_LEN;
+                data[parent+4..parent+8].copy_from_slice(&node.to_le_bytes());
+            }
+            data[offset+16..offset+24].copy_from_slice(&price_in_ticks.to_le_bytes());
+            data[offset+40..offset+48].copy_from_slice(&num_base_lots.to_le_bytes());
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_deserialize() {
+        let data = test_utils::market_data(&[(true, 149_900, 5), (true, 149_800, 7), (false, 150_100, 3)]);
+        let header = MarketHeader::try_deserialize(&data).unwrap();
+        assert_eq!(header.bids_size, 4);
+        assert_eq!(header.base_decimals, 9);
+        assert_eq!(data.len(), 576 + 256 + 48 + 2 * (32 + 4 * 64) + 32 + 144);
+
+        let params = MarketParams::try_deserialize(&data).unwrap();
+        assert_eq!(params.taker_fee_bps, 2);
+
+        let (bids, asks) = read_orders(&data, &header).unwrap();
+        assert_eq!(bids.len(), 2);
+        assert_eq!(asks.len(), 1);
+        assert!(bids.iter().any(|o| o.price_in_ticks == 149_800 && o.num_base_lots == 7));
+        assert_eq!(asks[0].price_in_ticks, 150_100);
+
+        assert!(read_orders(&data[..1000], &header).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+// src/dex/pool.rs - Venue-independent view of a liquidity pool
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use crate::dex::meteora_dlmm::pool::MeteoraDlmmPool;
+use crate::dex::meteora_dlmm::swap::DlmmSwapError;
+use crate::dex::openbook_v2::pool::OpenBookMarket;
+use crate::dex::order_book::{OrderBook, OrderBookError};
+use crate::dex::phoenix::pool::PhoenixMarket;
+use crate::dex::raydium_amm::pool::RaydiumAmmPool;
+use crate::dex::raydium_clmm::pool::RaydiumClmmPool;
+use crate::dex::raydium_clmm::state::PoolState as RaydiumClmmPoolState;
+use crate::dex::whirlpool::state::Whirlpool;
+use crate::dex::whirlpool::swap::SwapError;
+
+/// Raw account data keyed by address, as returned by `getMultipleAccounts`.
+pub type AccountDataMap = HashMap<Pubkey, Vec<u8>>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DexError {
+    #[error("invalid account data: {0}")]
+    InvalidAccountData(#[from] std::io::Error),
+    #[error("account {0} is required but was not loaded")]
+    MissingAccount(Pubkey),
+    #[error("swap quote failed: {0}")]
+    Swap(#[from] SwapError),
+    #[error("swap quote failed: {0}")]
+    DlmmSwap(#[from] DlmmSwapError),
+    #[error("order book quote failed: {0}")]
+    OrderBook(#[from] OrderBookError),
+}
+
+/// The venues the terminal can decode. Serialized by name in `PoolConfig.dex`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DexKind {
+    Whirlpool,
+    RaydiumClmm,
+    RaydiumAmmV4,
+    MeteoraDlmm,
+    Phoenix,
+    OpenBookV2,
+}
+
+impl DexKind {
+    pub const ALL: [DexKind; 6] = [
+        DexKind::Whirlpool,
+        DexKind::RaydiumClmm,
+        DexKind::RaydiumAmmV4,
+        DexKind::MeteoraDlmm,
+        DexKind::Phoenix,
+        DexKind::OpenBookV2,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DexKind::Whirlpool => "Whirlpool",
+            DexKind::RaydiumClmm => "RaydiumClmm",
+            DexKind::RaydiumAmmV4 => "RaydiumAmmV4",
+            DexKind::MeteoraDlmm => "MeteoraDlmm",
+            DexKind::Phoenix => "Phoenix",
+            DexKind::OpenBookV2 => "OpenBookV2",
+        }
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            DexKind::Whirlpool => crate::dex::whirlpool::constants::whirlpool_profram_id(),
+            DexKind::RaydiumClmm => crate::dex::raydium_clmm::constants::raydium_clmm_program_id(),
+            DexKind::RaydiumAmmV4 => crate::dex::raydium_amm::constants::raydium_amm_v4_program_id(),
+            DexKind::MeteoraDlmm => crate::dex::meteora_dlmm::constants::meteora_dlmm_program_id(),
+            DexKind::Phoenix => crate::dex::phoenix::constants::phoenix_program_id(),
+            DexKind::OpenBookV2 => crate::dex::openbook_v2::constants::openbook_v2_program_id(),
+        }
+    }
+
+    /// Other accounts whose data `decode` needs alongside the pool account, e.g. a fee config.
+    pub fn companion_accounts(&self, data: &[u8]) -> Result<Vec<Pubkey>, DexError> {
+        match self {
+            DexKind::Whirlpool | DexKind::MeteoraDlmm | DexKind::Phoenix => Ok(Vec::new()),
+            DexKind::RaydiumClmm => Ok(vec![RaydiumClmmPoolState::try_deserialize(data)?.amm_config]),
+            DexKind::RaydiumAmmV4 => RaydiumAmmPool::companion_accounts(data),
+            DexKind::OpenBookV2 => OpenBookMarket::companion_accounts(data),
+        }
+    }
+
+    /// Decodes a pool account owned by this venue's program. `companions` must hold the
+    /// accounts listed by `companion_accounts`.
+    pub fn decode(&self, data: &[u8], companions: &AccountDataMap) -> Result<Arc<dyn DexPool>, DexError> {
+        match self {
+            DexKind::Whirlpool => Ok(Arc::new(Whirlpool::try_deserialize(data)?)),
+            DexKind::RaydiumClmm => Ok(Arc::new(RaydiumClmmPool::decode(data, companions)?)),
+            DexKind::RaydiumAmmV4 => Ok(Arc::new(RaydiumAmmPool::decode(data, companions)?)),
+            DexKind::MeteoraDlmm => Ok(Arc::new(MeteoraDlmmPool::decode(data)?)),
+            DexKind::Phoenix => Ok(Arc::new(PhoenixMarket::decode(data)?)),
+            DexKind::OpenBookV2 => Ok(Arc::new(OpenBookMarket::decode(data, companions)?)),
+        }
+    }
+}
+
+impl fmt::Display for DexKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for DexKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DexKind::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown dex: {}", s))
+    }
+}
+
+/// Result of quoting a swap against a pool. Amounts are raw token units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DexQuote {
+    /// Input including fees.
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+/// A decoded pool account. Implemented once per venue so the websocket client, chart and
+/// arbitrage detector can work with any of them.
+pub trait DexPool: fmt::Debug + Send + Sync {
+    fn kind(&self) -> DexKind;
+
+    fn as_any(&self) -> &dyn Any;
+
+    /// `(token_a, token_b)`; prices are quoted as token B per token A.
+    fn token_mints(&self) -> (Pubkey, Pubkey);
+
+    /// Marginal price of token A in token B, in UI units.
+    fn mid_price(&self, decimals_a: u8, decimals_b: u8) -> f64;
+
+    /// Swap fee as a fraction of the input amount.
+    fn fee_rate(&self) -> f64;
+
+    /// Venue-specific liquidity measure, only comparable between pools of the same kind.
+    fn liquidity(&self) -> f64;
+
+    /// Current tick (or bin) index for venues that have one.
+    fn tick_index(&self) -> Option<i32> {
+        None
+    }
+
+    /// Cumulative fees per unit of liquidity as Q64.64 `(token_a, token_b)`, for concentrated
+    /// liquidity venues.
+    fn fee_growth_global(&self) -> Option<(u128, u128)> {
+        None
+    }
+
+    /// Resting orders for limit order book venues.
+    fn order_book(&self) -> Option<&OrderBook> {
+        None
+    }
+
+    /// Accounts besides the pool itself that `quote_swap` needs, e.g. tick arrays.
+    fn required_accounts(&self, pool_address: &Pubkey) -> Vec<Pubkey>;
+
+    /// Quotes a swap using the pool state plus the accounts listed by `required_accounts`.
+    /// Accounts in `accounts` that belong to another pool than `pool_address` are ignored.
+    fn quote_swap(
+        &self,
+        pool_address: &Pubkey,
+        amount: u64,
+        a_to_b: bool,
+        amount_specified_is_input: bool,
+        accounts: &AccountDataMap,
+    ) -> Result<DexQuote, DexError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dex_kind_names_round_trip() {
+        for kind in DexKind::ALL {
+            assert_eq!(kind.name().parse::<DexKind>(), Ok(kind));
+        }
+        assert_eq!("whirlpool".parse::<DexKind>(), Ok(DexKind::Whirlpool));
+        assert!("Uniswap".parse::<DexKind>().is_err());
+    }
+}
@@ -0,0 +1,8 @@
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+
+pub fn raydium_amm_v4_program_id() -> Pubkey {
+    Pubkey::from_str(RAYDIUM_AMM_V4_PROGRAM_ID).expect("Invalid Raydium AMM v4 program ID")
+}
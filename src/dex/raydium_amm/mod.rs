@@ -0,0 +1,4 @@
+pub mod constants;
+pub mod pool;
+pub mod state;
+pub mod swap;
@@ -0,0 +1,146 @@
+// src/dex/raydium_amm/pool.rs - DexPool implementation for Raydium AMM v4
+
+use std::any::Any;
+use solana_program::pubkey::Pubkey;
+use crate::dex::pool::{AccountDataMap, DexError, DexKind, DexPool, DexQuote};
+use crate::dex::raydium_amm::state::AmmInfo;
+use crate::dex::raydium_amm::swap::constant_product_quote;
+use crate::utils::spl_token;
+
+/// An AMM v4 pool with the reserves read from its two vault token accounts.
+#[derive(Clone, Copy, Debug)]
+pub struct RaydiumAmmPool {
+    pub info: AmmInfo,
+    pub coin_reserve: u64,
+    pub pc_reserve: u64,
+}
+
+impl RaydiumAmmPool {
+    pub fn decode(data: &[u8], companions: &AccountDataMap) -> Result<Self, DexError> {
+        let info = AmmInfo::try_deserialize(data)?;
+        let vault_amount = |vault: Pubkey| -> Result<u64, DexError> {
+            let data = companions.get(&vault).ok_or(DexError::MissingAccount(vault))?;
+            spl_token::token_account_amount(data).ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "data too short for token account").into()
+            })
+        };
+
+        // Booked PnL still sits in the vaults but is not tradable
+        let coin_reserve = vault_amount(info.coin_vault)?.saturating_sub(info.need_take_pnl_coin);
+        let pc_reserve = vault_amount(info.pc_vault)?.saturating_sub(info.need_take_pnl_pc);
+
+        Ok(Self {
+            info,
+            coin_reserve,
+            pc_reserve,
+        })
+    }
+
+    pub fn companion_accounts(data: &[u8]) -> Result<Vec<Pubkey>, DexError> {
+        let info = AmmInfo::try_deserialize(data)?;
+        Ok(vec![info.coin_vault, info.pc_vault])
+    }
+}
+
+impl DexPool for RaydiumAmmPool {
+    fn kind(&self) -> DexKind {
+        DexKind::RaydiumAmmV4
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn token_mints(&self) -> (Pubkey, Pubkey) {
+        (self.info.coin_vault_mint, self.info.pc_vault_mint)
+    }
+
+    fn mid_price(&self, decimals_a: u8, decimals_b: u8) -> f64 {
+        if self.coin_reserve == 0 {
+            return 0.0;
+        }
+        let decimal_adjustment = 10f64.powi(decimals_a as i32 - decimals_b as i32);
+        self.pc_reserve as f64 / self.coin_reserve as f64 * decimal_adjustment
+    }
+
+    fn fee_rate(&self) -> f64 {
+        if self.info.swap_fee_denominator == 0 {
+            return 0.0;
+        }
+        self.info.swap_fee_numerator as f64 / self.info.swap_fee_denominator as f64
+    }
+
+    // Geometric mean of the reserves, the constant-product analogue of concentrated liquidity
+    fn liquidity(&self) -> f64 {
+        (self.coin_reserve as f64 * self.pc_reserve as f64).sqrt()
+    }
+
+    fn required_accounts(&self, _pool_address: &Pubkey) -> Vec<Pubkey> {
+        Vec::new()
+    }
+
+    fn quote_swap(
+        &self,
+        _pool_address: &Pubkey,
+        amount: u64,
+        a_to_b: bool,
+        amount_specified_is_input: bool,
+        _accounts: &AccountDataMap,
+    ) -> Result<DexQuote, DexError> {
+        let (reserve_in, reserve_out) = if a_to_b {
+            (self.coin_reserve, self.pc_reserve)
+        } else {
+            (self.pc_reserve, self.coin_reserve)
+        };
+        let quote = constant_product_quote(
+            amount,
+            amount_specified_is_input,
+            reserve_in,
+            reserve_out,
+            self.info.swap_fee_numerator,
+            self.info.swap_fee_denominator,
+        )?;
+
+        Ok(DexQuote {
+            amount_in: quote.amount_in,
+            amount_out: quote.amount_out,
+            fee_amount: quote.fee_amount,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserves_from_vaults() {
+        let coin_vault = Pubkey::new_unique();
+        let pc_vault = Pubkey::new_unique();
+        let mut data = vec![0u8; AmmInfo::LEN];
+        data[176..184].copy_from_slice(&25u64.to_le_bytes());
+        data[184..192].copy_from_slice(&10_000u64.to_le_bytes());
+        data[192..200].copy_from_slice(&1_000_000_000u64.to_le_bytes());
+        data[336..368].copy_from_slice(coin_vault.as_ref());
+        data[368..400].copy_from_slice(pc_vault.as_ref());
+
+        let token_account = |amount: u64| {
+            let mut account = vec![0u8; spl_token::TOKEN_ACCOUNT_LEN];
+            account[64..72].copy_from_slice(&amount.to_le_bytes());
+            account
+        };
+
+        let mut companions = AccountDataMap::new();
+        assert_eq!(RaydiumAmmPool::companion_accounts(&data).unwrap(), vec![coin_vault, pc_vault]);
+        assert!(RaydiumAmmPool::decode(&data, &companions).is_err());
+
+        // 101 SOL in the vault, 1 of it booked PnL; 15_000 USDC
+        companions.insert(coin_vault, token_account(101_000_000_000));
+        companions.insert(pc_vault, token_account(15_000_000_000));
+        let pool = RaydiumAmmPool::decode(&data, &companions).unwrap();
+
+        assert_eq!(pool.coin_reserve, 100_000_000_000);
+        assert!((pool.mid_price(9, 6) - 150.0).abs() < 1e-9);
+        assert!((pool.fee_rate() - 0.0025).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,103 @@
+use solana_program::pubkey::Pubkey;
+use std::io::{Error, ErrorKind, Result};
+
+/// The fields of Raydium's AMM v4 `AmmInfo` the terminal uses. Coin is token A, pc is token B.
+#[derive(Clone, Copy, Debug)]
+pub struct AmmInfo {
+    pub status: u64,
+    pub coin_decimals: u64,
+    pub pc_decimals: u64,
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+    // PnL the program has booked but not yet taken out of the vaults
+    pub need_take_pnl_coin: u64,
+    pub need_take_pnl_pc: u64,
+    pub pool_open_time: u64,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_vault_mint: Pubkey,
+    pub pc_vault_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub open_orders: Pubkey,
+    pub market: Pubkey,
+    pub market_program: Pubkey,
+    pub target_orders: Pubkey,
+    pub owner: Pubkey,
+    pub lp_reserve: u64,
+}
+
+impl AmmInfo {
+    pub const LEN: usize = 752;
+
+    // AMM v4 predates Anchor, so there is no discriminator and fields start at offset 0
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for Raydium AmmInfo"));
+        }
+
+        let read_u64 = |offset: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data[offset..offset+8]);
+            u64::from_le_bytes(bytes)
+        };
+        let read_pubkey = |offset: usize| {
+            let mut bytes = [0u8; 32];
+            bytes.copy_from_slice(&data[offset..offset+32]);
+            Pubkey::new_from_array(bytes)
+        };
+
+        Ok(AmmInfo {
+            status: read_u64(0),
+            coin_decimals: read_u64(32),
+            pc_decimals: read_u64(40),
+            trade_fee_numerator: read_u64(144),
+            trade_fee_denominator: read_u64(152),
+            swap_fee_numerator: read_u64(176),
+            swap_fee_denominator: read_u64(184),
+            need_take_pnl_coin: read_u64(192),
+            need_take_pnl_pc: read_u64(200),
+            pool_open_time: read_u64(224),
+            coin_vault: read_pubkey(336),
+            pc_vault: read_pubkey(368),
+            coin_vault_mint: read_pubkey(400),
+            pc_vault_mint: read_pubkey(432),
+            lp_mint: read_pubkey(464),
+            open_orders: read_pubkey(496),
+            market: read_pubkey(528),
+            market_program: read_pubkey(560),
+            target_orders: read_pubkey(592),
+            owner: read_pubkey(688),
+            lp_reserve: read_u64(720),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amm_info_deserialize() {
+        let mut data = vec![0u8; AmmInfo::LEN];
+        let coin_vault = Pubkey::new_unique();
+        let pc_mint = Pubkey::new_unique();
+        data[32..40].copy_from_slice(&9u64.to_le_bytes());
+        data[40..48].copy_from_slice(&6u64.to_le_bytes());
+        data[176..184].copy_from_slice(&25u64.to_le_bytes());
+        data[184..192].copy_from_slice(&10_000u64.to_le_bytes());
+        data[192..200].copy_from_slice(&7u64.to_le_bytes());
+        data[336..368].copy_from_slice(coin_vault.as_ref());
+        data[432..464].copy_from_slice(pc_mint.as_ref());
+
+        let info = AmmInfo::try_deserialize(&data).unwrap();
+        assert_eq!((info.coin_decimals, info.pc_decimals), (9, 6));
+        assert_eq!((info.swap_fee_numerator, info.swap_fee_denominator), (25, 10_000));
+        assert_eq!(info.need_take_pnl_coin, 7);
+        assert_eq!(info.coin_vault, coin_vault);
+        assert_eq!(info.pc_vault_mint, pc_mint);
+
+        assert!(AmmInfo::try_deserialize(&data[..751]).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+// Constant-product (x * y = k) quotes with the rounding of Raydium's AMM v4 program.
+
+use crate::dex::whirlpool::swap::SwapError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConstantProductQuote {
+    /// Input including fees.
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+fn ceil_div(numerator: u128, denominator: u128) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    Some(numerator.div_ceil(denominator))
+}
+
+/// Quotes a swap against reserves `reserve_in`/`reserve_out`. The fee is charged on the input
+/// side, rounded up.
+pub fn constant_product_quote(
+    amount: u64,
+    amount_specified_is_input: bool,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<ConstantProductQuote, SwapError> {
+    if amount == 0 {
+        return Err(SwapError::ZeroAmount);
+    }
+    if fee_numerator >= fee_denominator {
+        return Err(SwapError::MathOverflow);
+    }
+
+    let (reserve_in, reserve_out) = (reserve_in as u128, reserve_out as u128);
+    let (fee_numerator, fee_denominator) = (fee_numerator as u128, fee_denominator as u128);
+
+    if amount_specified_is_input {
+        let amount_in = amount as u128;
+        let fee_amount = ceil_div(amount_in * fee_numerator, fee_denominator).ok_or(SwapError::MathOverflow)?;
+        let amount_in_less_fee = amount_in - fee_amount;
+        let amount_out = reserve_out
+            .checked_mul(amount_in_less_fee)
+            .and_then(|n| n.checked_div(reserve_in + amount_in_less_fee))
+            .ok_or(SwapError::MathOverflow)?;
+
+        Ok(ConstantProductQuote {
+            amount_in: amount,
+            amount_out: amount_out as u64,
+            fee_amount: fee_amount as u64,
+        })
+    } else {
+        let amount_out = amount as u128;
+        if amount_out >= reserve_out {
+            return Err(SwapError::TokenMaxExceeded);
+        }
+        let amount_in_less_fee = reserve_in
+            .checked_mul(amount_out)
+            .and_then(|n| ceil_div(n, reserve_out - amount_out))
+            .ok_or(SwapError::MathOverflow)?;
+        let amount_in = ceil_div(amount_in_less_fee * fee_denominator, fee_denominator - fee_numerator)
+            .ok_or(SwapError::MathOverflow)?;
+        let amount_in = u64::try_from(amount_in).map_err(|_| SwapError::TokenMaxExceeded)?;
+
+        Ok(ConstantProductQuote {
+            amount_in,
+            amount_out: amount,
+            fee_amount: amount_in - amount_in_less_fee as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_product_quote() {
+        // 1000 A / 2000 B with a 0.25% fee
+        let quote = constant_product_quote(10_000, true, 1_000_000, 2_000_000, 25, 10_000).unwrap();
+        assert_eq!(quote.fee_amount, 25);
+        // 2_000_000 * 9_975 / 1_009_975
+        assert_eq!(quote.amount_out, 19_752);
+
+        let exact_out = constant_product_quote(quote.amount_out, false, 1_000_000, 2_000_000, 25, 10_000).unwrap();
+        assert_eq!(exact_out.amount_out, quote.amount_out);
+        assert!(exact_out.amount_in <= quote.amount_in);
+
+        assert_eq!(
+            constant_product_quote(2_000_000, false, 1_000_000, 2_000_000, 25, 10_000),
+            Err(SwapError::TokenMaxExceeded)
+        );
+    }
+}
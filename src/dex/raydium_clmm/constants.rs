@@ -0,0 +1,13 @@
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const RAYDIUM_CLMM_PROGRAM_ID: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK";
+
+pub const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+
+// Trade and protocol fee rates are both in hundredths of a basis point
+pub const FEE_RATE_DENOMINATOR_VALUE: u32 = 1_000_000;
+
+pub fn raydium_clmm_program_id() -> Pubkey {
+    Pubkey::from_str(RAYDIUM_CLMM_PROGRAM_ID).expect("Invalid Raydium CLMM program ID")
+}
@@ -0,0 +1,60 @@
+pub mod constants;
+pub mod pool;
+pub mod state;
+pub mod swap;
+
+use crate::dex::raydium_clmm::constants::TICK_ARRAY_SEED;
+use crate::dex::raydium_clmm::state::TICK_ARRAY_SIZE;
+use crate::dex::whirlpool::constants::{MAX_TICK_INDEX, MIN_TICK_INDEX};
+use solana_program::pubkey::Pubkey;
+
+pub fn ticks_in_array(tick_spacing: u16) -> i32 {
+    TICK_ARRAY_SIZE as i32 * tick_spacing as i32
+}
+
+/// Start index of the tick array containing `tick_index`.
+pub fn tick_array_start_index(tick_index: i32, tick_spacing: u16) -> i32 {
+    tick_index.div_euclid(ticks_in_array(tick_spacing)) * ticks_in_array(tick_spacing)
+}
+
+/// Raydium seeds tick arrays with the big-endian start index, unlike Whirlpool's decimal string.
+pub fn get_tick_array_address(pool_id: &Pubkey, start_tick_index: i32, program_id: &Pubkey) -> Pubkey {
+    let seeds = &[
+        TICK_ARRAY_SEED,
+        pool_id.as_ref(),
+        &start_tick_index.to_be_bytes(),
+    ];
+
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+/// The array containing `tick_current` plus up to `arrays_each_side` arrays below and above it,
+/// ordered from the lowest start index.
+pub fn derive_surrounding_tick_array_starts(
+    tick_current: i32,
+    tick_spacing: u16,
+    arrays_each_side: i32,
+) -> Vec<i32> {
+    let current_start = tick_array_start_index(tick_current, tick_spacing);
+    let span = ticks_in_array(tick_spacing);
+
+    (-arrays_each_side..=arrays_each_side)
+        .map(|offset| current_start + offset * span)
+        .filter(|start| *start + span > MIN_TICK_INDEX && *start <= MAX_TICK_INDEX)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_array_start_index() {
+        assert_eq!(tick_array_start_index(0, 10), 0);
+        assert_eq!(tick_array_start_index(599, 10), 0);
+        assert_eq!(tick_array_start_index(600, 10), 600);
+        assert_eq!(tick_array_start_index(-1, 10), -600);
+        assert_eq!(tick_array_start_index(-600, 10), -600);
+        assert_eq!(derive_surrounding_tick_array_starts(5, 10, 1), vec![-600, 0, 600]);
+    }
+}
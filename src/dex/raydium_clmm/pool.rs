@@ -0,0 +1,107 @@
+// src/dex/raydium_clmm/pool.rs - DexPool implementation for Raydium CLMM
+
+use std::any::Any;
+use solana_program::pubkey::Pubkey;
+use crate::data::price_data::whirlpool_math;
+use crate::dex::pool::{AccountDataMap, DexError, DexKind, DexPool, DexQuote};
+use crate::dex::raydium_clmm::constants::{self, FEE_RATE_DENOMINATOR_VALUE};
+use crate::dex::raydium_clmm::state::{AmmConfig, PoolState, TickArrayState};
+use crate::dex::raydium_clmm::swap::swap_quote;
+use crate::dex::raydium_clmm::{derive_surrounding_tick_array_starts, get_tick_array_address};
+use crate::dex::whirlpool::swap::SwapParams;
+
+// Tick arrays hold only 60 ticks, so quotes load a few more than Whirlpool's three
+const TICK_ARRAYS_EACH_SIDE: i32 = 3;
+
+/// A pool together with its fee tier, which lives in a separate `AmmConfig` account.
+#[derive(Clone, Copy, Debug)]
+pub struct RaydiumClmmPool {
+    pub state: PoolState,
+    pub amm_config: AmmConfig,
+}
+
+impl RaydiumClmmPool {
+    pub fn decode(data: &[u8], companions: &AccountDataMap) -> Result<Self, DexError> {
+        let state = PoolState::try_deserialize(data)?;
+        let config_data = companions
+            .get(&state.amm_config)
+            .ok_or(DexError::MissingAccount(state.amm_config))?;
+        let amm_config = AmmConfig::try_deserialize(config_data)?;
+        Ok(Self { state, amm_config })
+    }
+}
+
+impl DexPool for RaydiumClmmPool {
+    fn kind(&self) -> DexKind {
+        DexKind::RaydiumClmm
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn token_mints(&self) -> (Pubkey, Pubkey) {
+        (self.state.token_mint_0, self.state.token_mint_1)
+    }
+
+    fn mid_price(&self, decimals_a: u8, decimals_b: u8) -> f64 {
+        whirlpool_math::sqrt_price_x64_to_price(self.state.sqrt_price_x64, decimals_a, decimals_b)
+    }
+
+    fn fee_rate(&self) -> f64 {
+        self.amm_config.trade_fee_rate as f64 / FEE_RATE_DENOMINATOR_VALUE as f64
+    }
+
+    fn liquidity(&self) -> f64 {
+        self.state.liquidity as f64
+    }
+
+    fn tick_index(&self) -> Option<i32> {
+        Some(self.state.tick_current)
+    }
+
+    fn fee_growth_global(&self) -> Option<(u128, u128)> {
+        Some((self.state.fee_growth_global_0_x64, self.state.fee_growth_global_1_x64))
+    }
+
+    fn required_accounts(&self, pool_address: &Pubkey) -> Vec<Pubkey> {
+        let program_id = constants::raydium_clmm_program_id();
+        derive_surrounding_tick_array_starts(
+            self.state.tick_current,
+            self.state.tick_spacing,
+            TICK_ARRAYS_EACH_SIDE,
+        )
+        .into_iter()
+        .map(|start| get_tick_array_address(pool_address, start, &program_id))
+        .collect()
+    }
+
+    fn quote_swap(
+        &self,
+        pool_address: &Pubkey,
+        amount: u64,
+        a_to_b: bool,
+        amount_specified_is_input: bool,
+        accounts: &AccountDataMap,
+    ) -> Result<DexQuote, DexError> {
+        let tick_arrays: Vec<TickArrayState> = accounts
+            .values()
+            .filter_map(|data| TickArrayState::try_deserialize(data).ok())
+            .filter(|tick_array| tick_array.pool_id == *pool_address)
+            .collect();
+
+        let params = SwapParams {
+            amount,
+            sqrt_price_limit: 0,
+            amount_specified_is_input,
+            a_to_b,
+        };
+        let quote = swap_quote(&self.state, &self.amm_config, &tick_arrays, params)?;
+
+        Ok(DexQuote {
+            amount_in: quote.amount_in,
+            amount_out: quote.amount_out,
+            fee_amount: quote.fee_amount,
+        })
+    }
+}
@@ -0,0 +1,212 @@
+use solana_program::pubkey::Pubkey;
+use std::io::{Error, ErrorKind, Result};
+use crate::dex::bytes::{read_pubkey, read_u128, read_u64, read_u32, read_u16};
+
+pub const REWARD_NUM: usize = 3;
+pub const TICK_ARRAY_SIZE: usize = 60;
+
+/// The fields of Raydium's `PoolState` the terminal uses. Token 0/1 are called A/B elsewhere.
+#[derive(Clone, Copy, Debug)]
+pub struct PoolState {
+    pub amm_config: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub observation_key: Pubkey,
+    pub mint_decimals_0: u8,
+    pub mint_decimals_1: u8,
+    pub tick_spacing: u16,
+    pub liquidity: u128,
+    pub sqrt_price_x64: u128,
+    pub tick_current: i32,
+    pub fee_growth_global_0_x64: u128,
+    pub fee_growth_global_1_x64: u128,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub status: u8,
+    pub tick_array_bitmap: [u64; 16],
+    pub open_time: u64,
+}
+
+impl PoolState {
+    pub const LEN: usize = 1544;
+}
+
+/// Fee tier shared by every pool created with it.
+#[derive(Clone, Copy, Debug)]
+pub struct AmmConfig {
+    pub index: u16,
+    pub protocol_fee_rate: u32,
+    pub trade_fee_rate: u32,
+    pub tick_spacing: u16,
+    pub fund_fee_rate: u32,
+}
+
+impl AmmConfig {
+    pub const LEN: usize = 117;
+}
+
+#[derive(Clone, Debug)]
+pub struct TickArrayState {
+    pub pool_id: Pubkey,
+    pub start_tick_index: i32,
+    pub ticks: [TickState; TICK_ARRAY_SIZE],
+    pub initialized_tick_count: u8,
+}
+
+impl TickArrayState {
+    pub const LEN: usize = 10240;
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct TickState {
+    pub tick: i32,
+    pub liquidity_net: i128,
+    pub liquidity_gross: u128,
+    pub fee_growth_outside_0_x64: u128,
+    pub fee_growth_outside_1_x64: u128,
+    pub reward_growths_outside_x64: [u128; REWARD_NUM],
+}
+
+impl TickState {
+    pub const LEN: usize = 168;
+
+    // Raydium has no initialized flag; a tick is in use while it holds liquidity
+    pub fn is_initialized(&self) -> bool {
+        self.liquidity_gross != 0
+    }
+}
+
+// Offsets below include the 8 byte account discriminator
+impl PoolState {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for Raydium PoolState"));
+        }
+
+        let mut tick_array_bitmap = [0u64; 16];
+        for (i, word) in tick_array_bitmap.iter_mut().enumerate() {
+            *word = read_u64(data, 904 + i * 8);
+        }
+
+        Ok(PoolState {
+            amm_config: read_pubkey(data, 9),
+            owner: read_pubkey(data, 41),
+            token_mint_0: read_pubkey(data, 73),
+            token_mint_1: read_pubkey(data, 105),
+            token_vault_0: read_pubkey(data, 137),
+            token_vault_1: read_pubkey(data, 169),
+            observation_key: read_pubkey(data, 201),
+            mint_decimals_0: data[233],
+            mint_decimals_1: data[234],
+            tick_spacing: read_u16(data, 235),
+            liquidity: read_u128(data, 237),
+            sqrt_price_x64: read_u128(data, 253),
+            tick_current: read_u32(data, 269) as i32,
+            fee_growth_global_0_x64: read_u128(data, 277),
+            fee_growth_global_1_x64: read_u128(data, 293),
+            protocol_fees_token_0: read_u64(data, 309),
+            protocol_fees_token_1: read_u64(data, 317),
+            status: data[389],
+            tick_array_bitmap,
+            open_time: read_u64(data, 1080),
+        })
+    }
+}
+
+impl AmmConfig {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for Raydium AmmConfig"));
+        }
+
+        Ok(AmmConfig {
+            index: read_u16(data, 9),
+            protocol_fee_rate: read_u32(data, 43),
+            trade_fee_rate: read_u32(data, 47),
+            tick_spacing: read_u16(data, 51),
+            fund_fee_rate: read_u32(data, 53),
+        })
+    }
+}
+
+impl TickArrayState {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for Raydium TickArrayState"));
+        }
+
+        let mut ticks = [TickState::default(); TICK_ARRAY_SIZE];
+        for (i, tick) in ticks.iter_mut().enumerate() {
+            *tick = TickState::deserialize(&data[44 + i * TickState::LEN..]);
+        }
+
+        Ok(TickArrayState {
+            pool_id: read_pubkey(data, 8),
+            start_tick_index: read_u32(data, 40) as i32,
+            ticks,
+            initialized_tick_count: data[44 + TICK_ARRAY_SIZE * TickState::LEN],
+        })
+    }
+}
+
+impl TickState {
+    // Callers guarantee `data` holds at least `TickState::LEN` bytes
+    fn deserialize(data: &[u8]) -> Self {
+        let mut reward_growths_outside_x64 = [0u128; REWARD_NUM];
+        for (i, growth) in reward_growths_outside_x64.iter_mut().enumerate() {
+            *growth = read_u128(data, 68 + i * 16);
+        }
+
+        TickState {
+            tick: read_u32(data, 0) as i32,
+            liquidity_net: read_u128(data, 4) as i128,
+            liquidity_gross: read_u128(data, 20),
+            fee_growth_outside_0_x64: read_u128(data, 36),
+            fee_growth_outside_1_x64: read_u128(data, 52),
+            reward_growths_outside_x64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_state_and_tick_array_deserialize() {
+        let mut pool_data = vec![0u8; PoolState::LEN];
+        let amm_config = Pubkey::new_unique();
+        pool_data[9..41].copy_from_slice(amm_config.as_ref());
+        pool_data[233] = 9;
+        pool_data[234] = 6;
+        pool_data[235..237].copy_from_slice(&10u16.to_le_bytes());
+        pool_data[253..269].copy_from_slice(&(1u128 << 64).to_le_bytes());
+        pool_data[269..273].copy_from_slice(&(-17i32).to_le_bytes());
+
+        let pool = PoolState::try_deserialize(&pool_data).unwrap();
+        assert_eq!(pool.amm_config, amm_config);
+        assert_eq!((pool.mint_decimals_0, pool.mint_decimals_1), (9, 6));
+        assert_eq!(pool.tick_spacing, 10);
+        assert_eq!(pool.sqrt_price_x64, 1u128 << 64);
+        assert_eq!(pool.tick_current, -17);
+
+        let mut tick_array_data = vec![0u8; TickArrayState::LEN];
+        tick_array_data[40..44].copy_from_slice(&(-600i32).to_le_bytes());
+        let tick_offset = 44 + 2 * TickState::LEN;
+        tick_array_data[tick_offset..tick_offset+4].copy_from_slice(&(-580i32).to_le_bytes());
+        tick_array_data[tick_offset+4..tick_offset+20].copy_from_slice(&(-5i128).to_le_bytes());
+        tick_array_data[tick_offset+20..tick_offset+36].copy_from_slice(&5u128.to_le_bytes());
+
+        let tick_array = TickArrayState::try_deserialize(&tick_array_data).unwrap();
+        assert_eq!(tick_array.start_tick_index, -600);
+        assert_eq!(tick_array.ticks[2].tick, -580);
+        assert_eq!(tick_array.ticks[2].liquidity_net, -5);
+        assert!(tick_array.ticks[2].is_initialized());
+        assert!(!tick_array.ticks[3].is_initialized());
+
+        assert!(PoolState::try_deserialize(&pool_data[..200]).is_err());
+    }
+}
@@ -0,0 +1,296 @@
+// Swap quotes for Raydium CLMM pools. The concentrated liquidity math is the same Q64.64
+// Uniswap v3 design Whirlpool uses, so the step math is shared; only the tick storage differs.
+
+use std::collections::BTreeMap;
+use crate::dex::raydium_clmm::constants::FEE_RATE_DENOMINATOR_VALUE;
+use crate::dex::raydium_clmm::state::{AmmConfig, PoolState, TickArrayState};
+use crate::dex::raydium_clmm::{tick_array_start_index, ticks_in_array};
+use crate::dex::whirlpool::constants::{MAX_TICK_INDEX, MIN_TICK_INDEX};
+use crate::dex::whirlpool::swap::{resolve_sqrt_price_limit, SwapError, SwapParams, SwapQuote};
+use crate::dex::whirlpool::swap_math::compute_swap_step;
+use crate::dex::whirlpool::tick_math::{sqrt_price_from_tick_index, tick_index_from_sqrt_price};
+
+// Initialized ticks of the arrays that are loaded contiguously from the current one in swap
+// direction, plus the tick range they cover.
+struct LoadedTicks {
+    liquidity_net: BTreeMap<i32, i128>,
+    lower: i32,
+    upper: i32,
+}
+
+impl LoadedTicks {
+    fn collect(pool: &PoolState, tick_arrays: &[TickArrayState], a_to_b: bool) -> Result<Self, SwapError> {
+        let span = ticks_in_array(pool.tick_spacing);
+        let find = |start: i32| tick_arrays.iter().find(|ta| ta.start_tick_index == start);
+
+        let first_start = tick_array_start_index(pool.tick_current, pool.tick_spacing);
+        let mut start = first_start;
+        let mut liquidity_net = BTreeMap::new();
+        let mut loaded = 0;
+
+        while let Some(tick_array) = find(start) {
+            for tick in tick_array.ticks.iter().filter(|t| t.is_initialized()) {
+                liquidity_net.insert(tick.tick, tick.liquidity_net);
+            }
+            loaded += 1;
+            start = if a_to_b { start - span } else { start + span };
+        }
+
+        if loaded == 0 {
+            return Err(SwapError::MissingTickArray(first_start));
+        }
+
+        let (lower, upper) = if a_to_b {
+            (start + span, first_start + span)
+        } else {
+            (first_start, start)
+        };
+        Ok(Self {
+            liquidity_net,
+            lower: lower.max(MIN_TICK_INDEX),
+            upper: upper.min(MAX_TICK_INDEX),
+        })
+    }
+
+    // Next tick to swap towards and whether it is an initialized tick that must be crossed
+    fn next_tick(&self, tick_current: i32, a_to_b: bool) -> Result<(i32, bool), SwapError> {
+        if a_to_b {
+            if tick_current < self.lower {
+                return Err(SwapError::TickArraySequenceExhausted);
+            }
+            Ok(match self.liquidity_net.range(self.lower..=tick_current).next_back() {
+                Some((&tick, _)) => (tick, true),
+                None => (self.lower, false),
+            })
+        } else {
+            if tick_current >= self.upper {
+                return Err(SwapError::TickArraySequenceExhausted);
+            }
+            Ok(match self.liquidity_net.range(tick_current + 1..=self.upper).next() {
+                Some((&tick, _)) => (tick, true),
+                None => (self.upper, false),
+            })
+        }
+    }
+}
+
+/// Quotes a swap against a Raydium CLMM pool over whichever of `tick_arrays` lie on the swap path.
+pub fn swap_quote(
+    pool: &PoolState,
+    amm_config: &AmmConfig,
+    tick_arrays: &[TickArrayState],
+    params: SwapParams,
+) -> Result<SwapQuote, SwapError> {
+    if params.amount == 0 {
+        return Err(SwapError::ZeroAmount);
+    }
+
+    let a_to_b = params.a_to_b;
+    let exact_in = params.amount_specified_is_input;
+    let sqrt_price_limit = resolve_sqrt_price_limit(&params, pool.sqrt_price_x64)?;
+    let ticks = LoadedTicks::collect(pool, tick_arrays, a_to_b)?;
+
+    let mut amount_remaining = params.amount;
+    let mut amount_calculated = 0u64;
+    let mut curr_sqrt_price = pool.sqrt_price_x64;
+    let mut curr_tick_index = pool.tick_current;
+    let mut curr_liquidity = pool.liquidity;
+    let mut fee_amount = 0u64;
+    let mut protocol_fee = 0u64;
+    let mut ticks_crossed = 0u32;
+
+    while amount_remaining > 0 && sqrt_price_limit != curr_sqrt_price {
+        let (next_tick_index, initialized) = ticks.next_tick(curr_tick_index, a_to_b)?;
+
+        let next_tick_sqrt_price = sqrt_price_from_tick_index(next_tick_index);
+        let sqrt_price_target = if a_to_b {
+            next_tick_sqrt_price.max(sqrt_price_limit)
+        } else {
+            next_tick_sqrt_price.min(sqrt_price_limit)
+        };
+
+        let step = compute_swap_step(
+            amount_remaining,
+            amm_config.trade_fee_rate,
+            curr_liquidity,
+            curr_sqrt_price,
+            sqrt_price_target,
+            exact_in,
+            a_to_b,
+        )?;
+
+        if exact_in {
+            amount_remaining = amount_remaining
+                .checked_sub(step.amount_in)
+                .and_then(|rest| rest.checked_sub(step.fee_amount))
+                .ok_or(SwapError::MathOverflow)?;
+            amount_calculated = amount_calculated
+                .checked_add(step.amount_out)
+                .ok_or(SwapError::MathOverflow)?;
+        } else {
+            amount_remaining = amount_remaining
+                .checked_sub(step.amount_out)
+                .ok_or(SwapError::MathOverflow)?;
+            amount_calculated = amount_calculated
+                .checked_add(step.amount_in)
+                .and_then(|total| total.checked_add(step.fee_amount))
+                .ok_or(SwapError::MathOverflow)?;
+        }
+
+        protocol_fee += (step.fee_amount as u128 * amm_config.protocol_fee_rate as u128
+            / FEE_RATE_DENOMINATOR_VALUE as u128) as u64;
+        fee_amount += step.fee_amount;
+
+        if step.next_sqrt_price == next_tick_sqrt_price {
+            if initialized {
+                let liquidity_net = ticks.liquidity_net[&next_tick_index];
+                let liquidity_net = if a_to_b { -liquidity_net } else { liquidity_net };
+                curr_liquidity = curr_liquidity
+                    .checked_add_signed(liquidity_net)
+                    .ok_or(SwapError::LiquidityOverflow(next_tick_index))?;
+                ticks_crossed += 1;
+            }
+
+            curr_tick_index = if a_to_b {
+                next_tick_index - 1
+            } else {
+                next_tick_index
+            };
+        } else if step.next_sqrt_price != curr_sqrt_price {
+            curr_tick_index = tick_index_from_sqrt_price(step.next_sqrt_price);
+        }
+
+        curr_sqrt_price = step.next_sqrt_price;
+    }
+
+    let amount_specified_used = params.amount - amount_remaining;
+    let (amount_a, amount_b) = if a_to_b == exact_in {
+        (amount_specified_used, amount_calculated)
+    } else {
+        (amount_calculated, amount_specified_used)
+    };
+    let (amount_in, amount_out) = if a_to_b {
+        (amount_a, amount_b)
+    } else {
+        (amount_b, amount_a)
+    };
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        amount_a,
+        amount_b,
+        end_sqrt_price: curr_sqrt_price,
+        end_tick_index: curr_tick_index,
+        end_liquidity: curr_liquidity,
+        fee_amount,
+        protocol_fee,
+        ticks_crossed,
+        a_to_b,
+        amount_specified_is_input: exact_in,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::raydium_clmm::state::{TickState, TICK_ARRAY_SIZE};
+    use crate::dex::whirlpool::state::{Tick, TickArray, Whirlpool};
+    use crate::dex::whirlpool::swap::swap_quote as whirlpool_swap_quote;
+    use solana_program::pubkey::Pubkey;
+
+    const TICK_SPACING: u16 = 64;
+    const LIQUIDITY: u128 = 1_000_000_000_000;
+
+    fn test_pool() -> (PoolState, AmmConfig) {
+        let mut pool = PoolState::try_deserialize(&vec![0u8; PoolState::LEN]).unwrap();
+        pool.tick_spacing = TICK_SPACING;
+        pool.liquidity = LIQUIDITY;
+        pool.sqrt_price_x64 = sqrt_price_from_tick_index(0);
+        pool.tick_current = 0;
+
+        let mut amm_config = AmmConfig::try_deserialize(&[0u8; AmmConfig::LEN]).unwrap();
+        amm_config.trade_fee_rate = 3000;
+        (pool, amm_config)
+    }
+
+    // Liquidity from -5632 to 5632, the same position the Whirlpool swap tests use
+    fn range_tick_arrays() -> Vec<TickArrayState> {
+        let width = ticks_in_array(TICK_SPACING);
+        let mut arrays: Vec<TickArrayState> = (-3..3)
+            .map(|i| TickArrayState {
+                pool_id: Pubkey::default(),
+                start_tick_index: i * width,
+                ticks: [TickState::default(); TICK_ARRAY_SIZE],
+                initialized_tick_count: 0,
+            })
+            .collect();
+        for (tick_index, liquidity_net) in [(-5632, LIQUIDITY as i128), (5632, -(LIQUIDITY as i128))] {
+            let array = arrays
+                .iter_mut()
+                .find(|a| a.start_tick_index == tick_array_start_index(tick_index, TICK_SPACING))
+                .unwrap();
+            let tick = &mut array.ticks[((tick_index - array.start_tick_index) / TICK_SPACING as i32) as usize];
+            tick.tick = tick_index;
+            tick.liquidity_net = liquidity_net;
+            tick.liquidity_gross = LIQUIDITY;
+        }
+        arrays
+    }
+
+    fn equivalent_whirlpool() -> (Whirlpool, Vec<TickArray>) {
+        let mut whirlpool = Whirlpool::try_deserialize(&vec![0u8; Whirlpool::LEN]).unwrap();
+        whirlpool.tick_spacing = TICK_SPACING;
+        whirlpool.fee_rate = 3000;
+        whirlpool.liquidity = LIQUIDITY;
+        whirlpool.sqrt_price = sqrt_price_from_tick_index(0);
+
+        let width = 88 * TICK_SPACING as i32;
+        let mut arrays: Vec<TickArray> = (-3..3)
+            .map(|i| TickArray {
+                start_tick_index: i * width,
+                ticks: [Tick::default(); 88],
+                whirlpool: Pubkey::default(),
+            })
+            .collect();
+        arrays[2].ticks[0] = Tick { initialized: true, liquidity_net: LIQUIDITY as i128, liquidity_gross: LIQUIDITY, ..Tick::default() };
+        arrays[4].ticks[0] = Tick { initialized: true, liquidity_net: -(LIQUIDITY as i128), liquidity_gross: LIQUIDITY, ..Tick::default() };
+        (whirlpool, arrays)
+    }
+
+    #[test]
+    fn test_quote_matches_whirlpool_for_same_liquidity() {
+        let (pool, amm_config) = test_pool();
+        let arrays = range_tick_arrays();
+        let (whirlpool, whirlpool_arrays) = equivalent_whirlpool();
+
+        for params in [SwapParams::exact_in(1_000_000, true), SwapParams::exact_out(2_000_000, false)] {
+            let quote = swap_quote(&pool, &amm_config, &arrays, params).unwrap();
+            let expected = whirlpool_swap_quote(&whirlpool, &whirlpool_arrays, params).unwrap();
+            assert_eq!(quote.amount_in, expected.amount_in);
+            assert_eq!(quote.amount_out, expected.amount_out);
+            assert_eq!(quote.fee_amount, expected.fee_amount);
+            assert_eq!(quote.end_sqrt_price, expected.end_sqrt_price);
+        }
+    }
+
+    #[test]
+    fn test_swap_crosses_range_end() {
+        let (pool, amm_config) = test_pool();
+        let arrays = range_tick_arrays();
+
+        let limit = sqrt_price_from_tick_index(-6000);
+        let params = SwapParams {
+            sqrt_price_limit: limit,
+            ..SwapParams::exact_in(u64::MAX / 2, true)
+        };
+        let quote = swap_quote(&pool, &amm_config, &arrays, params).unwrap();
+        assert_eq!(quote.ticks_crossed, 1);
+        assert_eq!(quote.end_liquidity, 0);
+        assert_eq!(quote.end_sqrt_price, limit);
+
+        // Without a limit the swap runs off the loaded arrays
+        let result = swap_quote(&pool, &amm_config, &arrays, SwapParams::exact_in(u64::MAX / 2, true));
+        assert_eq!(result, Err(SwapError::TickArraySequenceExhausted));
+    }
+}
@@ -5,7 +5,22 @@ use std::str::FromStr;
 pub const WHIRLPOOL_PROGRAM_ID: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
 pub const MAX_TICK_INDEX: i32 = 443636;
 pub const MIN_TICK_INDEX: i32 = -443636;
+pub const MAX_SQRT_PRICE_X64: u128 = 79226673515401279992447579055;
+pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
+
+pub const FEE_RATE_MUL_VALUE: u128 = 1_000_000;
+pub const PROTOCOL_FEE_RATE_MUL_VALUE: u128 = 10_000;
 
 pub fn whirlpool_profram_id() -> Pubkey {
-    Pubkey::from_str(WHIRLPOOL_PROGRAM_ID).expect("Invalid Whirlpool program ID")   \\ .unrwap
+    Pubkey::from_str(WHIRLPOOL_PROGRAM_ID).expect("Invalid Whirlpool program ID")
+}
+
+pub const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfEhr";
+
+// Anchor instruction discriminators
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+pub const SWAP_V2_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+
+pub fn memo_program_id() -> Pubkey {
+    Pubkey::from_str(MEMO_PROGRAM_ID).expect("Invalid memo program ID")
 }
\ No newline at end of file
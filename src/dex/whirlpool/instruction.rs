@@ -0,0 +1,256 @@
+// Builders for the Whirlpool program's `swap` and `swapV2` instructions
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use crate::dex::whirlpool::constants::{self, SWAP_DISCRIMINATOR, SWAP_V2_DISCRIMINATOR};
+use crate::dex::whirlpool::state::Whirlpool;
+use crate::dex::whirlpool::swap::{resolve_sqrt_price_limit, SwapError, SwapParams, SwapQuote};
+use crate::dex::whirlpool::u256_math::mul_div;
+use crate::dex::whirlpool::{derive_tick_array_start_indexes, get_oracle_address, get_tick_array_address};
+use crate::utils::spl_token;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// Arguments of `swap` / `swapV2`, in instruction data order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapInstructionArgs {
+    pub amount: u64,
+    /// Minimum output for exact-in swaps, maximum input for exact-out swaps.
+    pub other_amount_threshold: u64,
+    pub sqrt_price_limit: u128,
+    pub amount_specified_is_input: bool,
+    pub a_to_b: bool,
+}
+
+impl SwapInstructionArgs {
+    /// Arguments for executing `quote`, allowing the unspecified side to move against the
+    /// caller by up to `slippage_percent`.
+    pub fn from_quote(
+        whirlpool: &Whirlpool,
+        params: SwapParams,
+        quote: &SwapQuote,
+        slippage_percent: f64,
+    ) -> Result<Self, SwapError> {
+        let slippage_bps = (slippage_percent.clamp(0.0, 100.0) * 100.0).round() as u128;
+        let other_amount_threshold = if params.amount_specified_is_input {
+            mul_div(quote.amount_out as u128, BPS_DENOMINATOR - slippage_bps, BPS_DENOMINATOR, false)
+                .ok_or(SwapError::MathOverflow)? as u64
+        } else {
+            mul_div(quote.amount_in as u128, BPS_DENOMINATOR + slippage_bps, BPS_DENOMINATOR, true)
+                .map_or(u64::MAX, |max_in| u64::try_from(max_in).unwrap_or(u64::MAX))
+        };
+
+        Ok(Self {
+            amount: params.amount,
+            other_amount_threshold,
+            sqrt_price_limit: resolve_sqrt_price_limit(&params, whirlpool.sqrt_price)?,
+            amount_specified_is_input: params.amount_specified_is_input,
+            a_to_b: params.a_to_b,
+        })
+    }
+
+    fn data(&self, discriminator: [u8; 8]) -> Vec<u8> {
+        let mut data = Vec::with_capacity(8 + 8 + 8 + 16 + 2 + 1);
+        data.extend_from_slice(&discriminator);
+        data.extend_from_slice(&self.amount.to_le_bytes());
+        data.extend_from_slice(&self.other_amount_threshold.to_le_bytes());
+        data.extend_from_slice(&self.sqrt_price_limit.to_le_bytes());
+        data.push(self.amount_specified_is_input as u8);
+        data.push(self.a_to_b as u8);
+        data
+    }
+}
+
+/// The trader's side of a swap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapAccounts {
+    /// Owner of both token accounts; signs the transaction.
+    pub token_authority: Pubkey,
+    pub token_owner_account_a: Pubkey,
+    pub token_owner_account_b: Pubkey,
+    /// Token programs of mint A and B. Only `swapV2` supports Token-2022 mints.
+    pub token_program_a: Pubkey,
+    pub token_program_b: Pubkey,
+}
+
+impl SwapAccounts {
+    /// Accounts for two mints owned by the SPL token program.
+    pub fn new(token_authority: Pubkey, token_owner_account_a: Pubkey, token_owner_account_b: Pubkey) -> Self {
+        let token_program = spl_token::token_program_id();
+        Self {
+            token_authority,
+            token_owner_account_a,
+            token_owner_account_b,
+            token_program_a: token_program,
+            token_program_b: token_program,
+        }
+    }
+}
+
+/// Builds swap instructions against one Whirlpool.
+pub struct SwapInstructionBuilder<'a> {
+    pub whirlpool: &'a Whirlpool,
+    pub whirlpool_address: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: SwapAccounts,
+}
+
+impl<'a> SwapInstructionBuilder<'a> {
+    pub fn new(whirlpool: &'a Whirlpool, whirlpool_address: Pubkey, accounts: SwapAccounts) -> Self {
+        Self {
+            whirlpool,
+            whirlpool_address,
+            program_id: constants::whirlpool_profram_id(),
+            accounts,
+        }
+    }
+
+    /// The three tick arrays the program walks in the swap direction. Arrays past the end of
+    /// the tick range are replaced by the last valid one, as the program allows.
+    pub fn tick_arrays(&self, a_to_b: bool) -> [Pubkey; 3] {
+        let (start_0, start_1, start_2) =
+            derive_tick_array_start_indexes(self.whirlpool.tick_current_index, self.whirlpool.tick_spacing, a_to_b);
+        let start_1 = start_1.unwrap_or(start_0);
+        let start_2 = start_2.unwrap_or(start_1);
+        [start_0, start_1, start_2].map(|start| get_tick_array_address(&self.whirlpool_address, start, &self.program_id))
+    }
+
+    fn pool_accounts(&self, a_to_b: bool) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.accounts.token_owner_account_a, false),
+            AccountMeta::new(self.whirlpool.token_vault_a, false),
+            AccountMeta::new(self.accounts.token_owner_account_b, false),
+            AccountMeta::new(self.whirlpool.token_vault_b, false),
+        ];
+        accounts.extend(self.tick_arrays(a_to_b).into_iter().map(|address| AccountMeta::new(address, false)));
+        // Writable since the program started recording adaptive fee state in the oracle
+        accounts.push(AccountMeta::new(get_oracle_address(&self.whirlpool_address, &self.program_id), false));
+        accounts
+    }
+
+    /// The legacy `swap` instruction. Both mints must belong to the SPL token program.
+    pub fn swap(&self, args: &SwapInstructionArgs) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(spl_token::token_program_id(), false),
+            AccountMeta::new_readonly(self.accounts.token_authority, true),
+            AccountMeta::new(self.whirlpool_address, false),
+        ];
+        accounts.extend(self.pool_accounts(args.a_to_b));
+
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: args.data(SWAP_DISCRIMINATOR),
+        }
+    }
+
+    /// The `swapV2` instruction, which also handles Token-2022 mints without transfer hooks.
+    pub fn swap_v2(&self, args: &SwapInstructionArgs) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.accounts.token_program_a, false),
+            AccountMeta::new_readonly(self.accounts.token_program_b, false),
+            AccountMeta::new_readonly(constants::memo_program_id(), false),
+            AccountMeta::new_readonly(self.accounts.token_authority, true),
+            AccountMeta::new(self.whirlpool_address, false),
+            AccountMeta::new_readonly(self.whirlpool.token_mint_a, false),
+            AccountMeta::new_readonly(self.whirlpool.token_mint_b, false),
+        ];
+        accounts.extend(self.pool_accounts(args.a_to_b));
+
+        let mut data = args.data(SWAP_V2_DISCRIMINATOR);
+        // remaining_accounts_info: None
+        data.push(0);
+
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::whirlpool::constants::MIN_SQRT_PRICE_X64;
+    use crate::dex::whirlpool::state::{whirlpool_at_tick_zero, TICK_ARRAY_SIZE};
+
+    fn quote(amount_in: u64, amount_out: u64) -> SwapQuote {
+        SwapQuote {
+            amount_in,
+            amount_out,
+            amount_a: amount_in,
+            amount_b: amount_out,
+            end_sqrt_price: 1u128 << 64,
+            end_tick_index: 0,
+            end_liquidity: 0,
+            fee_amount: 0,
+            protocol_fee: 0,
+            ticks_crossed: 0,
+            a_to_b: true,
+            amount_specified_is_input: true,
+        }
+    }
+
+    #[test]
+    fn test_slippage_thresholds() {
+        let whirlpool = whirlpool_at_tick_zero(1_000_000_000);
+
+        let params = SwapParams::exact_in(1_000, true);
+        let exact_in = SwapInstructionArgs::from_quote(&whirlpool, params, &quote(1_000, 995), 0.5).unwrap();
+        assert_eq!(exact_in.other_amount_threshold, 990);
+        assert_eq!(exact_in.sqrt_price_limit, MIN_SQRT_PRICE_X64);
+
+        let params = SwapParams::exact_out(995, true);
+        let exact_out = SwapInstructionArgs::from_quote(&whirlpool, params, &quote(1_001, 995), 1.0).unwrap();
+        // 1_001 * 1.01 = 1_011.01, rounded up
+        assert_eq!(exact_out.other_amount_threshold, 1_012);
+
+        // A limit on the wrong side of the current price is rejected
+        let mut params = SwapParams::exact_in(1_000, true);
+        params.sqrt_price_limit = (1u128 << 64) + 1;
+        assert!(SwapInstructionArgs::from_quote(&whirlpool, params, &quote(1_000, 995), 0.5).is_err());
+    }
+
+    #[test]
+    fn test_swap_instruction_layout() {
+        let whirlpool = whirlpool_at_tick_zero(1_000_000_000);
+        let address = Pubkey::new_unique();
+        let accounts = SwapAccounts::new(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let builder = SwapInstructionBuilder::new(&whirlpool, address, accounts);
+        let args = SwapInstructionArgs {
+            amount: 1_000,
+            other_amount_threshold: 990,
+            sqrt_price_limit: MIN_SQRT_PRICE_X64,
+            amount_specified_is_input: true,
+            a_to_b: true,
+        };
+
+        let swap = builder.swap(&args);
+        assert_eq!(swap.accounts.len(), 11);
+        assert_eq!(swap.data.len(), 42);
+        assert_eq!(swap.data[..8], SWAP_DISCRIMINATOR);
+        assert_eq!(swap.data[8..16], 1_000u64.to_le_bytes());
+        assert_eq!(swap.data[16..24], 990u64.to_le_bytes());
+        assert_eq!(swap.data[40..], [1, 1]);
+        assert!(swap.accounts[1].is_signer);
+        assert_eq!(swap.accounts[4].pubkey, whirlpool.token_vault_a);
+
+        // a_to_b walks down from the array holding tick 0
+        let width = TICK_ARRAY_SIZE as i32 * 64;
+        let expected: Vec<Pubkey> = [0, -width, -2 * width]
+            .iter()
+            .map(|&start| get_tick_array_address(&address, start, &builder.program_id))
+            .collect();
+        let tick_arrays: Vec<Pubkey> = swap.accounts[7..10].iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(tick_arrays, expected);
+        assert_eq!(swap.accounts[10].pubkey, get_oracle_address(&address, &builder.program_id));
+
+        let swap_v2 = builder.swap_v2(&args);
+        assert_eq!(swap_v2.accounts.len(), 15);
+        assert_eq!(swap_v2.data[..8], SWAP_V2_DISCRIMINATOR);
+        assert_eq!(swap_v2.data.len(), 43);
+        assert_eq!(swap_v2.accounts[5].pubkey, whirlpool.token_mint_a);
+        assert_eq!(swap_v2.accounts[11..14], swap.accounts[7..10]);
+    }
+}
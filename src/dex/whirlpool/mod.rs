@@ -1,6 +1,14 @@
 
 pub mod constants;
+pub mod instruction;
+pub mod pool;
+pub mod position;
+pub mod snapshot;
 pub mod state;
+pub mod swap;
+pub mod swap_math;
+pub mod tick_math;
+pub mod u256_math;
 
 use crate::dex::whirlpool::state::{Whirlpool, TICK_ARRAY_SIZE};
 use crate::dex::whirlpool::constants::{MAX_TICK_INDEX, MIN_TICK_INDEX};
@@ -73,6 +81,20 @@ pub fn get_tick_array_address(
     Pubkey::find_program_address(seeds, program_id).0
 }
 
+/// Address of the `Position` account for a position NFT mint.
+pub fn get_position_address(position_mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    let seeds = &[b"position", position_mint.as_ref()];
+
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+/// Address of a Whirlpool's `Oracle` account, passed to every swap.
+pub fn get_oracle_address(whirlpool: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    let seeds = &[b"oracle", whirlpool.as_ref()];
+
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
 pub fn update_tick_array_accounts_for_onchain(
     whirlpool: &Whirlpool,
     whirlpool_pk: &Pubkey,
@@ -112,3 +134,17 @@ pub fn update_tick_array_accounts_for_onchain(
     ];
     tick_array_pks
 }
+
+/// Start indexes of the tick arrays a swap in either direction could touch, current array first.
+pub fn derive_surrounding_tick_array_starts(curr_tick: i32, tick_spacing: u16) -> Vec<i32> {
+    let (a0, a1, a2) = derive_tick_array_start_indexes(curr_tick, tick_spacing, true);
+    let (b0, b1, b2) = derive_tick_array_start_indexes(curr_tick, tick_spacing, false);
+
+    let mut starts = vec![derive_start_tick(curr_tick, tick_spacing)];
+    for start in [Some(a0), a1, a2, Some(b0), b1, b2].into_iter().flatten() {
+        if !starts.contains(&start) {
+            starts.push(start);
+        }
+    }
+    starts
+}
@@ -0,0 +1,83 @@
+// src/dex/whirlpool/pool.rs - DexPool implementation for Whirlpool
+
+use std::any::Any;
+use solana_program::pubkey::Pubkey;
+use crate::data::price_data::whirlpool_math;
+use crate::dex::pool::{AccountDataMap, DexError, DexKind, DexPool, DexQuote};
+use crate::dex::whirlpool::constants::{self, FEE_RATE_MUL_VALUE};
+use crate::dex::whirlpool::state::{TickArray, Whirlpool};
+use crate::dex::whirlpool::swap::{swap_quote, SwapParams};
+use crate::dex::whirlpool::{derive_surrounding_tick_array_starts, get_tick_array_address};
+
+impl DexPool for Whirlpool {
+    fn kind(&self) -> DexKind {
+        DexKind::Whirlpool
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn token_mints(&self) -> (Pubkey, Pubkey) {
+        (self.token_mint_a, self.token_mint_b)
+    }
+
+    fn mid_price(&self, decimals_a: u8, decimals_b: u8) -> f64 {
+        whirlpool_math::calculate_price_from_whirlpool(self, decimals_a, decimals_b)
+    }
+
+    fn fee_rate(&self) -> f64 {
+        self.fee_rate as f64 / FEE_RATE_MUL_VALUE as f64
+    }
+
+    fn liquidity(&self) -> f64 {
+        self.liquidity as f64
+    }
+
+    fn tick_index(&self) -> Option<i32> {
+        Some(self.tick_current_index)
+    }
+
+    fn fee_growth_global(&self) -> Option<(u128, u128)> {
+        Some((self.fee_growth_global_a, self.fee_growth_global_b))
+    }
+
+    fn required_accounts(&self, pool_address: &Pubkey) -> Vec<Pubkey> {
+        let program_id = constants::whirlpool_profram_id();
+        derive_surrounding_tick_array_starts(self.tick_current_index, self.tick_spacing)
+            .into_iter()
+            .map(|start| get_tick_array_address(pool_address, start, &program_id))
+            .collect()
+    }
+
+    fn quote_swap(
+        &self,
+        pool_address: &Pubkey,
+        amount: u64,
+        a_to_b: bool,
+        amount_specified_is_input: bool,
+        accounts: &AccountDataMap,
+    ) -> Result<DexQuote, DexError> {
+        // `accounts` holds the tick arrays listed by `required_accounts`; any that are missing
+        // only shorten how far the quote can walk
+        let tick_arrays: Vec<TickArray> = accounts
+            .values()
+            .filter_map(|data| TickArray::try_deserialize(data).ok())
+            .filter(|tick_array| tick_array.whirlpool == *pool_address)
+            .collect();
+
+        let params = SwapParams {
+            amount,
+            sqrt_price_limit: 0,
+            amount_specified_is_input,
+            a_to_b,
+        };
+        let quote = swap_quote(self, &tick_arrays, params)?;
+
+        Ok(DexQuote {
+            amount_in: quote.amount_in,
+            amount_out: quote.amount_out,
+            fee_amount: quote.fee_amount,
+        })
+    }
+}
@@ -0,0 +1,264 @@
+// src/dex/whirlpool/position.rs - Value of a Whirlpool liquidity position
+
+use solana_program::pubkey::Pubkey;
+use crate::dex::whirlpool::derive_start_tick;
+use crate::dex::whirlpool::state::{Position, Tick, TickArray, Whirlpool, NUM_REWARDS};
+use crate::dex::whirlpool::swap::SwapError;
+use crate::dex::whirlpool::swap_math::{get_amount_delta_a, get_amount_delta_b};
+use crate::dex::whirlpool::tick_math::sqrt_price_from_tick_index;
+use crate::dex::whirlpool::u256_math::{mul_div, mul_shift};
+
+/// What a position would pay out if it were closed now. Raw token units.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PositionValue {
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub fee_owed_a: u64,
+    pub fee_owed_b: u64,
+    pub reward_owed: [u64; NUM_REWARDS],
+}
+
+/// Start indexes of the tick arrays holding the position's lower and upper ticks.
+pub fn position_tick_array_starts(position: &Position, tick_spacing: u16) -> (i32, i32) {
+    (
+        derive_start_tick(position.tick_lower_index, tick_spacing),
+        derive_start_tick(position.tick_upper_index, tick_spacing),
+    )
+}
+
+/// Finds the position's lower and upper ticks in the loaded tick arrays.
+pub fn find_position_ticks<'a>(
+    position: &Position,
+    tick_spacing: u16,
+    tick_arrays: &'a [TickArray],
+) -> Option<(&'a Tick, &'a Tick)> {
+    let find = |tick_index| tick_arrays.iter().find_map(|array| array.tick(tick_index, tick_spacing));
+    Some((find(position.tick_lower_index)?, find(position.tick_upper_index)?))
+}
+
+/// Token amounts the position's liquidity is worth at the pool's current price, rounded down
+/// as on withdrawal.
+pub fn position_token_amounts(whirlpool: &Whirlpool, position: &Position) -> Result<(u64, u64), SwapError> {
+    let sqrt_price_lower = sqrt_price_from_tick_index(position.tick_lower_index);
+    let sqrt_price_upper = sqrt_price_from_tick_index(position.tick_upper_index);
+    let liquidity = position.liquidity;
+
+    if whirlpool.tick_current_index < position.tick_lower_index {
+        Ok((get_amount_delta_a(sqrt_price_lower, sqrt_price_upper, liquidity, false)?, 0))
+    } else if whirlpool.tick_current_index < position.tick_upper_index {
+        Ok((
+            get_amount_delta_a(whirlpool.sqrt_price, sqrt_price_upper, liquidity, false)?,
+            get_amount_delta_b(sqrt_price_lower, whirlpool.sqrt_price, liquidity, false)?,
+        ))
+    } else {
+        Ok((0, get_amount_delta_b(sqrt_price_lower, sqrt_price_upper, liquidity, false)?))
+    }
+}
+
+// Growth inside [lower, upper] from the global growth and the growth outside each tick.
+// Uninitialized ticks count all growth as below them, as the program does.
+fn growth_inside(
+    tick_current_index: i32,
+    position: &Position,
+    lower: (bool, u128),
+    upper: (bool, u128),
+    global: u128,
+) -> u128 {
+    let (lower_initialized, lower_outside) = lower;
+    let (upper_initialized, upper_outside) = upper;
+
+    let below = if !lower_initialized {
+        global
+    } else if tick_current_index < position.tick_lower_index {
+        global.wrapping_sub(lower_outside)
+    } else {
+        lower_outside
+    };
+    let above = if !upper_initialized {
+        0
+    } else if tick_current_index < position.tick_upper_index {
+        upper_outside
+    } else {
+        global.wrapping_sub(upper_outside)
+    };
+
+    global.wrapping_sub(below).wrapping_sub(above)
+}
+
+// Tokens earned by `liquidity` since `checkpoint`. Growth is Q64.64 per unit of liquidity.
+fn owed_since(liquidity: u128, growth_inside: u128, checkpoint: u128) -> u64 {
+    mul_shift(liquidity, growth_inside.wrapping_sub(checkpoint), 64)
+        .and_then(|owed| u64::try_from(owed).ok())
+        .unwrap_or(0)
+}
+
+/// Fees the position can collect, including those accrued since it was last touched.
+pub fn collectible_fees(whirlpool: &Whirlpool, position: &Position, tick_lower: &Tick, tick_upper: &Tick) -> (u64, u64) {
+    let inside_a = growth_inside(
+        whirlpool.tick_current_index,
+        position,
+        (tick_lower.initialized, tick_lower.fee_growth_outside_a),
+        (tick_upper.initialized, tick_upper.fee_growth_outside_a),
+        whirlpool.fee_growth_global_a,
+    );
+    let inside_b = growth_inside(
+        whirlpool.tick_current_index,
+        position,
+        (tick_lower.initialized, tick_lower.fee_growth_outside_b),
+        (tick_upper.initialized, tick_upper.fee_growth_outside_b),
+        whirlpool.fee_growth_global_b,
+    );
+
+    (
+        position.fee_owed_a.wrapping_add(owed_since(position.liquidity, inside_a, position.fee_growth_checkpoint_a)),
+        position.fee_owed_b.wrapping_add(owed_since(position.liquidity, inside_b, position.fee_growth_checkpoint_b)),
+    )
+}
+
+/// Global reward growths brought forward to `now` (unix seconds) from the pool's last update.
+pub fn next_reward_growths_global(whirlpool: &Whirlpool, now: u64) -> [u128; NUM_REWARDS] {
+    let mut growths = whirlpool.reward_infos.map(|info| info.growth_global_x64);
+    if whirlpool.liquidity == 0 || now <= whirlpool.reward_last_updated_timestamp {
+        return growths;
+    }
+
+    let elapsed = (now - whirlpool.reward_last_updated_timestamp) as u128;
+    for (growth, info) in growths.iter_mut().zip(&whirlpool.reward_infos) {
+        if info.mint == Pubkey::default() {
+            continue;
+        }
+        let delta = mul_div(elapsed, info.emissions_per_second_x64, whirlpool.liquidity, false).unwrap_or(0);
+        *growth = growth.wrapping_add(delta);
+    }
+    growths
+}
+
+/// Rewards the position can collect as of `now` (unix seconds).
+pub fn collectible_rewards(
+    whirlpool: &Whirlpool,
+    position: &Position,
+    tick_lower: &Tick,
+    tick_upper: &Tick,
+    now: u64,
+) -> [u64; NUM_REWARDS] {
+    let globals = next_reward_growths_global(whirlpool, now);
+
+    let mut owed = [0u64; NUM_REWARDS];
+    for i in 0..NUM_REWARDS {
+        let reward = &position.reward_infos[i];
+        if whirlpool.reward_infos[i].mint == Pubkey::default() {
+            owed[i] = reward.amount_owed;
+            continue;
+        }
+        let inside = growth_inside(
+            whirlpool.tick_current_index,
+            position,
+            (tick_lower.initialized, tick_lower.reward_growths_outside[i]),
+            (tick_upper.initialized, tick_upper.reward_growths_outside[i]),
+            globals[i],
+        );
+        owed[i] = reward
+            .amount_owed
+            .wrapping_add(owed_since(position.liquidity, inside, reward.growth_inside_checkpoint));
+    }
+    owed
+}
+
+/// Token amounts, fees and rewards of a position. `tick_lower` and `tick_upper` are the
+/// position's boundary ticks, e.g. from `find_position_ticks`.
+pub fn position_value(
+    whirlpool: &Whirlpool,
+    position: &Position,
+    tick_lower: &Tick,
+    tick_upper: &Tick,
+    now: u64,
+) -> Result<PositionValue, SwapError> {
+    let (amount_a, amount_b) = position_token_amounts(whirlpool, position)?;
+    let (fee_owed_a, fee_owed_b) = collectible_fees(whirlpool, position, tick_lower, tick_upper);
+
+    Ok(PositionValue {
+        amount_a,
+        amount_b,
+        fee_owed_a,
+        fee_owed_b,
+        reward_owed: collectible_rewards(whirlpool, position, tick_lower, tick_upper, now),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::whirlpool::state::{whirlpool_at_tick_zero, PositionRewardInfo, TICK_ARRAY_SIZE};
+
+    const LIQUIDITY: u128 = 1_000_000_000;
+
+    fn position(tick_lower_index: i32, tick_upper_index: i32) -> Position {
+        Position {
+            whirlpool: Pubkey::default(),
+            position_mint: Pubkey::default(),
+            liquidity: LIQUIDITY,
+            tick_lower_index,
+            tick_upper_index,
+            fee_growth_checkpoint_a: 0,
+            fee_owed_a: 0,
+            fee_growth_checkpoint_b: 0,
+            fee_owed_b: 5,
+            reward_infos: [PositionRewardInfo::default(); NUM_REWARDS],
+        }
+    }
+
+    #[test]
+    fn test_token_amounts_by_range() {
+        let whirlpool = whirlpool_at_tick_zero(LIQUIDITY);
+
+        // In range: both tokens, symmetric around price 1
+        let (a, b) = position_token_amounts(&whirlpool, &position(-640, 640)).unwrap();
+        let expected = LIQUIDITY as f64 * (1.0 - 1.0001f64.powf(-320.0));
+        assert!((a as f64 - expected).abs() <= 1.0);
+        assert!(b.abs_diff(a) <= 1);
+
+        // Above the price only token A, below it only token B
+        let (a, b) = position_token_amounts(&whirlpool, &position(64, 640)).unwrap();
+        assert!(a > 0 && b == 0);
+        let (a, b) = position_token_amounts(&whirlpool, &position(-640, -64)).unwrap();
+        assert!(a == 0 && b > 0);
+    }
+
+    #[test]
+    fn test_fees_and_rewards() {
+        let mut whirlpool = whirlpool_at_tick_zero(LIQUIDITY);
+        whirlpool.reward_last_updated_timestamp = 1_000;
+        whirlpool.fee_growth_global_a = 1u128 << 60;
+        whirlpool.reward_infos[0].mint = Pubkey::new_unique();
+        whirlpool.reward_infos[0].emissions_per_second_x64 = 1u128 << 64;
+
+        let position = position(-640, 640);
+        let mut tick_arrays = vec![
+            TickArray {
+                start_tick_index: -5632,
+                ticks: [Tick::default(); TICK_ARRAY_SIZE],
+                whirlpool: Pubkey::default(),
+            },
+            TickArray {
+                start_tick_index: 0,
+                ticks: [Tick::default(); TICK_ARRAY_SIZE],
+                whirlpool: Pubkey::default(),
+            },
+        ];
+        tick_arrays[0].ticks[78].initialized = true;
+        tick_arrays[1].ticks[10].initialized = true;
+
+        assert_eq!(position_tick_array_starts(&position, 64), (-5632, 0));
+        let (lower, upper) = find_position_ticks(&position, 64, &tick_arrays).unwrap();
+        assert!(lower.initialized && upper.initialized);
+
+        // All fee growth is inside the range: L / 16 of token A
+        let value = position_value(&whirlpool, &position, lower, upper, 1_100).unwrap();
+        assert_eq!(value.fee_owed_a, 62_500_000);
+        assert_eq!(value.fee_owed_b, 5);
+
+        // One token per second for 100s, and the position holds all the liquidity
+        assert!((99..=100).contains(&value.reward_owed[0]));
+        assert_eq!(value.reward_owed[1], 0);
+    }
+}
@@ -0,0 +1,48 @@
+// src/dex/whirlpool/snapshot.rs - A Whirlpool and its tick arrays read at one slot
+
+use solana_program::pubkey::Pubkey;
+use crate::dex::whirlpool::state::{TickArray, Whirlpool};
+use crate::dex::whirlpool::swap::{swap_quote, SwapError, SwapParams, SwapQuote};
+
+/// A pool and the tick arrays around its current price, all read at the same slot, so
+/// quotes and depth built from it agree with the chain at `slot`. Read-only once built.
+#[derive(Clone, Debug)]
+pub struct PoolSnapshot {
+    address: Pubkey,
+    slot: u64,
+    whirlpool: Whirlpool,
+    tick_arrays: Vec<TickArray>,
+}
+
+impl PoolSnapshot {
+    /// Callers vouch that every account was read at `slot`.
+    pub(crate) fn new(address: Pubkey, slot: u64, whirlpool: Whirlpool, tick_arrays: Vec<TickArray>) -> Self {
+        Self {
+            address,
+            slot,
+            whirlpool,
+            tick_arrays,
+        }
+    }
+
+    pub fn address(&self) -> Pubkey {
+        self.address
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+
+    pub fn whirlpool(&self) -> &Whirlpool {
+        &self.whirlpool
+    }
+
+    /// Initialized tick arrays a swap from the snapshot's price could touch, current array first.
+    pub fn tick_arrays(&self) -> &[TickArray] {
+        &self.tick_arrays
+    }
+
+    pub fn quote(&self, params: SwapParams) -> Result<SwapQuote, SwapError> {
+        swap_quote(&self.whirlpool, &self.tick_arrays, params)
+    }
+}
@@ -39,6 +39,10 @@ pub struct Whirlpool {
 
 impl Whirlpool {
     pub const LEN: usize = 8 + 261 + 384;
+
+    /// Byte offsets of the mints in account data, for `memcmp` filters.
+    pub const TOKEN_MINT_A_OFFSET: usize = 101;
+    pub const TOKEN_MINT_B_OFFSET: usize = 181;
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -67,7 +71,13 @@ pub struct Tick {
     pub reward_growths_outside: [u128; NUM_REWARDS],
 }
 
+impl TickArray {
+    pub const LEN: usize = 8 + 4 + TICK_ARRAY_SIZE * Tick::LEN + 32;
+}
+
 impl Tick {
+    pub const LEN: usize = 1 + 16 + 16 + 16 + 16 + 16 * NUM_REWARDS;
+
     pub fn check_is_valid_start_tick(tick_index: i32, tick_spacing: u16) -> bool {
         tick_index % (tick_spacing as i32 * TICK_ARRAY_SIZE as i32) == 0
     }
@@ -75,6 +85,34 @@ impl Tick {
 
 pub const TICK_ARRAY_SIZE: usize = 88;
 
+#[derive(Copy, Clone, Default, Debug)]
+pub struct PositionRewardInfo {
+    pub growth_inside_checkpoint: u128,
+    pub amount_owed: u64,
+}
+
+/// A liquidity position, owned by whoever holds its position NFT.
+#[derive(Clone, Copy, Debug)]
+pub struct Position {
+    pub whirlpool: Pubkey,     // 32
+    pub position_mint: Pubkey, // 32
+    pub liquidity: u128,       // 16
+
+    pub tick_lower_index: i32, // 4
+    pub tick_upper_index: i32, // 4
+
+    pub fee_growth_checkpoint_a: u128, // 16
+    pub fee_owed_a: u64,               // 8
+    pub fee_growth_checkpoint_b: u128, // 16
+    pub fee_owed_b: u64,               // 8
+
+    pub reward_infos: [PositionRewardInfo; NUM_REWARDS], // 72
+}
+
+impl Position {
+    pub const LEN: usize = 8 + 136 + 72;
+}
+
 use std::io::{Error, ErrorKind, Result};
 
 impl Whirlpool {
@@ -226,4 +264,215 @@ impl Whirlpool {
             reward_infos,
         })
     }
+}
+
+impl TickArray {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for TickArray"));
+        }
+
+        let data = &data[8..];
+
+        let mut offset = 0;
+
+        let mut start_tick_index_bytes = [0u8; 4];
+        start_tick_index_bytes.copy_from_slice(&data[offset..offset+4]);
+        let start_tick_index = i32::from_le_bytes(start_tick_index_bytes);
+        offset += 4;
+
+        let mut ticks = [Tick::default(); TICK_ARRAY_SIZE];
+        for tick in ticks.iter_mut() {
+            *tick = Tick::deserialize(&data[offset..offset+Tick::LEN]);
+            offset += Tick::LEN;
+        }
+
+        let mut whirlpool_bytes = [0u8; 32];
+        whirlpool_bytes.copy_from_slice(&data[offset..offset+32]);
+        let whirlpool = Pubkey::new_from_array(whirlpool_bytes);
+
+        Ok(TickArray {
+            start_tick_index,
+            ticks,
+            whirlpool,
+        })
+    }
+}
+
+impl TickArray {
+    /// The tick at `tick_index`, if it falls inside this array.
+    pub fn tick(&self, tick_index: i32, tick_spacing: u16) -> Option<&Tick> {
+        let offset = tick_index - self.start_tick_index;
+        let spacing = tick_spacing as i32;
+        if offset < 0 || offset % spacing != 0 {
+            return None;
+        }
+        self.ticks.get((offset / spacing) as usize)
+    }
+}
+
+impl Tick {
+    // Callers guarantee `data` holds at least `Tick::LEN` bytes
+    fn deserialize(data: &[u8]) -> Self {
+        let read_u128 = |offset: usize| {
+            let mut bytes = [0u8; 16];
+            bytes.copy_from_slice(&data[offset..offset+16]);
+            u128::from_le_bytes(bytes)
+        };
+
+        let initialized = data[0] != 0;
+        let liquidity_net = read_u128(1) as i128;
+        let liquidity_gross = read_u128(17);
+        let fee_growth_outside_a = read_u128(33);
+        let fee_growth_outside_b = read_u128(49);
+
+        let mut reward_growths_outside = [0u128; NUM_REWARDS];
+        for (i, growth) in reward_growths_outside.iter_mut().enumerate() {
+            *growth = read_u128(65 + i * 16);
+        }
+
+        Tick {
+            initialized,
+            liquidity_net,
+            liquidity_gross,
+            fee_growth_outside_a,
+            fee_growth_outside_b,
+            reward_growths_outside,
+        }
+    }
+}
+impl Position {
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for Position"));
+        }
+
+        let data = &data[8..];
+        let read_pubkey = |offset: usize| {
+            let mut bytes = [0u8; 32];
+            bytes.copy_from_slice(&data[offset..offset+32]);
+            Pubkey::new_from_array(bytes)
+        };
+        let read_u128 = |offset: usize| {
+            let mut bytes = [0u8; 16];
+            bytes.copy_from_slice(&data[offset..offset+16]);
+            u128::from_le_bytes(bytes)
+        };
+        let read_u64 = |offset: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data[offset..offset+8]);
+            u64::from_le_bytes(bytes)
+        };
+        let read_i32 = |offset: usize| {
+            i32::from_le_bytes([data[offset], data[offset+1], data[offset+2], data[offset+3]])
+        };
+
+        let mut reward_infos = [PositionRewardInfo::default(); NUM_REWARDS];
+        for (i, reward) in reward_infos.iter_mut().enumerate() {
+            let offset = 136 + i * 24;
+            reward.growth_inside_checkpoint = read_u128(offset);
+            reward.amount_owed = read_u64(offset + 16);
+        }
+
+        Ok(Position {
+            whirlpool: read_pubkey(0),
+            position_mint: read_pubkey(32),
+            liquidity: read_u128(64),
+            tick_lower_index: read_i32(80),
+            tick_upper_index: read_i32(84),
+            fee_growth_checkpoint_a: read_u128(88),
+            fee_owed_a: read_u64(104),
+            fee_growth_checkpoint_b: read_u128(112),
+            fee_owed_b: read_u64(128),
+            reward_infos,
+        })
+    }
+}
+
+/// A pool at price 1 (tick 0) with tick spacing 64 and a 0.3% fee, for tests across the crate.
+#[cfg(test)]
+pub(crate) fn whirlpool_at_tick_zero(liquidity: u128) -> Whirlpool {
+    let reward_info = WhirlpoolRewardInfo {
+        mint: Pubkey::default(),
+        vault: Pubkey::default(),
+        authority: Pubkey::default(),
+        emissions_per_second_x64: 0,
+        growth_global_x64: 0,
+    };
+    Whirlpool {
+        whirlpools_config: Pubkey::default(),
+        whirlpool_bump: [0],
+        tick_spacing: 64,
+        tick_spacing_seed: [64, 0],
+        fee_rate: 3000,
+        protocol_fee_rate: 0,
+        liquidity,
+        sqrt_price: 1u128 << 64,
+        tick_current_index: 0,
+        protocol_fee_owed_a: 0,
+        protocol_fee_owed_b: 0,
+        token_mint_a: Pubkey::new_unique(),
+        token_vault_a: Pubkey::new_unique(),
+        fee_growth_global_a: 0,
+        token_mint_b: Pubkey::new_unique(),
+        token_vault_b: Pubkey::new_unique(),
+        fee_growth_global_b: 0,
+        reward_last_updated_timestamp: 0,
+        reward_infos: [reward_info; NUM_REWARDS],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_array_deserialize() {
+        let mut data = vec![0u8; TickArray::LEN];
+        data[8..12].copy_from_slice(&(-5632i32).to_le_bytes());
+
+        // Tick 3: initialized, liquidity_net = -42, fee_growth_outside_b = 7
+        let tick_offset = 12 + 3 * Tick::LEN;
+        data[tick_offset] = 1;
+        data[tick_offset+1..tick_offset+17].copy_from_slice(&(-42i128).to_le_bytes());
+        data[tick_offset+49..tick_offset+65].copy_from_slice(&7u128.to_le_bytes());
+
+        let whirlpool = Pubkey::new_unique();
+        data[TickArray::LEN-32..].copy_from_slice(whirlpool.as_ref());
+
+        let tick_array = TickArray::try_deserialize(&data).unwrap();
+        assert_eq!(TickArray::LEN, 9988);
+        assert_eq!(tick_array.start_tick_index, -5632);
+        assert!(tick_array.ticks[3].initialized);
+        assert_eq!(tick_array.ticks[3].liquidity_net, -42);
+        assert_eq!(tick_array.ticks[3].fee_growth_outside_b, 7);
+        assert!(!tick_array.ticks[4].initialized);
+        assert_eq!(tick_array.whirlpool, whirlpool);
+
+        assert!(TickArray::try_deserialize(&data[..100]).is_err());
+    }
+
+    #[test]
+    fn test_position_deserialize() {
+        let mut data = vec![0u8; Position::LEN];
+        let whirlpool = Pubkey::new_unique();
+        data[8..40].copy_from_slice(whirlpool.as_ref());
+        data[72..88].copy_from_slice(&5_000u128.to_le_bytes());
+        data[88..92].copy_from_slice(&(-128i32).to_le_bytes());
+        data[92..96].copy_from_slice(&256i32.to_le_bytes());
+        data[136..144].copy_from_slice(&11u64.to_le_bytes());
+        // Third reward: amount_owed = 3
+        data[144+2*24+16..144+2*24+24].copy_from_slice(&3u64.to_le_bytes());
+
+        let position = Position::try_deserialize(&data).unwrap();
+        assert_eq!(Position::LEN, 216);
+        assert_eq!(position.whirlpool, whirlpool);
+        assert_eq!(position.liquidity, 5_000);
+        assert_eq!(position.tick_lower_index, -128);
+        assert_eq!(position.tick_upper_index, 256);
+        assert_eq!(position.fee_owed_b, 11);
+        assert_eq!(position.reward_infos[2].amount_owed, 3);
+
+        assert!(Position::try_deserialize(&data[..200]).is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,506 @@
+// Swap quote engine that replays the Whirlpool program's swap loop over
+// loaded TickArrays.
+
+use crate::dex::whirlpool::constants::{
+    MAX_SQRT_PRICE_X64, MAX_TICK_INDEX, MIN_SQRT_PRICE_X64, MIN_TICK_INDEX,
+    PROTOCOL_FEE_RATE_MUL_VALUE,
+};
+use crate::dex::whirlpool::derive_tick_array_start_indexes;
+use crate::dex::whirlpool::state::{Tick, TickArray, Whirlpool, TICK_ARRAY_SIZE};
+use crate::dex::whirlpool::swap_math::compute_swap_step;
+use crate::dex::whirlpool::tick_math::{sqrt_price_from_tick_index, tick_index_from_sqrt_price};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SwapError {
+    #[error("Swap amount must be greater than zero")]
+    ZeroAmount,
+
+    #[error("Invalid sqrt price limit for swap direction: {0}")]
+    InvalidSqrtPriceLimit(u128),
+
+    #[error("Tick array starting at {0} is not loaded")]
+    MissingTickArray(i32),
+
+    #[error("Swap ran past the end of the loaded tick arrays")]
+    TickArraySequenceExhausted,
+
+    #[error("Liquidity overflow when crossing tick {0}")]
+    LiquidityOverflow(i32),
+
+    #[error("Token amount exceeds u64")]
+    TokenMaxExceeded,
+
+    #[error("Sqrt price out of bounds")]
+    SqrtPriceOutOfBounds,
+
+    #[error("Arithmetic overflow in swap math")]
+    MathOverflow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapParams {
+    pub amount: u64,
+    /// Price at which the swap stops. `0` means no limit in the swap direction.
+    pub sqrt_price_limit: u128,
+    pub amount_specified_is_input: bool,
+    pub a_to_b: bool,
+}
+
+impl SwapParams {
+    pub fn exact_in(amount: u64, a_to_b: bool) -> Self {
+        Self {
+            amount,
+            sqrt_price_limit: 0,
+            amount_specified_is_input: true,
+            a_to_b,
+        }
+    }
+
+    pub fn exact_out(amount: u64, a_to_b: bool) -> Self {
+        Self {
+            amount,
+            sqrt_price_limit: 0,
+            amount_specified_is_input: false,
+            a_to_b,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Input token amount, fees included.
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub end_sqrt_price: u128,
+    pub end_tick_index: i32,
+    pub end_liquidity: u128,
+    /// Total fee charged on the input token, protocol share included.
+    pub fee_amount: u64,
+    pub protocol_fee: u64,
+    pub ticks_crossed: u32,
+    pub a_to_b: bool,
+    pub amount_specified_is_input: bool,
+}
+
+/// Pool state the swap loop mutates. Fee rates use the on-chain denominators.
+#[derive(Clone, Copy, Debug)]
+pub struct SwapPoolState {
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    pub liquidity: u128,
+    pub tick_spacing: u16,
+    pub fee_rate: u16,
+    pub protocol_fee_rate: u16,
+}
+
+impl From<&Whirlpool> for SwapPoolState {
+    fn from(whirlpool: &Whirlpool) -> Self {
+        Self {
+            sqrt_price: whirlpool.sqrt_price,
+            tick_current_index: whirlpool.tick_current_index,
+            liquidity: whirlpool.liquidity,
+            tick_spacing: whirlpool.tick_spacing,
+            fee_rate: whirlpool.fee_rate,
+            protocol_fee_rate: whirlpool.protocol_fee_rate,
+        }
+    }
+}
+
+/// Up to three consecutive tick arrays in swap direction, like the on-chain `SwapTickSequence`.
+pub struct TickArraySequence<'a> {
+    arrays: Vec<&'a TickArray>,
+    tick_spacing: u16,
+}
+
+impl<'a> TickArraySequence<'a> {
+    /// Picks the arrays the program would use for a swap starting at `tick_current_index`.
+    /// The first array must be loaded; the sequence stops at the first missing array after it.
+    pub fn from_loaded(
+        loaded: &'a [TickArray],
+        tick_current_index: i32,
+        tick_spacing: u16,
+        a_to_b: bool,
+    ) -> Result<Self, SwapError> {
+        let (start_0, start_1, start_2) =
+            derive_tick_array_start_indexes(tick_current_index, tick_spacing, a_to_b);
+        let find = |start: i32| loaded.iter().find(|ta| ta.start_tick_index == start);
+
+        let first = find(start_0).ok_or(SwapError::MissingTickArray(start_0))?;
+        let mut arrays = vec![first];
+        for start in [start_1, start_2].into_iter().flatten() {
+            match find(start) {
+                Some(tick_array) => arrays.push(tick_array),
+                None => break,
+            }
+        }
+
+        Ok(Self { arrays, tick_spacing })
+    }
+
+    fn ticks_in_array(&self) -> i32 {
+        TICK_ARRAY_SIZE as i32 * self.tick_spacing as i32
+    }
+
+    fn in_search_range(&self, array_index: usize, tick_index: i32, shifted: bool) -> bool {
+        let start = self.arrays[array_index].start_tick_index;
+        let shift = if shifted { self.tick_spacing as i32 } else { 0 };
+        tick_index >= start - shift && tick_index < start + self.ticks_in_array() - shift
+    }
+
+    fn next_init_tick_in_array(
+        &self,
+        array_index: usize,
+        tick_index: i32,
+        a_to_b: bool,
+    ) -> Result<Option<i32>, SwapError> {
+        if !self.in_search_range(array_index, tick_index, !a_to_b) {
+            return Err(SwapError::TickArraySequenceExhausted);
+        }
+
+        let tick_array = self.arrays[array_index];
+        let spacing = self.tick_spacing as i32;
+        let mut offset = (tick_index - tick_array.start_tick_index).div_euclid(spacing);
+
+        // Searching right never returns a tick in the current offset
+        if !a_to_b {
+            offset += 1;
+        }
+
+        while offset >= 0 && offset < TICK_ARRAY_SIZE as i32 {
+            if tick_array.ticks[offset as usize].initialized {
+                return Ok(Some(tick_array.start_tick_index + offset * spacing));
+            }
+            offset = if a_to_b { offset - 1 } else { offset + 1 };
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the array index and tick index of the next initialized tick, or the edge of
+    /// the last loaded array when none is left.
+    pub fn next_initialized_tick(
+        &self,
+        tick_index: i32,
+        start_array_index: usize,
+        a_to_b: bool,
+    ) -> Result<(usize, i32), SwapError> {
+        let mut search_index = tick_index;
+        let mut array_index = start_array_index;
+
+        loop {
+            // A crossed tick can leave the current tick just past this array's range
+            if !self.in_search_range(array_index, search_index, !a_to_b)
+                && array_index + 1 < self.arrays.len()
+                && self.in_search_range(array_index + 1, search_index, !a_to_b)
+            {
+                array_index += 1;
+            }
+
+            if let Some(next) = self.next_init_tick_in_array(array_index, search_index, a_to_b)? {
+                return Ok((array_index, next));
+            }
+
+            let start = self.arrays[array_index].start_tick_index;
+
+            // The outermost arrays stop at the price bounds rather than the array edge
+            if a_to_b && start <= MIN_TICK_INDEX {
+                return Ok((array_index, MIN_TICK_INDEX));
+            }
+            if !a_to_b && start + self.ticks_in_array() > MAX_TICK_INDEX {
+                return Ok((array_index, MAX_TICK_INDEX));
+            }
+
+            if array_index + 1 == self.arrays.len() {
+                let edge = if a_to_b {
+                    start
+                } else {
+                    start + self.ticks_in_array() - 1
+                };
+                return Ok((array_index, edge));
+            }
+
+            search_index = if a_to_b {
+                start - 1
+            } else {
+                start + self.ticks_in_array() - 1
+            };
+            array_index += 1;
+        }
+    }
+
+    fn tick(&self, array_index: usize, tick_index: i32) -> Option<&Tick> {
+        let tick_array = self.arrays[array_index];
+        let offset = tick_index - tick_array.start_tick_index;
+        if offset % self.tick_spacing as i32 != 0 {
+            return None;
+        }
+        tick_array.ticks.get((offset / self.tick_spacing as i32) as usize)
+    }
+}
+
+pub(crate) fn resolve_sqrt_price_limit(params: &SwapParams, sqrt_price: u128) -> Result<u128, SwapError> {
+    let limit = match (params.sqrt_price_limit, params.a_to_b) {
+        (0, true) => MIN_SQRT_PRICE_X64,
+        (0, false) => MAX_SQRT_PRICE_X64,
+        (limit, _) => limit,
+    };
+
+    let valid = if params.a_to_b {
+        limit >= MIN_SQRT_PRICE_X64 && limit < sqrt_price
+    } else {
+        limit <= MAX_SQRT_PRICE_X64 && limit > sqrt_price
+    };
+
+    if valid {
+        Ok(limit)
+    } else {
+        Err(SwapError::InvalidSqrtPriceLimit(limit))
+    }
+}
+
+/// Quotes a swap against a Whirlpool using whichever of `tick_arrays` lie on the swap path.
+pub fn swap_quote(
+    whirlpool: &Whirlpool,
+    tick_arrays: &[TickArray],
+    params: SwapParams,
+) -> Result<SwapQuote, SwapError> {
+    let sequence = TickArraySequence::from_loaded(
+        tick_arrays,
+        whirlpool.tick_current_index,
+        whirlpool.tick_spacing,
+        params.a_to_b,
+    )?;
+    simulate_swap(SwapPoolState::from(whirlpool), &sequence, params)
+}
+
+pub fn simulate_swap(
+    pool: SwapPoolState,
+    sequence: &TickArraySequence,
+    params: SwapParams,
+) -> Result<SwapQuote, SwapError> {
+    if params.amount == 0 {
+        return Err(SwapError::ZeroAmount);
+    }
+
+    let a_to_b = params.a_to_b;
+    let exact_in = params.amount_specified_is_input;
+    let sqrt_price_limit = resolve_sqrt_price_limit(&params, pool.sqrt_price)?;
+
+    let mut amount_remaining = params.amount;
+    let mut amount_calculated = 0u64;
+    let mut curr_sqrt_price = pool.sqrt_price;
+    let mut curr_tick_index = pool.tick_current_index;
+    let mut curr_liquidity = pool.liquidity;
+    let mut curr_array_index = 0usize;
+    let mut fee_amount = 0u64;
+    let mut protocol_fee = 0u64;
+    let mut ticks_crossed = 0u32;
+
+    while amount_remaining > 0 && sqrt_price_limit != curr_sqrt_price {
+        let (next_array_index, next_tick_index) =
+            sequence.next_initialized_tick(curr_tick_index, curr_array_index, a_to_b)?;
+
+        let next_tick_sqrt_price = sqrt_price_from_tick_index(next_tick_index);
+        let sqrt_price_target = if a_to_b {
+            next_tick_sqrt_price.max(sqrt_price_limit)
+        } else {
+            next_tick_sqrt_price.min(sqrt_price_limit)
+        };
+
+        let step = compute_swap_step(
+            amount_remaining,
+            pool.fee_rate as u32,
+            curr_liquidity,
+            curr_sqrt_price,
+            sqrt_price_target,
+            exact_in,
+            a_to_b,
+        )?;
+
+        if exact_in {
+            amount_remaining = amount_remaining
+                .checked_sub(step.amount_in)
+                .and_then(|rest| rest.checked_sub(step.fee_amount))
+                .ok_or(SwapError::MathOverflow)?;
+            amount_calculated = amount_calculated
+                .checked_add(step.amount_out)
+                .ok_or(SwapError::MathOverflow)?;
+        } else {
+            amount_remaining = amount_remaining
+                .checked_sub(step.amount_out)
+                .ok_or(SwapError::MathOverflow)?;
+            amount_calculated = amount_calculated
+                .checked_add(step.amount_in)
+                .and_then(|total| total.checked_add(step.fee_amount))
+                .ok_or(SwapError::MathOverflow)?;
+        }
+
+        let step_protocol_fee = if pool.protocol_fee_rate > 0 {
+            (step.fee_amount as u128 * pool.protocol_fee_rate as u128 / PROTOCOL_FEE_RATE_MUL_VALUE) as u64
+        } else {
+            0
+        };
+        protocol_fee += step_protocol_fee;
+        fee_amount += step.fee_amount;
+
+        if step.next_sqrt_price == next_tick_sqrt_price {
+            if let Some(tick) = sequence.tick(next_array_index, next_tick_index) {
+                if tick.initialized {
+                    let liquidity_net = if a_to_b {
+                        -tick.liquidity_net
+                    } else {
+                        tick.liquidity_net
+                    };
+                    curr_liquidity = if liquidity_net >= 0 {
+                        curr_liquidity.checked_add(liquidity_net as u128)
+                    } else {
+                        curr_liquidity.checked_sub(liquidity_net.unsigned_abs())
+                    }
+                    .ok_or(SwapError::LiquidityOverflow(next_tick_index))?;
+                    ticks_crossed += 1;
+                }
+            }
+
+            curr_tick_index = if a_to_b {
+                next_tick_index - 1
+            } else {
+                next_tick_index
+            };
+        } else if step.next_sqrt_price != curr_sqrt_price {
+            curr_tick_index = tick_index_from_sqrt_price(step.next_sqrt_price);
+        }
+
+        curr_sqrt_price = step.next_sqrt_price;
+        curr_array_index = next_array_index;
+    }
+
+    let amount_specified_used = params.amount - amount_remaining;
+    let (amount_a, amount_b) = if a_to_b == exact_in {
+        (amount_specified_used, amount_calculated)
+    } else {
+        (amount_calculated, amount_specified_used)
+    };
+    let (amount_in, amount_out) = if a_to_b {
+        (amount_a, amount_b)
+    } else {
+        (amount_b, amount_a)
+    };
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        amount_a,
+        amount_b,
+        end_sqrt_price: curr_sqrt_price,
+        end_tick_index: curr_tick_index,
+        end_liquidity: curr_liquidity,
+        fee_amount,
+        protocol_fee,
+        ticks_crossed,
+        a_to_b,
+        amount_specified_is_input: exact_in,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::whirlpool::state::whirlpool_at_tick_zero;
+    use solana_program::pubkey::Pubkey;
+
+    const TICK_SPACING: u16 = 64;
+    const LIQUIDITY: u128 = 1_000_000_000_000;
+
+    fn test_whirlpool(tick_current_index: i32) -> Whirlpool {
+        Whirlpool {
+            protocol_fee_rate: 300,
+            sqrt_price: sqrt_price_from_tick_index(tick_current_index),
+            tick_current_index,
+            ..whirlpool_at_tick_zero(LIQUIDITY)
+        }
+    }
+
+    fn empty_tick_array(start_tick_index: i32) -> TickArray {
+        TickArray {
+            start_tick_index,
+            ticks: [Tick::default(); TICK_ARRAY_SIZE],
+            whirlpool: Pubkey::default(),
+        }
+    }
+
+    // Liquidity starts at -5632 and ends at 5632 around a pool sitting at tick 0
+    fn range_tick_arrays() -> Vec<TickArray> {
+        let width = TICK_ARRAY_SIZE as i32 * TICK_SPACING as i32;
+        let mut arrays: Vec<TickArray> = (-3..3).map(|i| empty_tick_array(i * width)).collect();
+        let lower = &mut arrays[2].ticks[0];
+        lower.initialized = true;
+        lower.liquidity_net = LIQUIDITY as i128;
+        lower.liquidity_gross = LIQUIDITY;
+        let upper = &mut arrays[4].ticks[0];
+        upper.initialized = true;
+        upper.liquidity_net = -(LIQUIDITY as i128);
+        upper.liquidity_gross = LIQUIDITY;
+        arrays
+    }
+
+    #[test]
+    fn test_exact_in_within_single_range() {
+        let whirlpool = test_whirlpool(0);
+        let arrays = range_tick_arrays();
+
+        let quote = swap_quote(&whirlpool, &arrays, SwapParams::exact_in(1_000_000, true)).unwrap();
+
+        assert_eq!(quote.amount_in, 1_000_000);
+        assert_eq!(quote.amount_a, 1_000_000);
+        assert_eq!(quote.fee_amount, 3000);
+        assert_eq!(quote.protocol_fee, 90);
+        assert_eq!(quote.ticks_crossed, 0);
+        // At price 1.0 the output is the fee-adjusted input minus a tiny price impact
+        assert!(quote.amount_out < 997_000 && quote.amount_out > 996_000);
+        assert!(quote.end_sqrt_price < whirlpool.sqrt_price);
+        assert_eq!(quote.end_tick_index, tick_index_from_sqrt_price(quote.end_sqrt_price));
+    }
+
+    #[test]
+    fn test_exact_out_matches_exact_in() {
+        let whirlpool = test_whirlpool(0);
+        let arrays = range_tick_arrays();
+
+        let exact_in = swap_quote(&whirlpool, &arrays, SwapParams::exact_in(5_000_000, false)).unwrap();
+        let exact_out =
+            swap_quote(&whirlpool, &arrays, SwapParams::exact_out(exact_in.amount_out, false)).unwrap();
+
+        assert_eq!(exact_out.amount_out, exact_in.amount_out);
+        assert!(exact_out.amount_in <= exact_in.amount_in);
+        assert!(exact_in.amount_in - exact_out.amount_in <= 1);
+    }
+
+    #[test]
+    fn test_swap_crosses_range_end() {
+        let whirlpool = test_whirlpool(0);
+        let arrays = range_tick_arrays();
+
+        // Far more token A than the range can absorb: liquidity drops to zero at the lower tick
+        let result = swap_quote(&whirlpool, &arrays, SwapParams::exact_in(u64::MAX / 2, true));
+        assert_eq!(result, Err(SwapError::TickArraySequenceExhausted));
+
+        let limit = sqrt_price_from_tick_index(-6000);
+        let params = SwapParams {
+            sqrt_price_limit: limit,
+            ..SwapParams::exact_in(u64::MAX / 2, true)
+        };
+        let quote = swap_quote(&whirlpool, &arrays, params).unwrap();
+        assert_eq!(quote.ticks_crossed, 1);
+        assert_eq!(quote.end_liquidity, 0);
+        assert_eq!(quote.end_sqrt_price, limit);
+    }
+
+    #[test]
+    fn test_missing_first_tick_array() {
+        let whirlpool = test_whirlpool(0);
+        let result = swap_quote(&whirlpool, &[], SwapParams::exact_in(1, true));
+        assert_eq!(result, Err(SwapError::MissingTickArray(0)));
+    }
+}
@@ -0,0 +1,271 @@
+// Single-step swap math (token deltas and sqrt price stepping) in Q64.64,
+// following the rounding rules of the Whirlpool program.
+
+use crate::dex::whirlpool::constants::{FEE_RATE_MUL_VALUE, MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64};
+use crate::dex::whirlpool::swap::SwapError;
+use crate::dex::whirlpool::u256_math::{mul_div, U256};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapStepComputation {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub next_sqrt_price: u128,
+    pub fee_amount: u64,
+}
+
+fn increasing_price_order(sqrt_price_0: u128, sqrt_price_1: u128) -> (u128, u128) {
+    if sqrt_price_0 > sqrt_price_1 {
+        (sqrt_price_1, sqrt_price_0)
+    } else {
+        (sqrt_price_0, sqrt_price_1)
+    }
+}
+
+// Token A delta between two prices, unbounded so callers can tell "exceeds u64" apart from errors:
+// liquidity * (sqrt_upper - sqrt_lower) * 2^64 / (sqrt_upper * sqrt_lower)
+fn amount_delta_a_u128(
+    sqrt_price_0: u128,
+    sqrt_price_1: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<u128, SwapError> {
+    let (sqrt_price_lower, sqrt_price_upper) = increasing_price_order(sqrt_price_0, sqrt_price_1);
+    let sqrt_price_diff = sqrt_price_upper - sqrt_price_lower;
+
+    let numerator = U256::mul_u128(liquidity, sqrt_price_diff)
+        .checked_shl_64()
+        .ok_or(SwapError::MathOverflow)?;
+    let denominator = U256::mul_u128(sqrt_price_upper, sqrt_price_lower);
+
+    numerator
+        .div_round(denominator, round_up)
+        .and_then(|quotient| quotient.try_into_u128())
+        .ok_or(SwapError::MathOverflow)
+}
+
+// Token B delta between two prices: liquidity * (sqrt_upper - sqrt_lower) / 2^64
+fn amount_delta_b_u128(
+    sqrt_price_0: u128,
+    sqrt_price_1: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<u128, SwapError> {
+    let (sqrt_price_lower, sqrt_price_upper) = increasing_price_order(sqrt_price_0, sqrt_price_1);
+    let product = U256::mul_u128(liquidity, sqrt_price_upper - sqrt_price_lower);
+    let should_round = round_up && product.lo & (u64::MAX as u128) != 0;
+
+    (product >> 64)
+        .try_into_u128()
+        .and_then(|value| value.checked_add(should_round as u128))
+        .ok_or(SwapError::MathOverflow)
+}
+
+pub fn get_amount_delta_a(
+    sqrt_price_0: u128,
+    sqrt_price_1: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<u64, SwapError> {
+    let amount = amount_delta_a_u128(sqrt_price_0, sqrt_price_1, liquidity, round_up)?;
+    u64::try_from(amount).map_err(|_| SwapError::TokenMaxExceeded)
+}
+
+pub fn get_amount_delta_b(
+    sqrt_price_0: u128,
+    sqrt_price_1: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<u64, SwapError> {
+    let amount = amount_delta_b_u128(sqrt_price_0, sqrt_price_1, liquidity, round_up)?;
+    u64::try_from(amount).map_err(|_| SwapError::TokenMaxExceeded)
+}
+
+pub fn get_next_sqrt_price(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u64,
+    amount_specified_is_input: bool,
+    a_to_b: bool,
+) -> Result<u128, SwapError> {
+    if amount_specified_is_input == a_to_b {
+        get_next_sqrt_price_from_a_round_up(sqrt_price, liquidity, amount, amount_specified_is_input)
+    } else {
+        get_next_sqrt_price_from_b_round_down(sqrt_price, liquidity, amount, amount_specified_is_input)
+    }
+}
+
+// sqrt_price' = (liquidity * sqrt_price) / (liquidity +/- amount * sqrt_price), rounded up
+fn get_next_sqrt_price_from_a_round_up(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u64,
+    amount_specified_is_input: bool,
+) -> Result<u128, SwapError> {
+    if amount == 0 {
+        return Ok(sqrt_price);
+    }
+
+    let product = U256::mul_u128(sqrt_price, amount as u128);
+    let numerator = U256::mul_u128(liquidity, sqrt_price)
+        .checked_shl_64()
+        .ok_or(SwapError::MathOverflow)?;
+    let liquidity_shift_left = U256::from_u128(liquidity) << 64;
+
+    let denominator = if amount_specified_is_input {
+        liquidity_shift_left.checked_add(product)
+    } else {
+        liquidity_shift_left.checked_sub(product)
+    }
+    .ok_or(SwapError::MathOverflow)?;
+
+    let price = numerator
+        .div_round(denominator, true)
+        .and_then(|quotient| quotient.try_into_u128())
+        .ok_or(SwapError::MathOverflow)?;
+
+    if !(MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&price) {
+        return Err(SwapError::SqrtPriceOutOfBounds);
+    }
+
+    Ok(price)
+}
+
+// sqrt_price' = sqrt_price +/- amount / liquidity, rounded down
+fn get_next_sqrt_price_from_b_round_down(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u64,
+    amount_specified_is_input: bool,
+) -> Result<u128, SwapError> {
+    if liquidity == 0 {
+        return Err(SwapError::MathOverflow);
+    }
+
+    let amount_x64 = (amount as u128) << 64;
+    let delta = amount_x64 / liquidity
+        + (!amount_specified_is_input && !amount_x64.is_multiple_of(liquidity)) as u128;
+
+    if amount_specified_is_input {
+        sqrt_price.checked_add(delta)
+    } else {
+        sqrt_price.checked_sub(delta)
+    }
+    .ok_or(SwapError::SqrtPriceOutOfBounds)
+}
+
+// The "fixed" side is the token whose amount the caller specified.
+fn amount_fixed_delta(
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_specified_is_input: bool,
+    a_to_b: bool,
+) -> Result<u128, SwapError> {
+    if a_to_b == amount_specified_is_input {
+        amount_delta_a_u128(sqrt_price_current, sqrt_price_target, liquidity, amount_specified_is_input)
+    } else {
+        amount_delta_b_u128(sqrt_price_current, sqrt_price_target, liquidity, amount_specified_is_input)
+    }
+}
+
+fn amount_unfixed_delta(
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_specified_is_input: bool,
+    a_to_b: bool,
+) -> Result<u64, SwapError> {
+    if a_to_b == amount_specified_is_input {
+        get_amount_delta_b(sqrt_price_current, sqrt_price_target, liquidity, !amount_specified_is_input)
+    } else {
+        get_amount_delta_a(sqrt_price_current, sqrt_price_target, liquidity, !amount_specified_is_input)
+    }
+}
+
+/// Computes one step of a swap from `sqrt_price_current` towards `sqrt_price_target`
+/// within a single liquidity range. `fee_rate` is in hundredths of a basis point.
+pub fn compute_swap_step(
+    amount_remaining: u64,
+    fee_rate: u32,
+    liquidity: u128,
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    amount_specified_is_input: bool,
+    a_to_b: bool,
+) -> Result<SwapStepComputation, SwapError> {
+    let fee_rate = fee_rate as u128;
+
+    let amount_calc = if amount_specified_is_input {
+        mul_div(amount_remaining as u128, FEE_RATE_MUL_VALUE - fee_rate, FEE_RATE_MUL_VALUE, false)
+            .ok_or(SwapError::MathOverflow)? as u64
+    } else {
+        amount_remaining
+    };
+
+    let mut fixed_delta = amount_fixed_delta(
+        sqrt_price_current,
+        sqrt_price_target,
+        liquidity,
+        amount_specified_is_input,
+        a_to_b,
+    )?;
+
+    let next_sqrt_price = if fixed_delta <= amount_calc as u128 {
+        sqrt_price_target
+    } else {
+        get_next_sqrt_price(
+            sqrt_price_current,
+            liquidity,
+            amount_calc,
+            amount_specified_is_input,
+            a_to_b,
+        )?
+    };
+
+    let is_max_swap = next_sqrt_price == sqrt_price_target;
+
+    let unfixed_delta = amount_unfixed_delta(
+        sqrt_price_current,
+        next_sqrt_price,
+        liquidity,
+        amount_specified_is_input,
+        a_to_b,
+    )?;
+
+    // Re-derive the fixed amount when the step stops short of the target
+    if !is_max_swap {
+        fixed_delta = amount_fixed_delta(
+            sqrt_price_current,
+            next_sqrt_price,
+            liquidity,
+            amount_specified_is_input,
+            a_to_b,
+        )?;
+    }
+    let fixed_delta = u64::try_from(fixed_delta).map_err(|_| SwapError::TokenMaxExceeded)?;
+
+    let (amount_in, mut amount_out) = if amount_specified_is_input {
+        (fixed_delta, unfixed_delta)
+    } else {
+        (unfixed_delta, fixed_delta)
+    };
+
+    if !amount_specified_is_input && amount_out > amount_remaining {
+        amount_out = amount_remaining;
+    }
+
+    let fee_amount = if amount_specified_is_input && !is_max_swap {
+        amount_remaining - amount_in
+    } else {
+        mul_div(amount_in as u128, fee_rate, FEE_RATE_MUL_VALUE - fee_rate, true)
+            .and_then(|fee| u64::try_from(fee).ok())
+            .ok_or(SwapError::MathOverflow)?
+    };
+
+    Ok(SwapStepComputation {
+        amount_in,
+        amount_out,
+        next_sqrt_price,
+        fee_amount,
+    })
+}
@@ -0,0 +1,224 @@
+// Integer tick <-> sqrt price conversion, mirroring the Whirlpool program's
+// tick_math so that tick boundaries match the chain exactly.
+
+use crate::dex::whirlpool::constants::{
+    MAX_SQRT_PRICE_X64, MAX_TICK_INDEX, MIN_SQRT_PRICE_X64, MIN_TICK_INDEX,
+};
+use crate::dex::whirlpool::u256_math::mul_shift;
+
+const BIT_PRECISION: u32 = 14;
+const LOG_B_2_X32: i128 = 59543866431248i128;
+const LOG_B_P_ERR_MARGIN_LOWER_X64: i128 = 184467440737095516i128; // 0.01
+const LOG_B_P_ERR_MARGIN_UPPER_X64: i128 = 15793534762490258745i128; // 2^-precision / log_2_b + 0.01
+
+pub fn is_tick_in_bounds(tick: i32) -> bool {
+    (MIN_TICK_INDEX..=MAX_TICK_INDEX).contains(&tick)
+}
+
+pub fn is_sqrt_price_in_bounds(sqrt_price_x64: u128) -> bool {
+    (MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&sqrt_price_x64)
+}
+
+/// Like `sqrt_price_from_tick_index`, but rejects ticks outside the program's range.
+pub fn try_sqrt_price_from_tick_index(tick: i32) -> Option<u128> {
+    is_tick_in_bounds(tick).then(|| sqrt_price_from_tick_index(tick))
+}
+
+/// Like `tick_index_from_sqrt_price`, but rejects prices outside the program's range.
+pub fn try_tick_index_from_sqrt_price(sqrt_price_x64: u128) -> Option<i32> {
+    is_sqrt_price_in_bounds(sqrt_price_x64).then(|| tick_index_from_sqrt_price(sqrt_price_x64))
+}
+
+/// Rounds a tick down to the nearest multiple of `tick_spacing`.
+pub fn floor_to_initializable_tick(tick: i32, tick_spacing: u16) -> i32 {
+    let spacing = tick_spacing as i32;
+    tick.div_euclid(spacing) * spacing
+}
+
+/// Returns sqrt(1.0001^tick) as a Q64.64 value.
+///
+/// `tick` must lie within `MIN_TICK_INDEX..=MAX_TICK_INDEX`.
+pub fn sqrt_price_from_tick_index(tick: i32) -> u128 {
+    if tick >= 0 {
+        get_sqrt_price_positive_tick(tick)
+    } else {
+        get_sqrt_price_negative_tick(tick)
+    }
+}
+
+// Positive ticks are computed in Q32.96 and shifted down to Q64.64 at the end.
+fn get_sqrt_price_positive_tick(tick: i32) -> u128 {
+    const FACTORS: [(i32, u128); 18] = [
+        (2, 79236085330515764027303304731),
+        (4, 79244008939048815603706035061),
+        (8, 79259858533276714757314932305),
+        (16, 79291567232598584799939703904),
+        (32, 79355022692464371645785046466),
+        (64, 79482085999252804386437311141),
+        (128, 79736823300114093921829183326),
+        (256, 80248749790819932309965073892),
+        (512, 81282483887344747381513967011),
+        (1024, 83390072131320151908154831281),
+        (2048, 87770609709833776024991924138),
+        (4096, 97234110755111693312479820773),
+        (8192, 119332217159966728226237229890),
+        (16384, 179736315981702064433883588727),
+        (32768, 407748233172238350107850275304),
+        (65536, 2098478828474011932436660412517),
+        (131072, 55581415166113811149459800483533),
+        (262144, 38992368544603139932233054999993551),
+    ];
+
+    let mut ratio: u128 = if tick & 1 != 0 {
+        79232123823359799118286999567
+    } else {
+        79228162514264337593543950336
+    };
+
+    for (bit, factor) in FACTORS {
+        if tick & bit != 0 {
+            ratio = mul_shift(ratio, factor, 96).expect("sqrt price ratio fits in u128");
+        }
+    }
+
+    ratio >> 32
+}
+
+fn get_sqrt_price_negative_tick(tick: i32) -> u128 {
+    const FACTORS: [(i32, u128); 18] = [
+        (2, 18444899583751176498),
+        (4, 18443055278223354162),
+        (8, 18439367220385604838),
+        (16, 18431993317065449817),
+        (32, 18417254355718160513),
+        (64, 18387811781193591352),
+        (128, 18329067761203520168),
+        (256, 18212142134806087854),
+        (512, 17980523815641551639),
+        (1024, 17526086738831147013),
+        (2048, 16651378430235024244),
+        (4096, 15030750278693429944),
+        (8192, 12247334978882834399),
+        (16384, 8131365268884726200),
+        (32768, 3584323654723342297),
+        (65536, 696457651847595233),
+        (131072, 26294789957452057),
+        (262144, 37481735321082),
+    ];
+
+    let abs_tick = tick.abs();
+
+    let mut ratio: u128 = if abs_tick & 1 != 0 {
+        18445821805675392311
+    } else {
+        18446744073709551616
+    };
+
+    for (bit, factor) in FACTORS {
+        if abs_tick & bit != 0 {
+            ratio = (ratio * factor) >> 64;
+        }
+    }
+
+    ratio
+}
+
+/// Returns the greatest tick whose sqrt price is less than or equal to `sqrt_price_x64`.
+///
+/// `sqrt_price_x64` must lie within `MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64`.
+pub fn tick_index_from_sqrt_price(sqrt_price_x64: u128) -> i32 {
+    // Integer part of log2(sqrt_price) from the most significant bit
+    let msb: u32 = 128 - sqrt_price_x64.leading_zeros() - 1;
+    let log2p_integer_x32 = (msb as i128 - 64) << 32;
+
+    // Fractional part by repeated squaring, starting from bit 63 (0.5 in Q64.64)
+    let mut bit: i128 = 0x8000_0000_0000_0000i128;
+    let mut precision = 0;
+    let mut log2p_fraction_x64 = 0;
+
+    let mut r = if msb >= 64 {
+        sqrt_price_x64 >> (msb - 63)
+    } else {
+        sqrt_price_x64 << (63 - msb)
+    };
+
+    while bit > 0 && precision < BIT_PRECISION {
+        r *= r;
+        let is_r_more_than_two = (r >> 127) as u32;
+        r >>= 63 + is_r_more_than_two;
+        log2p_fraction_x64 += bit * is_r_more_than_two as i128;
+        bit >>= 1;
+        precision += 1;
+    }
+
+    let log2p_fraction_x32 = log2p_fraction_x64 >> 32;
+    let log2p_x32 = log2p_integer_x32 + log2p_fraction_x32;
+
+    // Change of base from 2 to 1.0001^0.5
+    let logbp_x64 = log2p_x32 * LOG_B_2_X32;
+
+    let tick_low = ((logbp_x64 - LOG_B_P_ERR_MARGIN_LOWER_X64) >> 64) as i32;
+    let tick_high = ((logbp_x64 + LOG_B_P_ERR_MARGIN_UPPER_X64) >> 64) as i32;
+
+    if tick_low == tick_high {
+        tick_low
+    } else if sqrt_price_from_tick_index(tick_high) <= sqrt_price_x64 {
+        tick_high
+    } else {
+        tick_low
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_match_constants() {
+        assert_eq!(sqrt_price_from_tick_index(MAX_TICK_INDEX), MAX_SQRT_PRICE_X64);
+        assert_eq!(sqrt_price_from_tick_index(MIN_TICK_INDEX), MIN_SQRT_PRICE_X64);
+        assert_eq!(sqrt_price_from_tick_index(0), 1u128 << 64);
+        assert_eq!(tick_index_from_sqrt_price(MAX_SQRT_PRICE_X64), MAX_TICK_INDEX);
+        assert_eq!(tick_index_from_sqrt_price(MIN_SQRT_PRICE_X64), MIN_TICK_INDEX);
+
+        assert_eq!(try_sqrt_price_from_tick_index(MAX_TICK_INDEX + 1), None);
+        assert_eq!(try_sqrt_price_from_tick_index(MIN_TICK_INDEX - 1), None);
+        assert_eq!(try_tick_index_from_sqrt_price(MIN_SQRT_PRICE_X64 - 1), None);
+        assert_eq!(try_tick_index_from_sqrt_price(MAX_SQRT_PRICE_X64 + 1), None);
+    }
+
+    #[test]
+    fn test_round_trip_full_range() {
+        let mut prev_sqrt_price = 0u128;
+        for tick in MIN_TICK_INDEX..=MAX_TICK_INDEX {
+            let sqrt_price = sqrt_price_from_tick_index(tick);
+            assert!(sqrt_price > prev_sqrt_price, "not strictly increasing at tick {}", tick);
+            prev_sqrt_price = sqrt_price;
+
+            assert_eq!(tick_index_from_sqrt_price(sqrt_price), tick);
+            if tick < MAX_TICK_INDEX {
+                // Anything between two tick prices floors to the lower tick
+                assert_eq!(tick_index_from_sqrt_price(sqrt_price + 1), tick);
+            }
+            if tick > MIN_TICK_INDEX {
+                assert_eq!(tick_index_from_sqrt_price(sqrt_price - 1), tick - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_close_to_float_reference() {
+        for tick in (MIN_TICK_INDEX..=MAX_TICK_INDEX).step_by(997) {
+            let exact = sqrt_price_from_tick_index(tick) as f64;
+            let reference = 1.0001f64.powf(tick as f64 / 2.0) * (1u128 << 64) as f64;
+            assert!((exact - reference).abs() / reference < 1e-9, "tick {}", tick);
+        }
+    }
+
+    #[test]
+    fn test_floor_to_initializable_tick() {
+        assert_eq!(floor_to_initializable_tick(130, 64), 128);
+        assert_eq!(floor_to_initializable_tick(-1, 64), -64);
+        assert_eq!(floor_to_initializable_tick(-128, 64), -128);
+    }
+}
@@ -0,0 +1,211 @@
+// Minimal 256-bit unsigned arithmetic for the Q64.64 swap and tick math.
+//
+// Only the operations the Whirlpool math needs are implemented: widening
+// 128x128 multiplication, shifts, add/sub and division with remainder.
+
+use std::cmp::Ordering;
+use std::ops::{Shl, Shr};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct U256 {
+    pub hi: u128,
+    pub lo: u128,
+}
+
+const LO_MASK: u128 = u64::MAX as u128;
+
+impl U256 {
+    pub const ZERO: U256 = U256 { hi: 0, lo: 0 };
+
+    pub fn from_u128(value: u128) -> Self {
+        Self { hi: 0, lo: value }
+    }
+
+    /// Full-width product of two u128 values.
+    pub fn mul_u128(a: u128, b: u128) -> Self {
+        let (a_hi, a_lo) = (a >> 64, a & LO_MASK);
+        let (b_hi, b_lo) = (b >> 64, b & LO_MASK);
+
+        let lo_lo = a_lo * b_lo;
+        let hi_lo = a_hi * b_lo;
+        let lo_hi = a_lo * b_hi;
+        let hi_hi = a_hi * b_hi;
+
+        let cross = (lo_lo >> 64) + (hi_lo & LO_MASK) + (lo_hi & LO_MASK);
+        let lo = (cross << 64) | (lo_lo & LO_MASK);
+        let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+
+        Self { hi, lo }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.hi == 0 && self.lo == 0
+    }
+
+    /// Shifts left by 64 bits, returning `None` if any set bit would be lost.
+    pub fn checked_shl_64(self) -> Option<Self> {
+        if self.hi >> 64 != 0 {
+            None
+        } else {
+            Some(self << 64)
+        }
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (lo, carry) = self.lo.overflowing_add(other.lo);
+        let hi = self.hi.checked_add(other.hi)?.checked_add(carry as u128)?;
+        Some(Self { hi, lo })
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (lo, borrow) = self.lo.overflowing_sub(other.lo);
+        let hi = self.hi.checked_sub(other.hi)?.checked_sub(borrow as u128)?;
+        Some(Self { hi, lo })
+    }
+
+    fn bits(&self) -> u32 {
+        if self.hi != 0 {
+            256 - self.hi.leading_zeros()
+        } else {
+            128 - self.lo.leading_zeros()
+        }
+    }
+
+    fn bit(&self, index: u32) -> bool {
+        if index >= 128 {
+            (self.hi >> (index - 128)) & 1 == 1
+        } else {
+            (self.lo >> index) & 1 == 1
+        }
+    }
+
+    /// Returns `(quotient, remainder)`, or `None` when dividing by zero.
+    pub fn div_rem(self, divisor: Self) -> Option<(Self, Self)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        if self.hi == 0 && divisor.hi == 0 {
+            return Some((
+                Self::from_u128(self.lo / divisor.lo),
+                Self::from_u128(self.lo % divisor.lo),
+            ));
+        }
+        if self < divisor {
+            return Some((Self::ZERO, self));
+        }
+
+        // Plain shift-subtract long division over the significant bits.
+        let mut quotient = Self::ZERO;
+        let mut remainder = Self::ZERO;
+        for i in (0..self.bits()).rev() {
+            remainder = remainder << 1;
+            if self.bit(i) {
+                remainder.lo |= 1;
+            }
+            if remainder >= divisor {
+                remainder = remainder.checked_sub(divisor)?;
+                quotient = quotient << 1;
+                quotient.lo |= 1;
+            } else {
+                quotient = quotient << 1;
+            }
+        }
+        Some((quotient, remainder))
+    }
+
+    /// Division that optionally rounds the quotient up when there is a remainder.
+    pub fn div_round(self, divisor: Self, round_up: bool) -> Option<Self> {
+        let (quotient, remainder) = self.div_rem(divisor)?;
+        if round_up && !remainder.is_zero() {
+            quotient.checked_add(Self::from_u128(1))
+        } else {
+            Some(quotient)
+        }
+    }
+
+    pub fn try_into_u128(self) -> Option<u128> {
+        if self.hi == 0 {
+            Some(self.lo)
+        } else {
+            None
+        }
+    }
+}
+
+impl Shl<u32> for U256 {
+    type Output = Self;
+
+    fn shl(self, shift: u32) -> Self {
+        match shift {
+            0 => self,
+            1..=127 => Self {
+                hi: (self.hi << shift) | (self.lo >> (128 - shift)),
+                lo: self.lo << shift,
+            },
+            128..=255 => Self { hi: self.lo << (shift - 128), lo: 0 },
+            _ => Self::ZERO,
+        }
+    }
+}
+
+impl Shr<u32> for U256 {
+    type Output = Self;
+
+    fn shr(self, shift: u32) -> Self {
+        match shift {
+            0 => self,
+            1..=127 => Self {
+                hi: self.hi >> shift,
+                lo: (self.lo >> shift) | (self.hi << (128 - shift)),
+            },
+            128..=255 => Self { hi: 0, lo: self.hi >> (shift - 128) },
+            _ => Self::ZERO,
+        }
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.hi.cmp(&other.hi).then(self.lo.cmp(&other.lo))
+    }
+}
+
+/// `(a * b) >> shift`, returning `None` if the result does not fit in a u128.
+pub fn mul_shift(a: u128, b: u128, shift: u32) -> Option<u128> {
+    (U256::mul_u128(a, b) >> shift).try_into_u128()
+}
+
+/// `a * b / denominator` with optional rounding up.
+pub fn mul_div(a: u128, b: u128, denominator: u128, round_up: bool) -> Option<u128> {
+    U256::mul_u128(a, b)
+        .div_round(U256::from_u128(denominator), round_up)?
+        .try_into_u128()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_matches_u128_when_small() {
+        let product = U256::mul_u128(u64::MAX as u128, u64::MAX as u128);
+        assert_eq!(product.hi, 0);
+        assert_eq!(product.lo, (u64::MAX as u128) * (u64::MAX as u128));
+    }
+
+    #[test]
+    fn test_mul_div_round_trip() {
+        let a = u128::MAX / 3;
+        let b = 1u128 << 100;
+        assert_eq!(mul_div(a, b, b, false), Some(a));
+        assert_eq!(mul_div(7, 1, 2, false), Some(3));
+        assert_eq!(mul_div(7, 1, 2, true), Some(4));
+        assert_eq!(mul_div(u128::MAX, 2, 1, false), None);
+    }
+}
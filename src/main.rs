@@ -3,23 +3,118 @@
 use eframe::{egui, App, Frame};
 use egui::{CentralPanel, Context, SidePanel, TopBottomPanel, Color32};
 use std::collections::HashMap;
+use std::sync::Arc;
 use solana_program::pubkey::Pubkey;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 
-mod data;
-mod connection;
-mod ui;
-mod dex;
+use sss::{config, connection, data, dex, trading, ui, utils};
+#[cfg(feature = "wallet")]
+use sss::wallet;
 
-use data::price_data::{PriceHistory, PricePoint, whirlpool_math};
-use connection::websocket::{SolanaWebSocketClient, SolanaHttpClient, WhirlpoolUpdate};
+use data::price_data::{PriceHistory, PricePoint};
+use data::liquidity_depth::build_liquidity_depth;
+use data::portfolio::{fetch_portfolio, Portfolio};
+use data::priority_fees::{estimate_whirlpool_priority_fees, PriorityFeeEstimate};
+use connection::websocket::{
+    AccountEncoding, AccountOptions, Commitment, ConnectionState, PoolUpdate, ReconnectPolicy,
+    SolanaHttpClient, SolanaWebSocketClient, WebSocketEvent, WhirlpoolFilter,
+};
+use ui::add_pool::{AddPoolAction, AddPoolDialog};
 use ui::chart::{TradingChart, PriceTicker, MarketDepth};
+use ui::lp_analytics::{LpAnalyticsPanel, LpPoolContext};
+use ui::paper_trading::{PaperPoolContext, PaperTradingView};
+use ui::portfolio::PortfolioView;
+use ui::priority_fees::show_priority_fees;
+use dex::order_book::OrderBook;
+use dex::pool::{DexKind, DexPool};
+use dex::whirlpool::snapshot::PoolSnapshot;
 use dex::whirlpool::state::Whirlpool;
+use trading::orders::PaperAccount;
+use trading::paper::LivePool;
+use config::{AppConfig, PoolConfig};
+use utils::ArbitrageDetector;
+#[cfg(feature = "wallet")]
+use config::WalletConfig;
+#[cfg(feature = "wallet")]
+use connection::websocket::TransactionStatus;
+#[cfg(feature = "wallet")]
+use ui::live_swap::{LiveSwapPanel, LiveSwapRequest};
+#[cfg(feature = "wallet")]
+use wallet::{wait_for_confirmation, Wallet};
+
+// Quotes and depth come from the latest snapshot of the active pool, so it is refetched
+// often enough to track the streamed price
+const POOL_SNAPSHOT_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+// getRecentPrioritizationFees covers the last 150 slots (about a minute)
+const PRIORITY_FEE_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+// Fee-inclusive edge, in percent, one pool must have over another of the same pair to be shown
+const ARBITRAGE_MIN_PROFIT_PERCENT: f64 = 0.05;
+
+// Paper balances, fills and resting orders survive restarts here
+const PAPER_ACCOUNT_PATH: &str = "paper_account.json";
+
+// Connection and wallet settings
+const CONFIG_PATH: &str = "config.toml";
+
+// Results of background tasks, drained on the UI thread each frame
+enum AppEvent {
+    PoolSnapshot(Box<PoolSnapshot>),
+    PoolSnapshotFailed {
+        pool: Pubkey,
+        error: String,
+    },
+    Portfolio(Portfolio),
+    PortfolioFailed {
+        owner: Pubkey,
+        error: String,
+    },
+    PriorityFees {
+        pool: Pubkey,
+        estimate: Option<PriorityFeeEstimate>,
+    },
+    PriorityFeesFailed {
+        pool: Pubkey,
+        error: String,
+    },
+    PoolsFound {
+        pools: Vec<(Pubkey, Whirlpool)>,
+        decimals: HashMap<Pubkey, u8>,
+    },
+    PoolSearchFailed(String),
+    TokenDecimals(HashMap<Pubkey, u8>),
+    TokenDecimalsFailed(String),
+    WebSocketConnected(SolanaWebSocketClient),
+    WebSocketConnectFailed(String),
+    #[cfg(feature = "wallet")]
+    SwapSubmitted {
+        id: u64,
+        signature: String,
+    },
+    #[cfg(feature = "wallet")]
+    SwapStatus {
+        id: u64,
+        status: TransactionStatus,
+    },
+}
+
+// A pool update whose price triggered resting orders, waiting for a snapshot at least as new
+#[derive(Clone, Copy)]
+struct PendingOrderCheck {
+    slot: u64,
+    price: f64,
+    timestamp: u64,
+    snapshot_requested: bool,
+}
 
 #[derive(PartialEq)]
 enum ViewTab {
     Chart,
     Orders,
+    Portfolio,
     GraphArb,
 }
 
@@ -31,11 +126,21 @@ pub struct MyApp {
     trading_chart: TradingChart,
     price_tickers: HashMap<String, PriceTicker>,
     market_depth: MarketDepth,
+    portfolio_view: PortfolioView,
+    lp_analytics_panel: LpAnalyticsPanel,
+    paper_trading_view: PaperTradingView,
+    add_pool_dialog: AddPoolDialog,
+    #[cfg(feature = "wallet")]
+    live_swap_panel: LiveSwapPanel,
+    #[cfg(feature = "wallet")]
+    wallet: Option<Arc<Wallet>>,
     
     // Connection state
     ws_client: Option<SolanaWebSocketClient>,
     http_client: SolanaHttpClient,
     connected: bool,
+    // A connect task is running and will report back through `app_event_sender`
+    connecting: bool,
     connection_status: String,
     
     // Selected pools and tokens
@@ -46,11 +151,35 @@ pub struct MyApp {
     show_settings: bool,
     rpc_endpoint: String,
     ws_endpoint: String,
+    // Commitment and encoding of pool subscriptions
+    account_options: AccountOptions,
     auto_reconnect: bool,
+    discover_pools: bool,
     
     // Real-time updates
     last_update_time: std::time::Instant,
     update_counter: u64,
+
+    // Background tasks
+    app_event_sender: mpsc::UnboundedSender<AppEvent>,
+    app_event_receiver: mpsc::UnboundedReceiver<AppEvent>,
+
+    // Liquidity distribution
+    latest_whirlpools: HashMap<Pubkey, Whirlpool>,
+    pool_snapshots: HashMap<Pubkey, PoolSnapshot>,
+    pool_snapshots_requested_at: HashMap<Pubkey, Instant>,
+    pending_order_checks: HashMap<Pubkey, PendingOrderCheck>,
+    priority_fees: HashMap<Pubkey, PriorityFeeEstimate>,
+    priority_fees_requested_at: HashMap<Pubkey, Instant>,
+    order_books: HashMap<Pubkey, OrderBook>,
+
+    // Whirlpools streamed for the selected pairs without being configured, in discovery order
+    discovered_pools: Vec<Pubkey>,
+
+    // Cross-pool arbitrage between the selected pools, from their latest updates
+    latest_pools: HashMap<Pubkey, Arc<dyn DexPool>>,
+    arbitrage_detection: bool,
+    arbitrage_detector: ArbitrageDetector,
 }
 
 #[derive(Clone, Debug)]
@@ -59,6 +188,7 @@ pub struct PoolInfo {
     pub name: String,
     pub token_a: Pubkey,
     pub token_b: Pubkey,
+    pub dex: DexKind,
     pub dex_name: String,
     pub tick_spacing: u16,
     pub fee_rate: u16,
@@ -72,8 +202,22 @@ pub struct TokenMetadata {
     pub logo_uri: Option<String>,
 }
 
+#[cfg(feature = "wallet")]
+fn load_wallet(config: &WalletConfig) -> Option<Arc<Wallet>> {
+    let path = config.keypair_path.as_deref()?;
+    match Wallet::load(path) {
+        Ok(wallet) => Some(Arc::new(wallet)),
+        Err(e) => {
+            eprintln!("Live trading disabled: {}", e);
+            None
+        }
+    }
+}
+
 impl Default for MyApp {
     fn default() -> Self {
+        let (app_event_sender, app_event_receiver) = mpsc::unbounded_channel();
+        let config = AppConfig::load_or_default(CONFIG_PATH);
         let mut app = Self {
             active_tab: ViewTab::Chart,
             show_file_menu: false,
@@ -84,49 +228,65 @@ impl Default for MyApp {
                 asks: vec![],
                 spread: 0.0,
             },
+            portfolio_view: PortfolioView::default(),
+            lp_analytics_panel: LpAnalyticsPanel::default(),
+            paper_trading_view: PaperTradingView {
+                account: PaperAccount::load_or_default(PAPER_ACCOUNT_PATH),
+                ..PaperTradingView::default()
+            },
+            add_pool_dialog: AddPoolDialog::default(),
+            #[cfg(feature = "wallet")]
+            live_swap_panel: LiveSwapPanel::new(&config.wallet),
+            #[cfg(feature = "wallet")]
+            wallet: load_wallet(&config.wallet),
             ws_client: None,
-            http_client: SolanaHttpClient::new("https://api.mainnet-beta.solana.com".to_string()),
+            http_client: SolanaHttpClient::from(&config.connection),
             connected: false,
+            connecting: false,
             connection_status: "Disconnected".to_string(),
             selected_pools: vec![],
             token_metadata: HashMap::new(),
             show_settings: false,
-            rpc_endpoint: "https://api.mainnet-beta.solana.com".to_string(),
-            ws_endpoint: "wss://api.mainnet-beta.solana.com".to_string(),
+            rpc_endpoint: config.connection.rpc_endpoint.clone(),
+            ws_endpoint: config.connection.ws_endpoint.clone(),
+            account_options: AccountOptions::from(&config.connection),
             auto_reconnect: true,
+            discover_pools: false,
             last_update_time: std::time::Instant::now(),
             update_counter: 0,
+            app_event_sender,
+            app_event_receiver,
+            latest_whirlpools: HashMap::new(),
+            pool_snapshots: HashMap::new(),
+            pool_snapshots_requested_at: HashMap::new(),
+            pending_order_checks: HashMap::new(),
+            priority_fees: HashMap::new(),
+            priority_fees_requested_at: HashMap::new(),
+            order_books: HashMap::new(),
+            discovered_pools: Vec::new(),
+            latest_pools: HashMap::new(),
+            arbitrage_detection: config.trading.enable_arbitrage_detection,
+            arbitrage_detector: ArbitrageDetector::new(ARBITRAGE_MIN_PROFIT_PERCENT),
         };
 
-        // Add some default pools (popular Whirlpool pools)
-        app.add_default_pools();
+        app.add_known_tokens();
+        app.add_configured_pools(&config.pools);
         
         // Initialize price histories for each DEX
-        app.trading_chart.add_price_history("Whirlpool".to_string(), PriceHistory::new(10000, 300));
+        for dex in DexKind::ALL {
+            app.trading_chart.add_price_history(dex.to_string(), PriceHistory::new(10000, 300));
+        }
         
         app
     }
 }
 
 impl MyApp {
-    fn add_default_pools(&mut self) {
-        // SOL/USDC pool (most liquid)
-        if let (Ok(pool_pubkey), Ok(sol_mint), Ok(usdc_mint)) = (
-            Pubkey::from_str("HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ"),
+    fn add_known_tokens(&mut self) {
+        if let (Ok(sol_mint), Ok(usdc_mint)) = (
             Pubkey::from_str("So11111111111111111111111111111111111111112"),
             Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
         ) {
-            self.selected_pools.push(PoolInfo {
-                pubkey: pool_pubkey,
-                name: "SOL/USDC".to_string(),
-                token_a: sol_mint,
-                token_b: usdc_mint,
-                dex_name: "Whirlpool".to_string(),
-                tick_spacing: 64,
-                fee_rate: 300, // 0.3%
-            });
-
-            // Add token metadata
             self.token_metadata.insert(sol_mint, TokenMetadata {
                 symbol: "SOL".to_string(),
                 name: "Solana".to_string(),
@@ -143,32 +303,170 @@ impl MyApp {
         }
     }
 
-    async fn connect_websocket(&mut self) {
-        match SolanaWebSocketClient::new(&self.ws_endpoint).await {
-            Ok(mut client) => {
-                // Subscribe to all selected pools
-                for pool in &self.selected_pools {
-                    if let Err(e) = client.subscribe(pool.pubkey) {
-                        eprintln!("Failed to subscribe to {}: {}", pool.name, e);
+    // Enabled pools from the config, lowest priority number first. The first pool of each
+    // venue charts under the venue's series, later ones under their own name.
+    fn add_configured_pools(&mut self, pools: &[PoolConfig]) {
+        let mut pools: Vec<&PoolConfig> = pools.iter().filter(|pool| pool.enabled).collect();
+        pools.sort_by_key(|pool| pool.priority);
+
+        for pool in pools {
+            let (Ok(pubkey), Ok(token_a), Ok(token_b)) = (
+                Pubkey::from_str(&pool.pubkey),
+                Pubkey::from_str(&pool.token_a),
+                Pubkey::from_str(&pool.token_b),
+            ) else {
+                eprintln!("Skipping pool {}: invalid address in config", pool.name);
+                continue;
+            };
+            let dex_name = if self.selected_pools.iter().any(|p| p.dex_name == pool.dex.to_string()) {
+                self.trading_chart.add_price_history(pool.name.clone(), PriceHistory::new(10000, 300));
+                pool.name.clone()
+            } else {
+                pool.dex.to_string()
+            };
+            self.selected_pools.push(PoolInfo {
+                pubkey,
+                name: pool.name.clone(),
+                token_a,
+                token_b,
+                dex: pool.dex,
+                dex_name,
+                // Not in the config; only pools found on chain know them up front
+                tick_spacing: 0,
+                fee_rate: 0,
+            });
+        }
+
+        if self.selected_pools.is_empty() {
+            self.add_default_pools();
+        }
+        self.request_token_decimals();
+    }
+
+    fn add_default_pools(&mut self) {
+        // SOL/USDC pool (most liquid)
+        if let (Ok(pool_pubkey), Ok(sol_mint), Ok(usdc_mint)) = (
+            Pubkey::from_str("HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ"),
+            Pubkey::from_str("So11111111111111111111111111111111111111112"),
+            Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+        ) {
+            self.selected_pools.push(PoolInfo {
+                pubkey: pool_pubkey,
+                name: "SOL/USDC".to_string(),
+                token_a: sol_mint,
+                token_b: usdc_mint,
+                dex: DexKind::Whirlpool,
+                dex_name: DexKind::Whirlpool.to_string(),
+                tick_spacing: 64,
+                fee_rate: 300, // 0.3%
+            });
+        }
+    }
+
+    // Prices need the decimals of every selected pool's tokens
+    fn request_token_decimals(&mut self) {
+        let mut mints: Vec<Pubkey> = Vec::new();
+        for pool in &self.selected_pools {
+            for mint in [pool.token_a, pool.token_b] {
+                if !self.token_metadata.contains_key(&mint) && !mints.contains(&mint) {
+                    mints.push(mint);
+                }
+            }
+        }
+        if mints.is_empty() {
+            return;
+        }
+
+        let http_client = self.http_client.clone();
+        let sender = self.app_event_sender.clone();
+        tokio::spawn(async move {
+            let event = match http_client.get_mint_decimals(&mints).await {
+                Ok(decimals) => AppEvent::TokenDecimals(decimals),
+                Err(e) => AppEvent::TokenDecimalsFailed(e.to_string()),
+            };
+            let _ = sender.send(event);
+        });
+    }
+
+    // Connects in the background; the client comes back as an AppEvent
+    fn connect_websocket(&mut self) {
+        let policy = ReconnectPolicy {
+            auto_reconnect: self.auto_reconnect,
+            ..ReconnectPolicy::default()
+        };
+
+        let ws_endpoint = self.ws_endpoint.clone();
+        let http_client = Some(self.http_client.clone());
+        let sender = self.app_event_sender.clone();
+        self.connecting = true;
+        self.connection_status = "Connecting...".to_string();
+        tokio::spawn(async move {
+            let event = match SolanaWebSocketClient::with_http_client(&ws_endpoint, policy, http_client).await {
+                Ok(client) => AppEvent::WebSocketConnected(client),
+                Err(e) => AppEvent::WebSocketConnectFailed(e.to_string()),
+            };
+            let _ = sender.send(event);
+        });
+    }
+
+    fn on_websocket_connected(&mut self, client: SolanaWebSocketClient) {
+        self.connecting = false;
+        let client = client.with_account_options(self.account_options);
+        // Subscribe to all selected pools; the client replays these after a reconnect
+        for pool in &self.selected_pools {
+            if let Err(e) = client.subscribe(pool.pubkey, pool.dex) {
+                eprintln!("Failed to subscribe to {}: {}", pool.name, e);
+            }
+        }
+
+        // Every other Whirlpool of the same pairs, whatever its fee tier
+        if self.discover_pools {
+            let mut filters: Vec<WhirlpoolFilter> = Vec::new();
+            for pool in self.selected_pools.iter().filter(|p| p.dex == DexKind::Whirlpool) {
+                for filter in WhirlpoolFilter::pair(pool.token_a, pool.token_b) {
+                    if !filters.contains(&filter) {
+                        filters.push(filter);
                     }
                 }
-                
-                self.ws_client = Some(client);
-                self.connected = true;
-                self.connection_status = "Connected".to_string();
             }
-            Err(e) => {
-                eprintln!("Failed to connect WebSocket: {}", e);
-                self.connection_status = format!("Connection failed: {}", e);
+            for filter in filters {
+                if let Err(e) = client.subscribe_whirlpools(filter) {
+                    eprintln!("Failed to subscribe to Whirlpools {:?}: {}", filter, e);
+                }
             }
         }
+        
+        self.ws_client = Some(client);
+    }
+
+    fn process_connection_state(&mut self, state: ConnectionState) {
+        self.connected = state == ConnectionState::Live;
+        self.connection_status = match state {
+            ConnectionState::Connecting { .. } => "Connecting...".to_string(),
+            ConnectionState::Live => "Connected".to_string(),
+            ConnectionState::Degraded { attempt, retry_in, reason } => format!(
+                "Reconnecting in {:.1}s (attempt {}): {}",
+                retry_in.as_secs_f64(),
+                attempt,
+                reason
+            ),
+            ConnectionState::Failed { reason } => {
+                self.ws_client = None;
+                format!("Connection lost: {}", reason)
+            }
+            ConnectionState::Disconnected => {
+                self.ws_client = None;
+                "Disconnected".to_string()
+            }
+        };
     }
 
-    fn process_whirlpool_update(&mut self, update: WhirlpoolUpdate) {
+    fn process_pool_update(&mut self, update: PoolUpdate) {
         // Find the pool info for this update
         let pool_info = self.selected_pools
             .iter()
             .find(|p| p.pubkey == update.pubkey);
+        let mut update_price = None;
 
         if let Some(pool) = pool_info {
             // Get token metadata for price calculation
@@ -176,21 +474,15 @@ impl MyApp {
             let token_b_meta = self.token_metadata.get(&pool.token_b);
 
             if let (Some(meta_a), Some(meta_b)) = (token_a_meta, token_b_meta) {
-                // Calculate price
-                let price = whirlpool_math::calculate_price_from_whirlpool(
-                    &update.whirlpool,
+                // Create price point
+                let price_point = PricePoint::from_pool(
+                    update.pool.as_ref(),
+                    update.timestamp,
                     meta_a.decimals,
                     meta_b.decimals,
                 );
-
-                // Create price point
-                let price_point = PricePoint {
-                    timestamp: update.timestamp,
-                    price,
-                    volume: 0.0, // Would need to calculate from recent trades
-                    liquidity: update.whirlpool.liquidity as f64,
-                    tick: update.whirlpool.tick_current_index,
-                };
+                let price = price_point.price;
+                update_price = Some(price);
 
                 // Update chart
                 self.trading_chart.update_price_point(&pool.dex_name, price_point);
@@ -213,6 +505,394 @@ impl MyApp {
                 self.last_update_time = std::time::Instant::now();
             }
         }
+
+        if self.arbitrage_detection {
+            self.latest_pools.insert(update.pubkey, update.pool.clone());
+            self.detect_arbitrage();
+        }
+
+        // Whirlpool depth is built from tick arrays; order book venues carry their book in the update
+        if let Some(whirlpool) = update.pool.as_any().downcast_ref::<Whirlpool>() {
+            self.latest_whirlpools.insert(update.pubkey, *whirlpool);
+            self.request_pool_snapshot(update.pubkey);
+            self.request_priority_fees(update.pubkey, *whirlpool);
+            self.refresh_market_depth();
+            if let Some(price) = update_price {
+                self.queue_order_check(update.pubkey, update.slot, price, update.timestamp);
+            }
+        } else if let Some(book) = update.pool.order_book() {
+            self.order_books.insert(update.pubkey, book.clone());
+            self.refresh_market_depth();
+        }
+    }
+
+    // Compares each selected pool with the others quoting the same pair in the same order
+    fn detect_arbitrage(&mut self) {
+        let mut quoted = Vec::new();
+        for pool in &self.selected_pools {
+            let (Some(latest), Some(meta_a), Some(meta_b)) = (
+                self.latest_pools.get(&pool.pubkey),
+                self.token_metadata.get(&pool.token_a),
+                self.token_metadata.get(&pool.token_b),
+            ) else {
+                continue;
+            };
+            let pair = format!("{}/{}", meta_a.symbol, meta_b.symbol);
+            quoted.push((pair, latest.as_ref(), meta_a.decimals, meta_b.decimals));
+        }
+        let pools: Vec<(&str, &dyn DexPool, u8, u8)> = quoted
+            .iter()
+            .map(|(pair, pool, decimals_a, decimals_b)| (pair.as_str(), *pool, *decimals_a, *decimals_b))
+            .collect();
+        self.arbitrage_detector.detect_from_pools(&pools);
+    }
+
+    // Pool whose depth is shown on the Orders tab
+    fn active_pool(&self) -> Option<&PoolInfo> {
+        self.selected_pools
+            .iter()
+            .find(|p| p.dex_name == self.trading_chart.selected_dex)
+    }
+
+    // Snapshots back the active pool's depth and quotes, and fills for resting orders on any pool
+    fn request_pool_snapshot(&mut self, pool: Pubkey) {
+        let is_active = self.active_pool().map(|p| p.pubkey) == Some(pool);
+        let has_orders = self.paper_trading_view.account.orders.has_active_orders(&pool);
+        let is_fresh = self
+            .pool_snapshots_requested_at
+            .get(&pool)
+            .is_some_and(|t| t.elapsed() < POOL_SNAPSHOT_REFRESH_INTERVAL);
+        if !(is_active || has_orders) || is_fresh {
+            return;
+        }
+        self.fetch_pool_snapshot(pool, None);
+    }
+
+    fn fetch_pool_snapshot(&mut self, pool: Pubkey, min_context_slot: Option<u64>) {
+        self.pool_snapshots_requested_at.insert(pool, Instant::now());
+
+        let http_client = self.http_client.clone();
+        let sender = self.app_event_sender.clone();
+        tokio::spawn(async move {
+            let event = match http_client.get_pool_snapshot_since(&pool, min_context_slot).await {
+                Ok(snapshot) => AppEvent::PoolSnapshot(Box::new(snapshot)),
+                Err(e) => AppEvent::PoolSnapshotFailed { pool, error: e.to_string() },
+            };
+            let _ = sender.send(event);
+        });
+    }
+
+    fn request_priority_fees(&mut self, pool: Pubkey, whirlpool: Whirlpool) {
+        let is_active = self.active_pool().map(|p| p.pubkey) == Some(pool);
+        let is_fresh = self
+            .priority_fees_requested_at
+            .get(&pool)
+            .is_some_and(|t| t.elapsed() < PRIORITY_FEE_REFRESH_INTERVAL);
+        if !is_active || is_fresh {
+            return;
+        }
+        self.priority_fees_requested_at.insert(pool, Instant::now());
+
+        let http_client = self.http_client.clone();
+        let sender = self.app_event_sender.clone();
+        tokio::spawn(async move {
+            let event = match estimate_whirlpool_priority_fees(&http_client, &pool, &whirlpool).await {
+                Ok(estimate) => AppEvent::PriorityFees { pool, estimate },
+                Err(e) => AppEvent::PriorityFeesFailed { pool, error: e.to_string() },
+            };
+            let _ = sender.send(event);
+        });
+    }
+
+    fn process_app_event(&mut self, event: AppEvent) {
+        match event {
+            AppEvent::PoolSnapshot(snapshot) => {
+                let is_stale = self
+                    .pool_snapshots
+                    .get(&snapshot.address())
+                    .is_some_and(|current| current.slot() >= snapshot.slot());
+                let pool = snapshot.address();
+                if !is_stale {
+                    self.pool_snapshots.insert(pool, *snapshot);
+                    self.refresh_market_depth();
+                }
+                self.run_order_check(pool);
+            }
+            AppEvent::PoolSnapshotFailed { pool, error } => {
+                eprintln!("Failed to fetch a snapshot of {}: {}", pool, error);
+                // The next update that triggers an order queues a new check
+                self.pending_order_checks.remove(&pool);
+            }
+            AppEvent::Portfolio(portfolio) => self.portfolio_view.set_portfolio(portfolio),
+            AppEvent::PortfolioFailed { owner, error } => {
+                eprintln!("Failed to load portfolio for {}: {}", owner, error);
+                self.portfolio_view.set_error(error);
+            }
+            AppEvent::PriorityFees { pool, estimate } => match estimate {
+                Some(estimate) => {
+                    self.priority_fees.insert(pool, estimate);
+                }
+                None => {
+                    self.priority_fees.remove(&pool);
+                }
+            },
+            AppEvent::PriorityFeesFailed { pool, error } => {
+                eprintln!("Failed to fetch priority fees for {}: {}", pool, error);
+            }
+            AppEvent::PoolsFound { pools, decimals } => {
+                self.add_token_decimals(decimals);
+                self.add_pool_dialog.set_results(pools);
+            }
+            AppEvent::PoolSearchFailed(error) => self.add_pool_dialog.set_error(error),
+            AppEvent::TokenDecimals(decimals) => self.add_token_decimals(decimals),
+            AppEvent::TokenDecimalsFailed(error) => {
+                eprintln!("Failed to read token decimals: {}", error);
+            }
+            AppEvent::WebSocketConnected(client) => self.on_websocket_connected(client),
+            AppEvent::WebSocketConnectFailed(error) => {
+                eprintln!("Failed to connect WebSocket: {}", error);
+                self.connecting = false;
+                self.connection_status = format!("Connection failed: {}", error);
+            }
+            #[cfg(feature = "wallet")]
+            AppEvent::SwapSubmitted { id, signature } => self.live_swap_panel.on_submitted(id, signature),
+            #[cfg(feature = "wallet")]
+            AppEvent::SwapStatus { id, status } => self.live_swap_panel.on_status(id, status),
+        }
+    }
+
+    // Signs and sends a confirmed swap, then reports its status until it lands or expires
+    #[cfg(feature = "wallet")]
+    fn submit_live_swap(&mut self, request: LiveSwapRequest) {
+        let (Some(wallet), Some(whirlpool)) = (self.wallet.clone(), self.latest_whirlpools.get(&request.pool)) else {
+            return;
+        };
+        let instructions = wallet.swap_instructions(request.pool, whirlpool, &request.args, request.priority_fee);
+        let http_client = self.http_client.clone();
+        let sender = self.app_event_sender.clone();
+        let id = request.id;
+        tokio::spawn(async move {
+            let (signature, last_valid_block_height) = match wallet.send(&http_client, &instructions).await {
+                Ok(sent) => sent,
+                Err(e) => {
+                    let _ = sender.send(AppEvent::SwapStatus { id, status: TransactionStatus::Failed(e.to_string()) });
+                    return;
+                }
+            };
+            let _ = sender.send(AppEvent::SwapSubmitted { id, signature: signature.clone() });
+            let confirmed = wait_for_confirmation(&http_client, &signature, last_valid_block_height, |status| {
+                let _ = sender.send(AppEvent::SwapStatus { id, status: status.clone() });
+            })
+            .await;
+            if let Err(e) = confirmed {
+                let _ = sender.send(AppEvent::SwapStatus { id, status: TransactionStatus::Failed(e.to_string()) });
+            }
+        });
+    }
+
+    fn search_pools(&mut self, mint_x: Pubkey, mint_y: Pubkey) {
+        let http_client = self.http_client.clone();
+        let sender = self.app_event_sender.clone();
+        tokio::spawn(async move {
+            let search = async {
+                let pools = http_client.find_whirlpools(&mint_x, &mint_y).await?;
+                // Decimals let pools of tokens without metadata be priced once added
+                let decimals = http_client.get_mint_decimals(&[mint_x, mint_y]).await?;
+                Ok::<_, Box<dyn std::error::Error + Send + Sync>>((pools, decimals))
+            };
+            let event = match search.await {
+                Ok((pools, decimals)) => AppEvent::PoolsFound { pools, decimals },
+                Err(e) => AppEvent::PoolSearchFailed(e.to_string()),
+            };
+            let _ = sender.send(event);
+        });
+    }
+
+    // Watches a Whirlpool found by the Add Pool dialog under its own chart series
+    fn add_whirlpool(&mut self, address: Pubkey, whirlpool: Whirlpool) {
+        let name = format!(
+            "{}/{} {:.2}%",
+            self.token_symbol(&whirlpool.token_mint_a),
+            self.token_symbol(&whirlpool.token_mint_b),
+            whirlpool.fee_rate as f64 / 10_000.0
+        );
+        let pool = PoolInfo {
+            pubkey: address,
+            name: name.clone(),
+            token_a: whirlpool.token_mint_a,
+            token_b: whirlpool.token_mint_b,
+            dex: DexKind::Whirlpool,
+            dex_name: name.clone(),
+            tick_spacing: whirlpool.tick_spacing,
+            fee_rate: whirlpool.fee_rate,
+        };
+        self.trading_chart.add_price_history(name, PriceHistory::new(10000, 300));
+        self.latest_whirlpools.insert(address, whirlpool);
+        if let Some(client) = &self.ws_client {
+            if let Err(e) = client.subscribe(pool.pubkey, pool.dex) {
+                eprintln!("Failed to subscribe to {}: {}", pool.name, e);
+            }
+        }
+        self.selected_pools.push(pool);
+    }
+
+    fn request_portfolio(&mut self, owner: Pubkey) {
+        // Pools already being streamed help price tokens the wallet's own pools don't cover
+        let known_pools: Vec<Whirlpool> = self.latest_whirlpools.values().copied().collect();
+        let http_client = self.http_client.clone();
+        let sender = self.app_event_sender.clone();
+        tokio::spawn(async move {
+            let event = match fetch_portfolio(&http_client, owner, &known_pools).await {
+                Ok(portfolio) => AppEvent::Portfolio(portfolio),
+                Err(e) => AppEvent::PortfolioFailed { owner, error: e.to_string() },
+            };
+            let _ = sender.send(event);
+        });
+    }
+
+    // Mints read from chain keep their address as a name until something better is known
+    fn add_token_decimals(&mut self, decimals: HashMap<Pubkey, u8>) {
+        for (mint, decimals) in decimals {
+            let symbol = self.token_symbol(&mint);
+            self.token_metadata.entry(mint).or_insert(TokenMetadata {
+                symbol,
+                name: mint.to_string(),
+                decimals,
+                logo_uri: None,
+            });
+        }
+    }
+
+    fn token_symbol(&self, mint: &Pubkey) -> String {
+        match self.token_metadata.get(mint) {
+            Some(meta) => meta.symbol.clone(),
+            None => {
+                let address = mint.to_string();
+                format!("{}..{}", &address[..4], &address[address.len() - 4..])
+            }
+        }
+    }
+
+    // A known token symbol (case-insensitive) or a mint address
+    fn resolve_mint(&self, input: &str) -> Option<Pubkey> {
+        if let Ok(mint) = Pubkey::from_str(input) {
+            return Some(mint);
+        }
+        self.token_metadata
+            .iter()
+            .find(|(_, meta)| meta.symbol.eq_ignore_ascii_case(input))
+            .map(|(mint, _)| *mint)
+    }
+
+    fn lp_pool_context(&self) -> Option<LpPoolContext<'_>> {
+        let pool = self.active_pool()?;
+        let whirlpool = self.latest_whirlpools.get(&pool.pubkey)?;
+        let meta_a = self.token_metadata.get(&pool.token_a)?;
+        let meta_b = self.token_metadata.get(&pool.token_b)?;
+        Some(LpPoolContext {
+            whirlpool,
+            history: self.trading_chart.price_histories.get(&pool.dex_name),
+            decimals_a: meta_a.decimals,
+            decimals_b: meta_b.decimals,
+            symbol_b: meta_b.symbol.clone(),
+        })
+    }
+
+    // Quotes come from the last snapshot rather than the streamed pool, whose price can be
+    // ahead of the tick arrays
+    fn live_pool(&self, address: &Pubkey) -> Option<LivePool<'_>> {
+        let pool = self.selected_pools.iter().find(|p| p.pubkey == *address)?;
+        let snapshot = self.pool_snapshots.get(&pool.pubkey)?;
+        Some(LivePool {
+            address: pool.pubkey,
+            whirlpool: snapshot.whirlpool(),
+            tick_arrays: snapshot.tick_arrays(),
+            decimals_a: self.token_metadata.get(&pool.token_a)?.decimals,
+            decimals_b: self.token_metadata.get(&pool.token_b)?.decimals,
+        })
+    }
+
+    fn paper_pool_context(&self) -> Option<PaperPoolContext<'_>> {
+        let pool = self.active_pool()?;
+        Some(PaperPoolContext {
+            pool: self.live_pool(&pool.pubkey)?,
+            symbol_a: self.token_metadata.get(&pool.token_a)?.symbol.clone(),
+            symbol_b: self.token_metadata.get(&pool.token_b)?.symbol.clone(),
+        })
+    }
+
+    // Resting paper orders trigger off the price each pool update carries, whichever tab is
+    // open. Later updates are not queued while a triggering one waits for its snapshot.
+    fn queue_order_check(&mut self, pool: Pubkey, slot: u64, price: f64, timestamp: u64) {
+        if self.pending_order_checks.contains_key(&pool)
+            || !self.paper_trading_view.account.orders.is_triggered(&pool, price)
+        {
+            return;
+        }
+        self.pending_order_checks.insert(pool, PendingOrderCheck {
+            slot,
+            price,
+            timestamp,
+            snapshot_requested: false,
+        });
+        self.run_order_check(pool);
+    }
+
+    // Fills wait for a snapshot read no earlier than the update that triggered them
+    fn run_order_check(&mut self, pool: Pubkey) {
+        let Some(check) = self.pending_order_checks.get(&pool).copied() else {
+            return;
+        };
+        if self.pool_snapshots.get(&pool).is_some_and(|snapshot| snapshot.slot() >= check.slot) {
+            self.pending_order_checks.remove(&pool);
+            self.check_resting_orders(pool, check.price, check.timestamp);
+        } else if !check.snapshot_requested {
+            self.pending_order_checks.insert(pool, PendingOrderCheck {
+                snapshot_requested: true,
+                ..check
+            });
+            self.fetch_pool_snapshot(pool, Some(check.slot));
+        }
+    }
+
+    fn check_resting_orders(&mut self, pool: Pubkey, price: f64, timestamp: u64) {
+        let mut account = std::mem::take(&mut self.paper_trading_view.account);
+        let changed = match self.live_pool(&pool) {
+            Some(live_pool) => !account.on_pool_update(&live_pool, price, timestamp).is_empty(),
+            None => false,
+        };
+        self.paper_trading_view.account = account;
+        if changed {
+            self.save_paper_account();
+        }
+    }
+
+    fn save_paper_account(&self) {
+        if let Err(e) = self.paper_trading_view.account.save_to_file(PAPER_ACCOUNT_PATH) {
+            eprintln!("Failed to save paper account: {}", e);
+        }
+    }
+
+    fn refresh_market_depth(&mut self) {
+        let Some(pool) = self.active_pool() else {
+            return;
+        };
+        if let Some(book) = self.order_books.get(&pool.pubkey) {
+            self.market_depth.update_from_order_book(book);
+            return;
+        }
+        let Some(snapshot) = self.pool_snapshots.get(&pool.pubkey) else {
+            return;
+        };
+        let (Some(meta_a), Some(meta_b)) = (
+            self.token_metadata.get(&pool.token_a),
+            self.token_metadata.get(&pool.token_b),
+        ) else {
+            return;
+        };
+
+        let depth = build_liquidity_depth(snapshot.whirlpool(), snapshot.tick_arrays(), meta_a.decimals, meta_b.decimals);
+        self.market_depth.update_from_liquidity_depth(&depth);
     }
 }
 
@@ -222,12 +902,35 @@ impl App for MyApp {
         ctx.set_visuals(egui::Visuals::dark());
 
         // Process WebSocket updates
+        let mut events = Vec::new();
         if let Some(ref mut client) = self.ws_client {
-            while let Ok(update) = client.try_recv() {
-                self.process_whirlpool_update(update);
+            while let Ok(event) = client.try_recv() {
+                events.push(event);
+            }
+        }
+        for event in events {
+            match event {
+                WebSocketEvent::Update(update) => self.process_pool_update(update),
+                WebSocketEvent::ConnectionState(state) => self.process_connection_state(state),
+                WebSocketEvent::SubscribeFailed { pubkey, error } => {
+                    eprintln!("Subscription to {} failed: {}", pubkey, error);
+                }
+                WebSocketEvent::PoolDiscovered { pubkey, .. } => {
+                    if !self.discovered_pools.contains(&pubkey) {
+                        self.discovered_pools.push(pubkey);
+                    }
+                }
+                WebSocketEvent::WhirlpoolSubscribeFailed { filter, error } => {
+                    eprintln!("Whirlpool subscription {:?} failed: {}", filter, error);
+                }
             }
         }
 
+        // Process background task results
+        while let Ok(event) = self.app_event_receiver.try_recv() {
+            self.process_app_event(event);
+        }
+
         // Request repaint for real-time updates
         ctx.request_repaint_after(std::time::Duration::from_millis(100));
 
@@ -256,6 +959,13 @@ impl App for MyApp {
                     self.active_tab = ViewTab::Orders;
                 }
 
+                if ui
+                    .selectable_label(self.active_tab == ViewTab::Portfolio, "Portfolio")
+                    .clicked()
+                {
+                    self.active_tab = ViewTab::Portfolio;
+                }
+
                 if ui
                     .selectable_label(self.active_tab == ViewTab::GraphArb, "Graph Arb")
                     .clicked()
@@ -274,18 +984,17 @@ impl App for MyApp {
                 ui.colored_label(status_color, &self.connection_status);
 
                 // Connect/Disconnect button
-                if self.connected {
+                if self.ws_client.is_some() {
                     if ui.button("Disconnect").clicked() {
-                        self.ws_client = None;
+                        if let Some(client) = self.ws_client.take() {
+                            let _ = client.shutdown();
+                        }
                         self.connected = false;
                         self.connection_status = "Disconnected".to_string();
                     }
                 } else {
-                    if ui.button("Connect").clicked() {
-                        let rt = tokio::runtime::Runtime::new().unwrap();
-                        rt.block_on(async {
-                            self.connect_websocket().await;
-                        });
+                    if ui.add_enabled(!self.connecting, egui::Button::new("Connect")).clicked() {
+                        self.connect_websocket();
                     }
                 }
 
@@ -336,13 +1045,47 @@ impl App for MyApp {
                         }
                     });
 
+                    if !self.discovered_pools.is_empty() {
+                        ui.collapsing(format!("Discovered Whirlpools ({})", self.discovered_pools.len()), |ui| {
+                            for pubkey in &self.discovered_pools {
+                                let Some(whirlpool) = self.latest_whirlpools.get(pubkey) else {
+                                    continue;
+                                };
+                                ui.label(format!(
+                                    "{}/{} {:.2}% (spacing {})",
+                                    self.token_symbol(&whirlpool.token_mint_a),
+                                    self.token_symbol(&whirlpool.token_mint_b),
+                                    whirlpool.fee_rate as f64 / 10_000.0,
+                                    whirlpool.tick_spacing
+                                ))
+                                .on_hover_text(pubkey.to_string());
+                            }
+                        });
+                    }
+
                     ui.separator();
                     if ui.button("Add Pool").clicked() {
-                        // TODO: Open pool selection dialog
+                        self.add_pool_dialog.open = true;
                     }
                 });
         }
 
+        if self.add_pool_dialog.open {
+            let mut dialog = std::mem::take(&mut self.add_pool_dialog);
+            let action = dialog.show(
+                ctx,
+                |input| self.resolve_mint(input),
+                |mint| self.token_symbol(mint),
+                |address| self.selected_pools.iter().any(|p| p.pubkey == *address),
+            );
+            self.add_pool_dialog = dialog;
+            match action {
+                Some(AddPoolAction::Search { mint_x, mint_y }) => self.search_pools(mint_x, mint_y),
+                Some(AddPoolAction::Add { address, whirlpool }) => self.add_whirlpool(address, *whirlpool),
+                None => {}
+            }
+        }
+
         // Settings panel
         if self.show_settings {
             SidePanel::right("settings_panel")
@@ -358,7 +1101,30 @@ impl App for MyApp {
                     ui.label("WebSocket Endpoint:");
                     ui.text_edit_singleline(&mut self.ws_endpoint);
 
+                    let mut options_changed = false;
+                    ui.horizontal(|ui| {
+                        ui.label("Commitment:");
+                        for commitment in [Commitment::Processed, Commitment::Confirmed, Commitment::Finalized] {
+                            options_changed |= ui
+                                .selectable_value(&mut self.account_options.commitment, commitment, commitment.as_str())
+                                .changed();
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Encoding:");
+                        for encoding in [AccountEncoding::Base64, AccountEncoding::Base64Zstd] {
+                            options_changed |= ui
+                                .selectable_value(&mut self.account_options.encoding, encoding, encoding.as_str())
+                                .changed();
+                        }
+                    });
+                    if options_changed {
+                        self.http_client = self.http_client.with_account_options(self.account_options);
+                    }
+                    ui.small("HTTP requests use a new commitment and encoding right away; pool subscriptions on the next connect");
                     ui.checkbox(&mut self.auto_reconnect, "Auto Reconnect");
+                    ui.checkbox(&mut self.discover_pools, "Stream every Whirlpool of the selected pairs")
+                        .on_hover_text("Uses programSubscribe, which some RPC providers disable. Applies on the next connect.");
 
                     ui.separator();
 
@@ -384,6 +1150,13 @@ impl App for MyApp {
 
                     // Main chart
                     self.trading_chart.show(ui);
+
+                    ui.separator();
+                    ui.collapsing("LP analytics", |ui| {
+                        let mut panel = std::mem::take(&mut self.lp_analytics_panel);
+                        panel.show(ui, self.lp_pool_context());
+                        self.lp_analytics_panel = panel;
+                    });
                 }
                 ViewTab::Orders => {
                     ui.vertical_centered(|ui| {
@@ -392,21 +1165,58 @@ impl App for MyApp {
                         
                         // Market depth chart
                         self.market_depth.show(ui);
-                        
-                        ui.separator();
-                        
-                        // Orders table would go here
-                        ui.label("Order book and trade history will be implemented here");
                     });
+
+                    let active_pool = self.active_pool().map(|pool| pool.pubkey);
+                    if let Some(snapshot) = active_pool.and_then(|pool| self.pool_snapshots.get(&pool)) {
+                        ui.label(format!("Depth and quotes as of slot {}", snapshot.slot()));
+                    }
+                    let fee_estimate = active_pool.and_then(|pool| self.priority_fees.get(&pool));
+                    show_priority_fees(ui, fee_estimate);
+
+                    ui.separator();
+
+                    let mut paper_trading_view = std::mem::take(&mut self.paper_trading_view);
+                    let changed = paper_trading_view.show(
+                        ui,
+                        self.paper_pool_context(),
+                        |mint| self.token_symbol(mint),
+                        |mint| self.token_metadata.get(mint).map(|meta| meta.decimals),
+                    );
+                    self.paper_trading_view = paper_trading_view;
+                    if changed {
+                        self.save_paper_account();
+                    }
+
+                    #[cfg(feature = "wallet")]
+                    {
+                        ui.separator();
+                        let mut live_swap_panel = std::mem::take(&mut self.live_swap_panel);
+                        let wallet = self.wallet.as_ref().map(|wallet| wallet.pubkey());
+                        let request = live_swap_panel.show(ui, self.paper_pool_context().as_ref(), fee_estimate, wallet);
+                        self.live_swap_panel = live_swap_panel;
+                        if let Some(request) = request {
+                            self.submit_live_swap(request);
+                        }
+                    }
+                }
+                ViewTab::Portfolio => {
+                    ui.heading("Portfolio");
+                    ui.separator();
+
+                    let mut portfolio_view = std::mem::take(&mut self.portfolio_view);
+                    let requested = portfolio_view.show(ui, |mint| self.token_symbol(mint));
+                    self.portfolio_view = portfolio_view;
+                    if let Some(owner) = requested {
+                        self.request_portfolio(owner);
+                    }
                 }
                 ViewTab::GraphArb => {
-                    ui.vertical_centered(|ui| {
-                        ui.heading("Arbitrage Graph");
-                        ui.label("Multi-DEX arbitrage visualization will be implemented here");
-                        
-                        // This would show a graph of price differences across DEXes
-                        // and potential arbitrage opportunities
-                    });
+                    if self.arbitrage_detection {
+                        self.arbitrage_detector.show_opportunities(ui);
+                    } else {
+                        ui.label("Arbitrage detection is off (trading.enable_arbitrage_detection in config.toml)");
+                    }
                 }
             }
         });
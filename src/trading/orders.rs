@@ -0,0 +1,349 @@
+// src/trading/orders.rs - Resting limit, stop-loss and take-profit orders for paper trading
+
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use crate::data::price_data::whirlpool_math;
+use crate::dex::whirlpool::swap::SwapError;
+use crate::trading::paper::{LivePool, PaperTradeError, PaperTradingEngine, Side};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderKind {
+    /// Fills at the trigger price or better, resting until fully filled.
+    Limit,
+    /// Fills at market once the price moves against the order's side through the trigger.
+    StopLoss,
+    /// Fills at market once the price moves in favour of the order's side through the trigger.
+    TakeProfit,
+}
+
+impl OrderKind {
+    pub const ALL: [OrderKind; 3] = [OrderKind::Limit, OrderKind::StopLoss, OrderKind::TakeProfit];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OrderKind::Limit => "Limit",
+            OrderKind::StopLoss => "Stop-loss",
+            OrderKind::TakeProfit => "Take-profit",
+        }
+    }
+
+    /// Whether an order of this kind on `side` triggers with the pool at `price`.
+    pub fn is_triggered(self, side: Side, trigger_price: f64, price: f64) -> bool {
+        match (self, side) {
+            (OrderKind::Limit | OrderKind::TakeProfit, Side::Buy) | (OrderKind::StopLoss, Side::Sell) => {
+                price <= trigger_price
+            }
+            (OrderKind::Limit | OrderKind::TakeProfit, Side::Sell) | (OrderKind::StopLoss, Side::Buy) => {
+                price >= trigger_price
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderStatus {
+    Open,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+    Rejected(String),
+}
+
+impl OrderStatus {
+    /// Open and partially filled orders still rest on the pool.
+    pub fn is_active(&self) -> bool {
+        matches!(self, OrderStatus::Open | OrderStatus::PartiallyFilled)
+    }
+}
+
+/// An order waiting for the pool price. Amounts are raw; the trigger is UI token B per token A.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestingOrder {
+    pub id: u64,
+    #[serde(with = "crate::utils::pubkey_serde")]
+    pub pool: Pubkey,
+    pub kind: OrderKind,
+    pub side: Side,
+    pub trigger_price: f64,
+    /// Input token to spend over the life of the order.
+    pub amount: u64,
+    pub filled_in: u64,
+    pub filled_out: u64,
+    pub status: OrderStatus,
+    pub created_at: u64,
+    pub updated_at: u64,
+    /// Ids of the engine fills made for this order.
+    pub fill_ids: Vec<u64>,
+}
+
+impl RestingOrder {
+    pub fn remaining(&self) -> u64 {
+        self.amount - self.filled_in
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OrderManager {
+    orders: Vec<RestingOrder>,
+    next_id: u64,
+}
+
+impl OrderManager {
+    /// All orders, oldest first, including finished ones.
+    pub fn orders(&self) -> &[RestingOrder] {
+        &self.orders
+    }
+
+    pub fn place(
+        &mut self,
+        pool: Pubkey,
+        kind: OrderKind,
+        side: Side,
+        trigger_price: f64,
+        amount: u64,
+        timestamp: u64,
+    ) -> Result<u64, PaperTradeError> {
+        if amount == 0 {
+            return Err(PaperTradeError::InvalidOrder("amount must be greater than zero".to_string()));
+        }
+        if !(trigger_price.is_finite() && trigger_price > 0.0) {
+            return Err(PaperTradeError::InvalidOrder(format!("invalid trigger price {}", trigger_price)));
+        }
+
+        self.next_id += 1;
+        self.orders.push(RestingOrder {
+            id: self.next_id,
+            pool,
+            kind,
+            side,
+            trigger_price,
+            amount,
+            filled_in: 0,
+            filled_out: 0,
+            status: OrderStatus::Open,
+            created_at: timestamp,
+            updated_at: timestamp,
+            fill_ids: Vec::new(),
+        });
+        Ok(self.next_id)
+    }
+
+    /// Cancels an active order, keeping what it already filled. Returns false if the order
+    /// does not exist or has finished.
+    pub fn cancel(&mut self, id: u64, timestamp: u64) -> bool {
+        match self.orders.iter_mut().find(|order| order.id == id && order.status.is_active()) {
+            Some(order) => {
+                order.status = OrderStatus::Cancelled;
+                order.updated_at = timestamp;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether any order still rests on `pool`.
+    pub fn has_active_orders(&self, pool: &Pubkey) -> bool {
+        self.orders.iter().any(|order| order.pool == *pool && order.status.is_active())
+    }
+
+    /// Whether `price` triggers any order resting on `pool`.
+    pub fn is_triggered(&self, pool: &Pubkey, price: f64) -> bool {
+        self.orders.iter().any(|order| {
+            order.pool == *pool
+                && order.status.is_active()
+                && order.kind.is_triggered(order.side, order.trigger_price, price)
+        })
+    }
+
+    /// Checks the active orders on `pool` against `price`, the price carried by the update
+    /// being processed, and fills the triggered ones through `engine` against `pool`. Limit
+    /// orders only take liquidity up to their price and keep resting with the rest. Returns
+    /// the ids of orders that changed.
+    pub fn on_pool_update(
+        &mut self,
+        engine: &mut PaperTradingEngine,
+        pool: &LivePool,
+        price: f64,
+        timestamp: u64,
+    ) -> Vec<u64> {
+        let mut changed = Vec::new();
+
+        for order in self.orders.iter_mut() {
+            if order.pool != pool.address
+                || !order.status.is_active()
+                || !order.kind.is_triggered(order.side, order.trigger_price, price)
+            {
+                continue;
+            }
+
+            let mint_in = if order.side.a_to_b() {
+                pool.whirlpool.token_mint_a
+            } else {
+                pool.whirlpool.token_mint_b
+            };
+            let amount = order.remaining().min(engine.balance(&mint_in));
+            if amount == 0 {
+                // A partly filled order keeps what it got and waits for funds; a new one is rejected
+                if order.status == OrderStatus::Open {
+                    order.status = OrderStatus::Rejected("insufficient balance".to_string());
+                    order.updated_at = timestamp;
+                    changed.push(order.id);
+                }
+                continue;
+            }
+
+            let sqrt_price_limit = match order.kind {
+                OrderKind::Limit => {
+                    whirlpool_math::price_to_sqrt_price_x64(order.trigger_price, pool.decimals_a, pool.decimals_b)
+                }
+                OrderKind::StopLoss | OrderKind::TakeProfit => 0,
+            };
+
+            match engine.fill(pool, order.side, amount, sqrt_price_limit, timestamp) {
+                Ok(fill) => {
+                    order.filled_in += fill.amount_in;
+                    order.filled_out += fill.amount_out;
+                    order.fill_ids.push(fill.id);
+                    order.status = if order.remaining() == 0 {
+                        OrderStatus::Filled
+                    } else {
+                        OrderStatus::PartiallyFilled
+                    };
+                }
+                // Sitting exactly on the limit, or the tick arrays have not caught up with the
+                // price yet: try again on a later update
+                Err(PaperTradeError::NoLiquidity)
+                | Err(PaperTradeError::Quote(
+                    SwapError::InvalidSqrtPriceLimit(_)
+                    | SwapError::MissingTickArray(_)
+                    | SwapError::TickArraySequenceExhausted,
+                )) => continue,
+                Err(e) => order.status = OrderStatus::Rejected(e.to_string()),
+            }
+            order.updated_at = timestamp;
+            changed.push(order.id);
+        }
+
+        changed
+    }
+}
+
+/// Everything a paper trader keeps between sessions.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PaperAccount {
+    pub engine: PaperTradingEngine,
+    pub orders: OrderManager,
+}
+
+impl PaperAccount {
+    pub fn load_from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Loads the saved account, starting empty if there is none yet.
+    pub fn load_or_default(path: &str) -> Self {
+        if !std::path::Path::new(path).exists() {
+            return Self::default();
+        }
+        Self::load_from_file(path).unwrap_or_else(|e| {
+            eprintln!("Failed to load paper account from {}: {}", path, e);
+            Self::default()
+        })
+    }
+
+    pub fn on_pool_update(&mut self, pool: &LivePool, price: f64, timestamp: u64) -> Vec<u64> {
+        self.orders.on_pool_update(&mut self.engine, pool, price, timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::whirlpool::state::{whirlpool_at_tick_zero, Tick, TickArray, TICK_ARRAY_SIZE};
+
+    fn tick_arrays() -> Vec<TickArray> {
+        let width = TICK_ARRAY_SIZE as i32 * 64;
+        (-3..3)
+            .map(|i| TickArray {
+                start_tick_index: i * width,
+                ticks: [Tick::default(); TICK_ARRAY_SIZE],
+                whirlpool: Pubkey::default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_trigger_directions() {
+        assert!(OrderKind::Limit.is_triggered(Side::Buy, 1.0, 0.99));
+        assert!(!OrderKind::Limit.is_triggered(Side::Buy, 1.0, 1.01));
+        assert!(OrderKind::StopLoss.is_triggered(Side::Sell, 1.0, 0.99));
+        assert!(!OrderKind::StopLoss.is_triggered(Side::Sell, 1.0, 1.01));
+        assert!(OrderKind::TakeProfit.is_triggered(Side::Sell, 1.0, 1.01));
+        assert!(OrderKind::StopLoss.is_triggered(Side::Buy, 1.0, 1.01));
+    }
+
+    #[test]
+    fn test_limit_order_partially_fills_up_to_its_price() {
+        let whirlpool = whirlpool_at_tick_zero(1_000_000_000);
+        let tick_arrays = tick_arrays();
+        let pool = LivePool {
+            address: Pubkey::new_unique(),
+            whirlpool: &whirlpool,
+            tick_arrays: &tick_arrays,
+            decimals_a: 6,
+            decimals_b: 6,
+        };
+
+        let mut account = PaperAccount::default();
+        account.engine.deposit(whirlpool.token_mint_b, 100_000_000);
+
+        // Only about 5 B of input moves the price from 1.0 to the 1.01 limit
+        let limit = account
+            .orders
+            .place(pool.address, OrderKind::Limit, Side::Buy, 1.01, 100_000_000, 1)
+            .unwrap();
+        let stop = account
+            .orders
+            .place(pool.address, OrderKind::StopLoss, Side::Buy, 2.0, 1_000, 1)
+            .unwrap();
+        assert_eq!(account.on_pool_update(&pool, 1.0, 2), vec![limit]);
+
+        let order = account.orders.orders()[0].clone();
+        assert_eq!(order.status, OrderStatus::PartiallyFilled);
+        assert!(order.filled_in > 0 && order.filled_in < 10_000_000);
+        assert_eq!(account.engine.fills()[0].id, order.fill_ids[0]);
+        assert_eq!(account.engine.balance(&whirlpool.token_mint_b), 100_000_000 - order.filled_in);
+
+        // Spending the rest of the balance elsewhere leaves the order partly filled, not rejected
+        let rest = account.engine.balance(&whirlpool.token_mint_b);
+        account.engine.market_order(&pool, Side::Buy, rest, 3).unwrap();
+        assert!(account.on_pool_update(&pool, 1.0, 3).is_empty());
+        assert_eq!(account.orders.orders()[0].status, OrderStatus::PartiallyFilled);
+
+        // The stop triggers on the update's price even though the pool read for the fill lags it
+        assert!(account.orders.has_active_orders(&pool.address));
+        account.engine.deposit(whirlpool.token_mint_b, 1_000);
+        assert!(account.orders.is_triggered(&pool.address, 2.5));
+        assert!(!account.orders.is_triggered(&pool.address, 1.5));
+        assert_eq!(account.on_pool_update(&pool, 2.5, 3), vec![stop]);
+        assert_eq!(account.orders.orders()[1].status, OrderStatus::Filled);
+
+        assert!(account.orders.cancel(limit, 3));
+        assert!(!account.orders.cancel(limit, 4));
+        assert!(!account.orders.has_active_orders(&pool.address));
+
+        // Round trip through the saved form
+        let saved = serde_json::to_string(&account).unwrap();
+        let restored: PaperAccount = serde_json::from_str(&saved).unwrap();
+        assert_eq!(restored.orders.orders(), account.orders.orders());
+        assert_eq!(restored.engine.fills(), account.engine.fills());
+        assert_eq!(restored.engine.balances(), account.engine.balances());
+    }
+}
@@ -0,0 +1,279 @@
+// src/trading/paper.rs - Paper trading against live Whirlpool state
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use crate::data::price_data::whirlpool_math;
+use crate::dex::whirlpool::state::{TickArray, Whirlpool};
+use crate::dex::whirlpool::swap::{swap_quote, SwapError, SwapParams};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PaperTradeError {
+    #[error("insufficient balance of {mint}: need {needed}, have {available}")]
+    InsufficientBalance {
+        mint: Pubkey,
+        needed: u64,
+        available: u64,
+    },
+    #[error("no liquidity up to the price limit")]
+    NoLiquidity,
+    #[error("invalid order: {0}")]
+    InvalidOrder(String),
+    #[error("swap quote failed: {0}")]
+    Quote(#[from] SwapError),
+}
+
+/// Buys spend token B for token A; sells spend token A for token B.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    pub fn a_to_b(self) -> bool {
+        self == Side::Sell
+    }
+}
+
+/// A pool as seen at the moment an order is placed.
+pub struct LivePool<'a> {
+    pub address: Pubkey,
+    pub whirlpool: &'a Whirlpool,
+    pub tick_arrays: &'a [TickArray],
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+}
+
+/// One filled market order. Amounts are raw; prices are UI token B per token A.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fill {
+    pub id: u64,
+    pub timestamp: u64,
+    #[serde(with = "crate::utils::pubkey_serde")]
+    pub pool: Pubkey,
+    pub side: Side,
+    #[serde(with = "crate::utils::pubkey_serde")]
+    pub mint_in: Pubkey,
+    #[serde(with = "crate::utils::pubkey_serde")]
+    pub mint_out: Pubkey,
+    /// Input spent, fee included.
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Fee charged on the input token.
+    pub fee_amount: u64,
+    /// Pool price before the fill.
+    pub mid_price: f64,
+    /// Average execution price, fee included.
+    pub fill_price: f64,
+    /// Price impact against `mid_price`, fee excluded. Positive means a worse price.
+    pub slippage: f64,
+}
+
+/// Virtual balances and the blotter of fills made against them.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PaperTradingEngine {
+    #[serde(with = "crate::utils::pubkey_serde::map")]
+    balances: HashMap<Pubkey, u64>,
+    fills: Vec<Fill>,
+    next_id: u64,
+}
+
+fn ui_amount(amount: u64, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(decimals as i32)
+}
+
+// Token B paid or received per token A, in UI units
+fn price_of(amount_a: u64, amount_b: u64, decimals_a: u8, decimals_b: u8) -> f64 {
+    ui_amount(amount_b, decimals_b) / ui_amount(amount_a, decimals_a)
+}
+
+impl PaperTradingEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn deposit(&mut self, mint: Pubkey, amount: u64) {
+        let balance = self.balances.entry(mint).or_insert(0);
+        *balance = balance.saturating_add(amount);
+    }
+
+    pub fn balance(&self, mint: &Pubkey) -> u64 {
+        self.balances.get(mint).copied().unwrap_or(0)
+    }
+
+    pub fn balances(&self) -> &HashMap<Pubkey, u64> {
+        &self.balances
+    }
+
+    /// Fills, oldest first.
+    pub fn fills(&self) -> &[Fill] {
+        &self.fills
+    }
+
+    /// Total fees paid per input mint.
+    pub fn fees_paid(&self) -> HashMap<Pubkey, u64> {
+        let mut fees = HashMap::new();
+        for fill in &self.fills {
+            *fees.entry(fill.mint_in).or_insert(0u64) += fill.fee_amount;
+        }
+        fees
+    }
+
+    /// Clears balances and the blotter.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Fills a market order spending `amount` raw units of the input token, at exactly what
+    /// the pool would pay out for it right now.
+    pub fn market_order(
+        &mut self,
+        pool: &LivePool,
+        side: Side,
+        amount: u64,
+        timestamp: u64,
+    ) -> Result<&Fill, PaperTradeError> {
+        self.fill(pool, side, amount, 0, timestamp)
+    }
+
+    /// Like `market_order`, but stops at `sqrt_price_limit` (`0` for none). Only the part of
+    /// `amount` the pool can absorb before the limit is spent.
+    pub fn fill(
+        &mut self,
+        pool: &LivePool,
+        side: Side,
+        amount: u64,
+        sqrt_price_limit: u128,
+        timestamp: u64,
+    ) -> Result<&Fill, PaperTradeError> {
+        let whirlpool = pool.whirlpool;
+        let a_to_b = side.a_to_b();
+        let (mint_in, mint_out) = if a_to_b {
+            (whirlpool.token_mint_a, whirlpool.token_mint_b)
+        } else {
+            (whirlpool.token_mint_b, whirlpool.token_mint_a)
+        };
+
+        let available = self.balance(&mint_in);
+        if amount > available {
+            return Err(PaperTradeError::InsufficientBalance {
+                mint: mint_in,
+                needed: amount,
+                available,
+            });
+        }
+
+        let params = SwapParams {
+            sqrt_price_limit,
+            ..SwapParams::exact_in(amount, a_to_b)
+        };
+        let quote = swap_quote(whirlpool, pool.tick_arrays, params)?;
+        let (amount_in, amount_out) = (quote.amount_in, quote.amount_out);
+        if amount_in == 0 {
+            return Err(PaperTradeError::NoLiquidity);
+        }
+        if amount_out == 0 {
+            return Err(PaperTradeError::InvalidOrder("amount too small to receive any output".to_string()));
+        }
+
+        let mid_price =
+            whirlpool_math::calculate_price_from_whirlpool(whirlpool, pool.decimals_a, pool.decimals_b);
+        let net_in = amount_in - quote.fee_amount;
+        let (fill_price, net_price) = if a_to_b {
+            (
+                price_of(amount_in, amount_out, pool.decimals_a, pool.decimals_b),
+                price_of(net_in, amount_out, pool.decimals_a, pool.decimals_b),
+            )
+        } else {
+            (
+                price_of(amount_out, amount_in, pool.decimals_a, pool.decimals_b),
+                price_of(amount_out, net_in, pool.decimals_a, pool.decimals_b),
+            )
+        };
+        let slippage = if a_to_b {
+            1.0 - net_price / mid_price
+        } else {
+            net_price / mid_price - 1.0
+        };
+
+        self.balances.insert(mint_in, available - amount_in);
+        self.deposit(mint_out, amount_out);
+
+        self.next_id += 1;
+        self.fills.push(Fill {
+            id: self.next_id,
+            timestamp,
+            pool: pool.address,
+            side,
+            mint_in,
+            mint_out,
+            amount_in,
+            amount_out,
+            fee_amount: quote.fee_amount,
+            mid_price,
+            fill_price,
+            slippage,
+        });
+        Ok(self.fills.last().expect("fill was just recorded"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::whirlpool::state::{whirlpool_at_tick_zero, Tick, TICK_ARRAY_SIZE};
+
+    const LIQUIDITY: u128 = 1_000_000_000_000;
+
+    // Uninitialized ticks on both sides of tick 0, so liquidity stays constant
+    fn tick_arrays() -> Vec<TickArray> {
+        let width = TICK_ARRAY_SIZE as i32 * 64;
+        (-3..3)
+            .map(|i| TickArray {
+                start_tick_index: i * width,
+                ticks: [Tick::default(); TICK_ARRAY_SIZE],
+                whirlpool: Pubkey::default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_market_orders_move_balances() {
+        let whirlpool = whirlpool_at_tick_zero(LIQUIDITY);
+        let tick_arrays = tick_arrays();
+        let pool = LivePool {
+            address: Pubkey::new_unique(),
+            whirlpool: &whirlpool,
+            tick_arrays: &tick_arrays,
+            decimals_a: 6,
+            decimals_b: 6,
+        };
+        let (mint_a, mint_b) = (whirlpool.token_mint_a, whirlpool.token_mint_b);
+
+        let mut engine = PaperTradingEngine::new();
+        engine.deposit(mint_b, 1_000_000);
+
+        let fill = engine.market_order(&pool, Side::Buy, 1_000_000, 42).unwrap().clone();
+        assert_eq!(fill.amount_in, 1_000_000);
+        assert_eq!(fill.fee_amount, 3_000);
+        assert_eq!(engine.balance(&mint_b), 0);
+        assert_eq!(engine.balance(&mint_a), fill.amount_out);
+
+        // Buying pays above the mid price: the 0.3% fee plus a small price impact
+        assert!(fill.fill_price > fill.mid_price * 1.003);
+        assert!(fill.slippage > 0.0 && fill.slippage < 1e-5);
+
+        let err = engine.market_order(&pool, Side::Buy, 1, 43).unwrap_err();
+        assert!(matches!(err, PaperTradeError::InsufficientBalance { available: 0, .. }));
+
+        let sold = fill.amount_out;
+        let sell = engine.market_order(&pool, Side::Sell, sold, 44).unwrap();
+        assert_eq!(sell.id, 2);
+        assert!(sell.fill_price < sell.mid_price);
+        assert_eq!(engine.balance(&mint_a), 0);
+        assert!(engine.balance(&mint_b) < 1_000_000);
+        assert_eq!(engine.fees_paid()[&mint_b], 3_000);
+        assert_eq!(engine.fills().len(), 2);
+    }
+}
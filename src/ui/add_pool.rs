@@ -0,0 +1,138 @@
+// src/ui/add_pool.rs
+
+use eframe::egui::*;
+use solana_program::pubkey::Pubkey;
+use crate::dex::whirlpool::state::Whirlpool;
+
+pub enum AddPoolAction {
+    /// Look up every Whirlpool of a mint pair.
+    Search { mint_x: Pubkey, mint_y: Pubkey },
+    Add { address: Pubkey, whirlpool: Box<Whirlpool> },
+}
+
+// "Add Pool" dialog: finds the Whirlpools of a token pair and adds one to the watch list
+pub struct AddPoolDialog {
+    pub open: bool,
+    pub mint_x_input: String,
+    pub mint_y_input: String,
+    pub filter_input: String,
+    pub status: String,
+    pub searching: bool,
+    results: Vec<(Pubkey, Whirlpool)>,
+}
+
+impl Default for AddPoolDialog {
+    fn default() -> Self {
+        Self {
+            open: false,
+            mint_x_input: String::new(),
+            mint_y_input: String::new(),
+            filter_input: String::new(),
+            status: "Enter two mints or known symbols".to_string(),
+            searching: false,
+            results: Vec::new(),
+        }
+    }
+}
+
+fn fee_percent(whirlpool: &Whirlpool) -> f64 {
+    whirlpool.fee_rate as f64 / 10_000.0
+}
+
+impl AddPoolDialog {
+    /// Pools found for the last search, most liquid first.
+    pub fn set_results(&mut self, results: Vec<(Pubkey, Whirlpool)>) {
+        self.status = format!("{} pools found", results.len());
+        self.searching = false;
+        self.results = results;
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.status = format!("Search failed: {}", error);
+        self.searching = false;
+    }
+
+    /// Draws the dialog while it is open. `resolve_mint` turns a symbol or address into a
+    /// mint, `symbol` names a mint, and `is_added` tells which pools are already watched.
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        resolve_mint: impl Fn(&str) -> Option<Pubkey>,
+        symbol: impl Fn(&Pubkey) -> String,
+        is_added: impl Fn(&Pubkey) -> bool,
+    ) -> Option<AddPoolAction> {
+        let mut action = None;
+        let mut open = self.open;
+
+        Window::new("Add Pool")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Token:");
+                    ui.add(TextEdit::singleline(&mut self.mint_x_input).desired_width(180.0));
+                    ui.label("Token:");
+                    ui.add(TextEdit::singleline(&mut self.mint_y_input).desired_width(180.0));
+                    if ui.add_enabled(!self.searching, Button::new("Search")).clicked() {
+                        match (resolve_mint(self.mint_x_input.trim()), resolve_mint(self.mint_y_input.trim())) {
+                            (Some(mint_x), Some(mint_y)) => {
+                                self.searching = true;
+                                self.status = "Searching...".to_string();
+                                action = Some(AddPoolAction::Search { mint_x, mint_y });
+                            }
+                            _ => self.status = "Unknown symbol or invalid mint address".to_string(),
+                        }
+                    }
+                });
+                ui.label(&self.status);
+
+                if self.results.is_empty() {
+                    return;
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Filter:");
+                    ui.add(TextEdit::singleline(&mut self.filter_input).hint_text("fee, tick spacing or address"));
+                });
+                let filter = self.filter_input.trim().to_lowercase();
+
+                ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    Grid::new("add_pool_results").striped(true).show(ui, |ui| {
+                        for header in ["Pair", "Fee", "Tick spacing", "Liquidity", "Address", ""] {
+                            ui.label(header);
+                        }
+                        ui.end_row();
+
+                        for (address, whirlpool) in &self.results {
+                            let pair = format!(
+                                "{}/{}",
+                                symbol(&whirlpool.token_mint_a),
+                                symbol(&whirlpool.token_mint_b)
+                            );
+                            let fee = format!("{:.2}%", fee_percent(whirlpool));
+                            let address_text = address.to_string();
+                            let searchable = format!("{} {} {} {}", pair, fee, whirlpool.tick_spacing, address_text);
+                            if !filter.is_empty() && !searchable.to_lowercase().contains(&filter) {
+                                continue;
+                            }
+
+                            ui.label(pair);
+                            ui.label(fee);
+                            ui.label(whirlpool.tick_spacing.to_string());
+                            ui.label(whirlpool.liquidity.to_string());
+                            ui.label(format!("{}..", &address_text[..8])).on_hover_text(&address_text);
+                            if is_added(address) {
+                                ui.label("Added");
+                            } else if ui.button("Add").clicked() {
+                                action = Some(AddPoolAction::Add { address: *address, whirlpool: Box::new(*whirlpool) });
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+
+        self.open = open;
+        action
+    }
+}
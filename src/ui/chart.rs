@@ -3,6 +3,8 @@
 use eframe::egui::{self, plot::*, *};
 use std::collections::VecDeque;
 use crate::data::price_data::{PricePoint, CandlestickData, PriceHistory};
+use crate::data::liquidity_depth::LiquidityDepth;
+use crate::dex::order_book::OrderBook;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ChartType {
@@ -440,6 +442,20 @@ pub struct MarketDepth {
 }
 
 impl MarketDepth {
+    /// Replaces the book with tick-derived depth. Levels are per-level amounts; `show` accumulates them.
+    pub fn update_from_liquidity_depth(&mut self, depth: &LiquidityDepth) {
+        self.bids = depth.bids.iter().map(|l| (l.price, l.amount)).collect();
+        self.asks = depth.asks.iter().map(|l| (l.price, l.amount)).collect();
+        self.spread = depth.spread();
+    }
+
+    /// Replaces the book with the resting orders of a limit order book market.
+    pub fn update_from_order_book(&mut self, book: &OrderBook) {
+        self.bids = book.ui_levels(true);
+        self.asks = book.ui_levels(false);
+        self.spread = book.spread().unwrap_or(0.0);
+    }
+
     pub fn show(&mut self, ui: &mut egui::Ui) {
         ui.label("Market Depth");
         
@@ -504,6 +520,7 @@ mod tests {
             volume: 1000.0,
             liquidity: 50000.0,
             tick: 0,
+            fee_growth_global: None,
         };
         
         history.add_price_point(point);
@@ -0,0 +1,275 @@
+// src/ui/live_swap.rs
+
+use eframe::egui::{self, *};
+use solana_program::pubkey::Pubkey;
+use crate::config::WalletConfig;
+use crate::connection::websocket::TransactionStatus;
+use crate::data::priority_fees::{PriorityFeeEstimate, PriorityLevel};
+use crate::dex::whirlpool::instruction::SwapInstructionArgs;
+use crate::dex::whirlpool::swap::{swap_quote, SwapParams};
+use crate::trading::paper::Side;
+use crate::ui::paper_trading::PaperPoolContext;
+use crate::wallet::PriorityFee;
+
+/// A swap the user confirmed, to be signed and sent by the caller.
+#[derive(Clone, Debug)]
+pub struct LiveSwapRequest {
+    pub id: u64,
+    pub pool: Pubkey,
+    pub args: SwapInstructionArgs,
+    pub priority_fee: PriorityFee,
+}
+
+struct PendingSwap {
+    request: LiveSwapRequest,
+    summary: String,
+}
+
+struct Submission {
+    id: u64,
+    summary: String,
+    signature: Option<String>,
+    status: TransactionStatus,
+}
+
+// Real swaps from the loaded keypair. Nothing is sent until the user confirms the quoted
+// amounts in a dialog.
+pub struct LiveSwapPanel {
+    pub side: Side,
+    pub amount_input: String,
+    pub slippage_input: String,
+    /// Fee level to pay from recent pool fees; `None` uses `priority_fee_input`.
+    pub fee_level: Option<PriorityLevel>,
+    pub priority_fee_input: String,
+    pub compute_unit_limit: u32,
+    pending: Option<PendingSwap>,
+    submissions: Vec<Submission>,
+    next_id: u64,
+    pub status: String,
+}
+
+impl Default for LiveSwapPanel {
+    fn default() -> Self {
+        Self::new(&WalletConfig::default())
+    }
+}
+
+impl LiveSwapPanel {
+    pub fn new(config: &WalletConfig) -> Self {
+        Self {
+            side: Side::Buy,
+            amount_input: String::new(),
+            slippage_input: "1.0".to_string(),
+            fee_level: Some(PriorityLevel::Medium),
+            priority_fee_input: config.priority_fee_micro_lamports.to_string(),
+            compute_unit_limit: config.compute_unit_limit,
+            pending: None,
+            submissions: Vec::new(),
+            next_id: 0,
+            status: String::new(),
+        }
+    }
+
+    pub fn on_submitted(&mut self, id: u64, signature: String) {
+        if let Some(submission) = self.submissions.iter_mut().find(|s| s.id == id) {
+            submission.signature = Some(signature);
+        }
+    }
+
+    pub fn on_status(&mut self, id: u64, status: TransactionStatus) {
+        if let Some(submission) = self.submissions.iter_mut().find(|s| s.id == id) {
+            submission.status = status;
+        }
+    }
+
+    /// Draws the panel and returns a swap once the user confirms it. `wallet` is the
+    /// loaded keypair's address, if any; `fee_estimate` is for the pool in `context`.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        context: Option<&PaperPoolContext>,
+        fee_estimate: Option<&PriorityFeeEstimate>,
+        wallet: Option<Pubkey>,
+    ) -> Option<LiveSwapRequest> {
+        ui.heading("Live swap");
+        let Some(wallet) = wallet else {
+            ui.label("No wallet loaded. Set wallet.keypair_path in the config to trade live.");
+            return None;
+        };
+        ui.label(format!("Wallet: {}", wallet));
+
+        match context {
+            Some(context) => self.swap_form(ui, context, fee_estimate),
+            None => {
+                ui.label("Select a Whirlpool with loaded tick arrays to swap");
+            }
+        }
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+
+        let confirmed = self.confirmation_dialog(ui.ctx());
+
+        if !self.submissions.is_empty() {
+            ui.separator();
+            Grid::new("live_swap_submissions").striped(true).show(ui, |ui| {
+                for header in ["Swap", "Signature", "Status"] {
+                    ui.label(header);
+                }
+                ui.end_row();
+                for submission in self.submissions.iter().rev() {
+                    ui.label(&submission.summary);
+                    match &submission.signature {
+                        Some(signature) => {
+                            ui.hyperlink_to(
+                                format!("{}..", &signature[..8]),
+                                format!("https://explorer.solana.com/tx/{}", signature),
+                            );
+                        }
+                        None => {
+                            ui.label("-");
+                        }
+                    }
+                    let (text, color) = match &submission.status {
+                        TransactionStatus::Pending => ("Pending".to_string(), Color32::GRAY),
+                        TransactionStatus::Processed => ("Processed".to_string(), Color32::YELLOW),
+                        TransactionStatus::Confirmed => ("Confirmed".to_string(), Color32::from_rgb(0, 200, 0)),
+                        TransactionStatus::Finalized => ("Finalized".to_string(), Color32::from_rgb(0, 200, 0)),
+                        TransactionStatus::Failed(e) => (format!("Failed: {}", e), Color32::from_rgb(200, 80, 80)),
+                        TransactionStatus::Expired => ("Expired".to_string(), Color32::from_rgb(200, 80, 80)),
+                    };
+                    ui.colored_label(color, text);
+                    ui.end_row();
+                }
+            });
+        }
+
+        confirmed
+    }
+
+    fn swap_form(&mut self, ui: &mut egui::Ui, context: &PaperPoolContext, fee_estimate: Option<&PriorityFeeEstimate>) {
+        let pool = &context.pool;
+        let (symbol_in, symbol_out, decimals_in, decimals_out) = match self.side {
+            Side::Buy => (&context.symbol_b, &context.symbol_a, pool.decimals_b, pool.decimals_a),
+            Side::Sell => (&context.symbol_a, &context.symbol_b, pool.decimals_a, pool.decimals_b),
+        };
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.side, Side::Buy, format!("Buy {}", context.symbol_a));
+            ui.selectable_value(&mut self.side, Side::Sell, format!("Sell {}", context.symbol_a));
+        });
+        ui.horizontal(|ui| {
+            ui.label(format!("Spend ({}):", symbol_in));
+            ui.add(TextEdit::singleline(&mut self.amount_input).desired_width(120.0));
+            ui.label("Slippage %:");
+            ui.add(TextEdit::singleline(&mut self.slippage_input).desired_width(50.0));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Priority fee:");
+            if let Some(estimate) = fee_estimate {
+                for level in PriorityLevel::ALL {
+                    let text = format!("{} ({})", level.name(), estimate.fee(level));
+                    ui.selectable_value(&mut self.fee_level, Some(level), text);
+                }
+            }
+            ui.selectable_value(&mut self.fee_level, None, "Custom");
+            if self.fee_level.is_none() || fee_estimate.is_none() {
+                ui.add(TextEdit::singleline(&mut self.priority_fee_input).desired_width(80.0));
+                ui.label("µlamports/CU");
+            }
+        });
+
+        if !ui.add_enabled(self.pending.is_none(), Button::new("Review swap")).clicked() {
+            return;
+        }
+        let fee_per_unit = match self.fee_level.zip(fee_estimate) {
+            Some((level, estimate)) => Ok(estimate.fee(level)),
+            None => self.priority_fee_input.trim().parse::<u64>(),
+        };
+        let parsed = (
+            self.amount_input.trim().parse::<f64>(),
+            self.slippage_input.trim().parse::<f64>(),
+            fee_per_unit,
+        );
+        let (amount, slippage, micro_lamports_per_unit) = match parsed {
+            (Ok(amount), Ok(slippage), Ok(fee)) if amount > 0.0 && (0.0..=100.0).contains(&slippage) => {
+                (amount, slippage, fee)
+            }
+            _ => {
+                self.status = "Enter an amount, a slippage between 0 and 100% and a priority fee".to_string();
+                return;
+            }
+        };
+
+        let raw = (amount * 10f64.powi(decimals_in as i32)).floor() as u64;
+        let params = SwapParams::exact_in(raw, self.side.a_to_b());
+        let args = swap_quote(pool.whirlpool, pool.tick_arrays, params)
+            .and_then(|quote| SwapInstructionArgs::from_quote(pool.whirlpool, params, &quote, slippage));
+        let args = match args {
+            Ok(args) => args,
+            Err(e) => {
+                self.status = format!("Quote failed: {}", e);
+                return;
+            }
+        };
+
+        let priority_fee = PriorityFee {
+            compute_unit_limit: self.compute_unit_limit,
+            micro_lamports_per_unit,
+        };
+        self.next_id += 1;
+        let min_out = args.other_amount_threshold as f64 / 10f64.powi(decimals_out as i32);
+        self.pending = Some(PendingSwap {
+            summary: format!("{} {} for at least {:.6} {}", amount, symbol_in, min_out, symbol_out),
+            request: LiveSwapRequest {
+                id: self.next_id,
+                pool: pool.address,
+                args,
+                priority_fee,
+            },
+        });
+        self.status.clear();
+    }
+
+    fn confirmation_dialog(&mut self, ctx: &Context) -> Option<LiveSwapRequest> {
+        let pending = self.pending.as_ref()?;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        Window::new("Confirm live swap")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(RichText::new("This sends a real transaction from your wallet.").strong());
+                ui.label(format!("Swap {}", pending.summary));
+                ui.label(format!("Pool: {}", pending.request.pool));
+                let fee = pending.request.priority_fee;
+                ui.label(format!(
+                    "Priority fee: up to {} lamports ({} CU at {} µlamports)",
+                    fee.max_lamports(),
+                    fee.compute_unit_limit,
+                    fee.micro_lamports_per_unit
+                ));
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Sign and send").clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+
+        if cancelled {
+            self.pending = None;
+            return None;
+        }
+        if !confirmed {
+            return None;
+        }
+        let pending = self.pending.take()?;
+        self.submissions.push(Submission {
+            id: pending.request.id,
+            summary: pending.summary,
+            signature: None,
+            status: TransactionStatus::Pending,
+        });
+        Some(pending.request)
+    }
+}
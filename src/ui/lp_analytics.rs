@@ -0,0 +1,152 @@
+// src/ui/lp_analytics.rs
+
+use eframe::egui::{self, *};
+use crate::data::lp_analytics::{estimate_fee_apr, impermanent_loss};
+use crate::data::price_data::{whirlpool_math, PriceHistory};
+use crate::dex::whirlpool::state::Whirlpool;
+use crate::dex::whirlpool::tick_math::floor_to_initializable_tick;
+
+/// The pool the panel analyses, with what the app knows about it.
+pub struct LpPoolContext<'a> {
+    pub whirlpool: &'a Whirlpool,
+    pub history: Option<&'a PriceHistory>,
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+    pub symbol_b: String,
+}
+
+// What-if panel for a Whirlpool range: impermanent loss against holding and fee APR
+pub struct LpAnalyticsPanel {
+    pub lower_price: String,
+    pub upper_price: String,
+    pub entry_price: String,
+    pub deposit: String,
+}
+
+impl Default for LpAnalyticsPanel {
+    fn default() -> Self {
+        Self {
+            lower_price: String::new(),
+            upper_price: String::new(),
+            entry_price: String::new(),
+            deposit: "1000".to_string(),
+        }
+    }
+}
+
+fn parse_price(input: &str) -> Option<f64> {
+    input.trim().parse::<f64>().ok().filter(|p| *p > 0.0)
+}
+
+impl LpAnalyticsPanel {
+    pub fn show(&mut self, ui: &mut egui::Ui, pool: Option<LpPoolContext>) {
+        let Some(pool) = pool else {
+            ui.label("Select a Whirlpool with known token decimals to analyse a range");
+            return;
+        };
+        let current_price = whirlpool_math::calculate_price_from_whirlpool(
+            pool.whirlpool,
+            pool.decimals_a,
+            pool.decimals_b,
+        );
+
+        Grid::new("lp_analytics_inputs").show(ui, |ui| {
+            ui.label("Range:");
+            ui.horizontal(|ui| {
+                ui.add(TextEdit::singleline(&mut self.lower_price).hint_text("lower").desired_width(90.0));
+                ui.label("-");
+                ui.add(TextEdit::singleline(&mut self.upper_price).hint_text("upper").desired_width(90.0));
+            });
+            ui.end_row();
+
+            ui.label("Entry price:");
+            ui.add(
+                TextEdit::singleline(&mut self.entry_price)
+                    .hint_text(format!("{:.6}", current_price))
+                    .desired_width(120.0),
+            );
+            ui.end_row();
+
+            ui.label(format!("Deposit ({}):", pool.symbol_b));
+            ui.add(TextEdit::singleline(&mut self.deposit).desired_width(120.0));
+            ui.end_row();
+        });
+
+        let (Some(lower), Some(upper)) = (parse_price(&self.lower_price), parse_price(&self.upper_price)) else {
+            ui.label("Enter a price range");
+            return;
+        };
+        let entry_price = if self.entry_price.trim().is_empty() {
+            current_price
+        } else {
+            match parse_price(&self.entry_price) {
+                Some(price) => price,
+                None => {
+                    ui.label("Invalid entry price");
+                    return;
+                }
+            }
+        };
+        let deposit = parse_price(&self.deposit).unwrap_or(0.0);
+
+        // Snap to ticks the position could actually be opened at
+        let spacing = pool.whirlpool.tick_spacing;
+        let to_tick = |price: f64| {
+            let tick = whirlpool_math::price_to_tick_index(price, pool.decimals_a, pool.decimals_b);
+            floor_to_initializable_tick(tick, spacing)
+        };
+        let (tick_lower, tick_upper) = (to_tick(lower), to_tick(upper));
+        if tick_lower >= tick_upper {
+            ui.label("The range must span at least one tick spacing");
+            return;
+        }
+
+        ui.separator();
+        ui.label(format!(
+            "Ticks {} to {} ({:.6} - {:.6})",
+            tick_lower,
+            tick_upper,
+            whirlpool_math::tick_to_price(tick_lower, pool.decimals_a, pool.decimals_b),
+            whirlpool_math::tick_to_price(tick_upper, pool.decimals_a, pool.decimals_b),
+        ));
+
+        match impermanent_loss(tick_lower, tick_upper, entry_price, current_price, pool.decimals_a, pool.decimals_b) {
+            Some(il) => {
+                let color = if il.loss < -1e-9 {
+                    Color32::from_rgb(200, 80, 80)
+                } else {
+                    ui.visuals().text_color()
+                };
+                ui.colored_label(color, format!("Impermanent loss: {:.2}%", il.loss * 100.0));
+                ui.label(format!(
+                    "Position: {:.2} {} vs HODL: {:.2} {}",
+                    il.position_value * deposit,
+                    pool.symbol_b,
+                    il.hodl_value * deposit,
+                    pool.symbol_b,
+                ));
+            }
+            None => {
+                ui.label("Impermanent loss: -");
+            }
+        }
+
+        let fee_apr = pool
+            .history
+            .and_then(|history| estimate_fee_apr(history, tick_lower, tick_upper, pool.decimals_a, pool.decimals_b));
+        match fee_apr {
+            Some(fees) => {
+                ui.label(format!(
+                    "Fee APR: {:.2}% (over {:.1}h, {:.0}% in range)",
+                    fees.apr * 100.0,
+                    fees.window_seconds as f64 / 3600.0,
+                    fees.time_in_range * 100.0,
+                ));
+                ui.label(format!("Fees on deposit over the window: {:.4} {}", fees.fee_return * deposit, pool.symbol_b));
+            }
+            None => {
+                ui.label("Fee APR: waiting for fee growth history");
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+// src/ui/paper_trading.rs
+
+use eframe::egui::{self, *};
+use solana_program::pubkey::Pubkey;
+use crate::trading::orders::{OrderKind, OrderStatus, PaperAccount};
+use crate::trading::paper::{LivePool, Side};
+use crate::utils::{current_timestamp, format_timestamp};
+
+/// The active pool with display names for its tokens.
+pub struct PaperPoolContext<'a> {
+    pub pool: LivePool<'a>,
+    pub symbol_a: String,
+    pub symbol_b: String,
+}
+
+// Orders tab: market and resting orders against live pool state, virtual balances and the
+// trade blotter
+pub struct PaperTradingView {
+    pub account: PaperAccount,
+    pub side: Side,
+    /// `None` places a market order.
+    pub order_kind: Option<OrderKind>,
+    pub amount_input: String,
+    pub price_input: String,
+    pub deposit_input: String,
+    pub status: String,
+}
+
+impl Default for PaperTradingView {
+    fn default() -> Self {
+        Self {
+            account: PaperAccount::default(),
+            side: Side::Buy,
+            order_kind: None,
+            amount_input: String::new(),
+            price_input: String::new(),
+            deposit_input: "1000".to_string(),
+            status: String::new(),
+        }
+    }
+}
+
+fn status_text(status: &OrderStatus) -> String {
+    match status {
+        OrderStatus::Open => "Open".to_string(),
+        OrderStatus::PartiallyFilled => "Partially filled".to_string(),
+        OrderStatus::Filled => "Filled".to_string(),
+        OrderStatus::Cancelled => "Cancelled".to_string(),
+        OrderStatus::Rejected(reason) => format!("Rejected: {}", reason),
+    }
+}
+
+fn to_raw(ui_amount: f64, decimals: u8) -> u64 {
+    (ui_amount * 10f64.powi(decimals as i32)).floor() as u64
+}
+
+fn to_ui(amount: u64, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(decimals as i32)
+}
+
+// Raw amounts are shown as-is for mints whose decimals are unknown
+fn format_amount(amount: u64, decimals: Option<u8>) -> String {
+    match decimals {
+        Some(decimals) => format!("{:.4}", to_ui(amount, decimals)),
+        None => amount.to_string(),
+    }
+}
+
+impl PaperTradingView {
+    /// Draws the tab and returns whether the account changed. `symbol` and `decimals`
+    /// describe mints of earlier fills, which may belong to other pools.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        context: Option<PaperPoolContext>,
+        symbol: impl Fn(&Pubkey) -> String,
+        decimals: impl Fn(&Pubkey) -> Option<u8>,
+    ) -> bool {
+        ui.heading("Paper trading");
+
+        let mut changed = match &context {
+            Some(context) => self.order_form(ui, context),
+            None => {
+                ui.label("Select a Whirlpool with loaded tick arrays to place orders");
+                false
+            }
+        };
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+
+        ui.separator();
+        ui.label(RichText::new("Balances").strong());
+        let engine = &self.account.engine;
+        let mut balances: Vec<(&Pubkey, &u64)> = engine.balances().iter().collect();
+        balances.sort_by_key(|(mint, _)| symbol(mint));
+        let fees = engine.fees_paid();
+        Grid::new("paper_balances").striped(true).show(ui, |ui| {
+            ui.label("Token");
+            ui.label("Balance");
+            ui.label("Fees paid");
+            ui.end_row();
+            for (mint, amount) in balances {
+                ui.label(symbol(mint));
+                ui.label(format_amount(*amount, decimals(mint)));
+                ui.label(format_amount(fees.get(mint).copied().unwrap_or(0), decimals(mint)));
+                ui.end_row();
+            }
+        });
+        if ui.button("Reset account").clicked() {
+            self.account = PaperAccount::default();
+            self.status = "Paper account reset".to_string();
+            changed = true;
+        }
+
+        ui.separator();
+        ui.label(RichText::new("Orders").strong());
+        Grid::new("paper_orders").striped(true).show(ui, |ui| {
+            for header in ["#", "Type", "Side", "Trigger", "Filled", "Status", ""] {
+                ui.label(header);
+            }
+            ui.end_row();
+
+            let mut cancelled = None;
+            for order in self.account.orders.orders().iter().rev() {
+                ui.label(order.id.to_string());
+                ui.label(order.kind.name());
+                ui.label(match order.side {
+                    Side::Buy => "Buy",
+                    Side::Sell => "Sell",
+                });
+                ui.label(format!("{:.6}", order.trigger_price));
+                ui.label(format!("{:.1}%", order.filled_in as f64 / order.amount as f64 * 100.0));
+                ui.label(status_text(&order.status));
+                if order.status.is_active() && ui.button("Cancel").clicked() {
+                    cancelled = Some(order.id);
+                }
+                ui.end_row();
+            }
+            if let Some(id) = cancelled {
+                changed |= self.account.orders.cancel(id, current_timestamp());
+            }
+        });
+
+        ui.separator();
+        ui.label(RichText::new("Trades").strong());
+        ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("paper_blotter_grid").striped(true).show(ui, |ui| {
+                for header in ["#", "Time", "Side", "Paid", "Received", "Fee", "Price", "Mid", "Slippage"] {
+                    ui.label(header);
+                }
+                ui.end_row();
+
+                for fill in self.account.engine.fills().iter().rev() {
+                    let (side_text, color) = match fill.side {
+                        Side::Buy => ("Buy", Color32::from_rgb(0, 200, 0)),
+                        Side::Sell => ("Sell", Color32::from_rgb(200, 80, 80)),
+                    };
+                    ui.label(fill.id.to_string());
+                    ui.label(format_timestamp(fill.timestamp));
+                    ui.colored_label(color, side_text);
+                    let (decimals_in, decimals_out) = (decimals(&fill.mint_in), decimals(&fill.mint_out));
+                    ui.label(format!("{} {}", format_amount(fill.amount_in, decimals_in), symbol(&fill.mint_in)));
+                    ui.label(format!("{} {}", format_amount(fill.amount_out, decimals_out), symbol(&fill.mint_out)));
+                    ui.label(format!("{} {}", format_amount(fill.fee_amount, decimals_in), symbol(&fill.mint_in)));
+                    ui.label(format!("{:.6}", fill.fill_price));
+                    ui.label(format!("{:.6}", fill.mid_price));
+                    ui.label(format!("{:.3}%", fill.slippage * 100.0));
+                    ui.end_row();
+                }
+            });
+        });
+
+        changed
+    }
+
+    fn order_form(&mut self, ui: &mut egui::Ui, context: &PaperPoolContext) -> bool {
+        let mut changed = false;
+        let pool = &context.pool;
+        let whirlpool = pool.whirlpool;
+        let (mint_in, symbol_in, decimals_in) = match self.side {
+            Side::Buy => (whirlpool.token_mint_b, &context.symbol_b, pool.decimals_b),
+            Side::Sell => (whirlpool.token_mint_a, &context.symbol_a, pool.decimals_a),
+        };
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.side, Side::Buy, format!("Buy {}", context.symbol_a));
+            ui.selectable_value(&mut self.side, Side::Sell, format!("Sell {}", context.symbol_a));
+            ui.separator();
+            ui.selectable_value(&mut self.order_kind, None, "Market");
+            for kind in OrderKind::ALL {
+                ui.selectable_value(&mut self.order_kind, Some(kind), kind.name());
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(format!("Spend ({}):", symbol_in));
+            ui.add(TextEdit::singleline(&mut self.amount_input).desired_width(120.0));
+            ui.label(format!(
+                "available {:.4}",
+                to_ui(self.account.engine.balance(&mint_in), decimals_in)
+            ));
+            if let Some(kind) = self.order_kind {
+                ui.label(format!("at ({}):", context.symbol_b));
+                ui.add(TextEdit::singleline(&mut self.price_input).desired_width(100.0));
+                if ui.button(format!("Place {}", kind.name())).clicked() {
+                    match (self.amount_input.trim().parse::<f64>(), self.price_input.trim().parse::<f64>()) {
+                        (Ok(amount), Ok(price)) if amount > 0.0 => {
+                            let raw = to_raw(amount, decimals_in);
+                            let placed = self.account.orders.place(
+                                pool.address,
+                                kind,
+                                self.side,
+                                price,
+                                raw,
+                                current_timestamp(),
+                            );
+                            self.status = match placed {
+                                Ok(id) => {
+                                    changed = true;
+                                    format!("Placed order #{}", id)
+                                }
+                                Err(e) => format!("Order rejected: {}", e),
+                            };
+                        }
+                        _ => self.status = "Enter an amount to spend and a price".to_string(),
+                    }
+                }
+            } else if ui.button("Market order").clicked() {
+                match self.amount_input.trim().parse::<f64>() {
+                    Ok(amount) if amount > 0.0 => {
+                        let raw = to_raw(amount, decimals_in);
+                        let filled = self.account.engine.market_order(pool, self.side, raw, current_timestamp());
+                        self.status = match filled {
+                            Ok(fill) => {
+                                changed = true;
+                                format!(
+                                    "Filled #{} at {:.6} ({:.3}% slippage)",
+                                    fill.id,
+                                    fill.fill_price,
+                                    fill.slippage * 100.0
+                                )
+                            }
+                            Err(e) => format!("Order rejected: {}", e),
+                        };
+                    }
+                    _ => self.status = "Enter an amount to spend".to_string(),
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Deposit:");
+            ui.add(TextEdit::singleline(&mut self.deposit_input).desired_width(120.0));
+            if let Ok(amount) = self.deposit_input.trim().parse::<f64>() {
+                if ui.button(format!("+ {}", context.symbol_a)).clicked() {
+                    self.account.engine.deposit(whirlpool.token_mint_a, to_raw(amount, pool.decimals_a));
+                    changed = true;
+                }
+                if ui.button(format!("+ {}", context.symbol_b)).clicked() {
+                    self.account.engine.deposit(whirlpool.token_mint_b, to_raw(amount, pool.decimals_b));
+                    changed = true;
+                }
+            }
+        });
+
+        changed
+    }
+}
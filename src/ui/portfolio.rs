@@ -0,0 +1,136 @@
+// src/ui/portfolio.rs
+
+use eframe::egui::{self, *};
+use solana_program::pubkey::Pubkey;
+use crate::data::portfolio::Portfolio;
+use crate::data::price_data::whirlpool_math;
+
+// Wallet portfolio tab: token balances and Whirlpool positions of one owner
+pub struct PortfolioView {
+    pub owner_input: String,
+    pub status: String,
+    pub loading: bool,
+    pub portfolio: Option<Portfolio>,
+}
+
+impl Default for PortfolioView {
+    fn default() -> Self {
+        Self {
+            owner_input: String::new(),
+            status: "Enter a wallet address".to_string(),
+            loading: false,
+            portfolio: None,
+        }
+    }
+}
+
+fn format_usd(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("${:.2}", v))
+}
+
+impl PortfolioView {
+    pub fn set_portfolio(&mut self, portfolio: Portfolio) {
+        self.status = format!(
+            "{} positions, {} tokens",
+            portfolio.positions.len(),
+            portfolio.balances.len()
+        );
+        self.loading = false;
+        self.portfolio = Some(portfolio);
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.status = format!("Failed to load portfolio: {}", error);
+        self.loading = false;
+    }
+
+    /// Draws the tab. Returns the wallet to load when the user asks for one. `symbol` names a
+    /// mint for display.
+    pub fn show(&mut self, ui: &mut egui::Ui, symbol: impl Fn(&Pubkey) -> String) -> Option<Pubkey> {
+        let mut requested = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Wallet:");
+            ui.add(TextEdit::singleline(&mut self.owner_input).desired_width(420.0));
+            let load = ui.add_enabled(!self.loading, Button::new("Load"));
+            if load.clicked() {
+                match self.owner_input.trim().parse::<Pubkey>() {
+                    Ok(owner) => {
+                        self.loading = true;
+                        self.status = "Loading...".to_string();
+                        requested = Some(owner);
+                    }
+                    Err(_) => self.status = "Invalid wallet address".to_string(),
+                }
+            }
+            ui.label(&self.status);
+        });
+
+        let Some(portfolio) = &self.portfolio else {
+            return requested;
+        };
+
+        ui.separator();
+        ui.heading(format!("Total: ${:.2}", portfolio.total_usd()));
+
+        ScrollArea::vertical().show(ui, |ui| {
+            ui.label(RichText::new("Whirlpool positions").strong());
+            Grid::new("portfolio_positions").striped(true).show(ui, |ui| {
+                ui.label("Pool");
+                ui.label("Range");
+                ui.label("Status");
+                ui.label("Holdings");
+                ui.label("Unclaimed fees");
+                ui.label("Value");
+                ui.end_row();
+
+                for position in &portfolio.positions {
+                    let symbol_a = symbol(&position.whirlpool.token_mint_a);
+                    let symbol_b = symbol(&position.whirlpool.token_mint_b);
+                    let (amount_a, amount_b) = position.ui_amounts();
+                    let (fee_a, fee_b) = position.ui_fees();
+                    let lower = whirlpool_math::tick_to_price(
+                        position.position.tick_lower_index,
+                        position.decimals_a,
+                        position.decimals_b,
+                    );
+                    let upper = whirlpool_math::tick_to_price(
+                        position.position.tick_upper_index,
+                        position.decimals_a,
+                        position.decimals_b,
+                    );
+
+                    ui.label(format!("{}/{}", symbol_a, symbol_b));
+                    ui.label(format!("{:.4} - {:.4}", lower, upper));
+                    if position.in_range() {
+                        ui.colored_label(Color32::from_rgb(0, 200, 0), "In range");
+                    } else {
+                        ui.colored_label(Color32::from_rgb(200, 150, 0), "Out of range");
+                    }
+                    ui.label(format!("{:.4} {} + {:.4} {}", amount_a, symbol_a, amount_b, symbol_b));
+                    ui.label(format!("{:.4} {} + {:.4} {}", fee_a, symbol_a, fee_b, symbol_b));
+                    ui.label(format_usd(portfolio.position_usd(position)));
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+            ui.label(RichText::new("Token balances").strong());
+            Grid::new("portfolio_balances").striped(true).show(ui, |ui| {
+                ui.label("Token");
+                ui.label("Amount");
+                ui.label("Value");
+                ui.end_row();
+
+                for balance in &portfolio.balances {
+                    ui.label(symbol(&balance.mint));
+                    ui.label(format!("{:.4}", balance.ui_amount()));
+                    ui.label(format_usd(portfolio.balance_usd(balance)));
+                    ui.end_row();
+                }
+            });
+        });
+
+        requested
+    }
+}
@@ -0,0 +1,32 @@
+// src/ui/priority_fees.rs
+
+use eframe::egui::{self, *};
+use crate::data::priority_fees::{PriorityFeeEstimate, PriorityLevel};
+
+/// Suggested priority fees for the active pool, one column per level.
+pub fn show_priority_fees(ui: &mut egui::Ui, estimate: Option<&PriorityFeeEstimate>) {
+    ui.label(RichText::new("Priority fees (µlamports/CU)").strong());
+    let Some(estimate) = estimate else {
+        ui.label("No recent fee data for this pool");
+        return;
+    };
+
+    Grid::new("priority_fee_grid").striped(true).show(ui, |ui| {
+        for level in PriorityLevel::ALL {
+            ui.label(format!("{} (p{})", level.name(), level.percentile()));
+        }
+        ui.label("Max");
+        ui.end_row();
+        for level in PriorityLevel::ALL {
+            ui.label(estimate.fee(level).to_string());
+        }
+        ui.label(estimate.max.to_string());
+        ui.end_row();
+    });
+    ui.label(format!(
+        "{:.0}% of the last {} slots had paid priority on this pool (latest slot {})",
+        estimate.contested_share * 100.0,
+        estimate.slots,
+        estimate.latest_slot
+    ));
+}
@@ -0,0 +1,504 @@
+// src/utils.rs - Utility functions
+
+use std::time::{SystemTime, UNIX_EPOCH};
+use solana_program::pubkey::Pubkey;
+use crate::dex::pool::DexPool;
+
+pub fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+pub fn format_timestamp(timestamp: u64) -> String {
+    let datetime = chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .unwrap_or_default();
+    datetime.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+pub fn format_price(price: f64, decimals: u8) -> String {
+    format!("${:.precision$}", price, precision = decimals as usize)
+}
+
+pub fn format_volume(volume: f64) -> String {
+    if volume >= 1_000_000.0 {
+        format!("${:.1}M", volume / 1_000_000.0)
+    } else if volume >= 1_000.0 {
+        format!("${:.1}K", volume / 1_000.0)
+    } else {
+        format!("${:.2}", volume)
+    }
+}
+
+pub fn calculate_percentage_change(old_value: f64, new_value: f64) -> f64 {
+    if old_value == 0.0 {
+        0.0
+    } else {
+        ((new_value - old_value) / old_value) * 100.0
+    }
+}
+
+pub fn is_valid_pubkey(pubkey_str: &str) -> bool {
+    pubkey_str.parse::<Pubkey>().is_ok()
+}
+
+// Price calculation utilities
+pub mod price_utils {
+    use crate::dex::whirlpool::state::Whirlpool;
+
+    pub fn calculate_pool_tvl(
+        whirlpool: &Whirlpool,
+        price_a_usd: f64,
+        price_b_usd: f64,
+        decimals_a: u8,
+        decimals_b: u8,
+    ) -> f64 {
+        // Simplified TVL calculation
+        // In reality, this would require complex math to determine exact token amounts
+        let liquidity = whirlpool.liquidity as f64;
+        let sqrt_price = whirlpool.sqrt_price as f64 / (1u128 << 64) as f64;
+        
+        // Approximate calculation - would need more sophisticated math for accuracy
+        let estimated_value_a = liquidity / sqrt_price / 10f64.powi(decimals_a as i32) * price_a_usd;
+        let estimated_value_b = liquidity * sqrt_price / 10f64.powi(decimals_b as i32) * price_b_usd;
+        
+        estimated_value_a + estimated_value_b
+    }
+
+    pub fn calculate_fee_tier_display(fee_rate: u16) -> String {
+        let percentage = fee_rate as f64 / 10000.0; // Fee rate is in basis points
+        format!("{:.2}%", percentage)
+    }
+
+    pub fn estimate_swap_output(
+        input_amount: f64,
+        current_price: f64,
+        liquidity: f64,
+        fee_rate: u16,
+    ) -> f64 {
+        // Simplified swap calculation - real implementation would use AMM math
+        let fee_multiplier = 1.0 - (fee_rate as f64 / 10000.0);
+        let effective_input = input_amount * fee_multiplier;
+        
+        // This is a very simplified calculation
+        // Real AMM math involves sqrt price calculations and liquidity distribution
+        effective_input * current_price
+    }
+}
+
+// SPL token account helpers
+pub mod spl_token {
+    use solana_program::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+    pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+    pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+    pub const TOKEN_ACCOUNT_LEN: usize = 165;
+    pub const MINT_LEN: usize = 82;
+
+    pub fn token_program_id() -> Pubkey {
+        Pubkey::from_str(TOKEN_PROGRAM_ID).expect("Invalid token program ID")
+    }
+
+    pub fn token_2022_program_id() -> Pubkey {
+        Pubkey::from_str(TOKEN_2022_PROGRAM_ID).expect("Invalid Token-2022 program ID")
+    }
+
+    /// The associated token account of `owner` for `mint`.
+    pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program_id: &Pubkey) -> Pubkey {
+        let program_id = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID).expect("Invalid associated token program ID");
+        let seeds = &[owner.as_ref(), token_program_id.as_ref(), mint.as_ref()];
+
+        Pubkey::find_program_address(seeds, &program_id).0
+    }
+
+    /// Decimals of an SPL token (or Token-2022) mint account.
+    pub fn mint_decimals(data: &[u8]) -> Option<u8> {
+        if data.len() < MINT_LEN {
+            return None;
+        }
+        Some(data[44])
+    }
+
+    /// Raw token amount of an SPL token (or Token-2022) account.
+    pub fn token_account_amount(data: &[u8]) -> Option<u64> {
+        let bytes = data.get(64..72)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    pub fn token_account_mint(data: &[u8]) -> Option<Pubkey> {
+        let bytes: [u8; 32] = data.get(0..32)?.try_into().ok()?;
+        Some(Pubkey::new_from_array(bytes))
+    }
+
+    pub fn token_account_owner(data: &[u8]) -> Option<Pubkey> {
+        let bytes: [u8; 32] = data.get(32..64)?.try_into().ok()?;
+        Some(Pubkey::new_from_array(bytes))
+    }
+}
+
+// Compute budget instructions, placed ahead of the instructions they budget for
+pub mod compute_budget {
+    use solana_program::instruction::Instruction;
+    use solana_program::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+    pub fn compute_budget_program_id() -> Pubkey {
+        Pubkey::from_str(COMPUTE_BUDGET_PROGRAM_ID).expect("Invalid compute budget program ID")
+    }
+
+    pub fn set_compute_unit_limit(units: u32) -> Instruction {
+        let mut data = vec![2];
+        data.extend_from_slice(&units.to_le_bytes());
+        Instruction::new_with_bytes(compute_budget_program_id(), &data, vec![])
+    }
+
+    /// Priority fee, in micro-lamports per compute unit.
+    pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+        let mut data = vec![3];
+        data.extend_from_slice(&micro_lamports.to_le_bytes());
+        Instruction::new_with_bytes(compute_budget_program_id(), &data, vec![])
+    }
+}
+
+// Serde helpers that store pubkeys as base58 strings, for files meant to be read by people.
+// Use with `#[serde(with = "...")]`.
+pub mod pubkey_serde {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_program::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+
+    /// Maps keyed by pubkey, which JSON cannot key by byte arrays.
+    pub mod map {
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+        use solana_program::pubkey::Pubkey;
+        use std::collections::HashMap;
+
+        pub fn serialize<S, V>(map: &HashMap<Pubkey, V>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+            V: serde::Serialize,
+        {
+            serializer.collect_map(map.iter().map(|(key, value)| (key.to_string(), value)))
+        }
+
+        pub fn deserialize<'de, D, V>(deserializer: D) -> Result<HashMap<Pubkey, V>, D::Error>
+        where
+            D: Deserializer<'de>,
+            V: Deserialize<'de>,
+        {
+            HashMap::<String, V>::deserialize(deserializer)?
+                .into_iter()
+                .map(|(key, value)| Ok((key.parse().map_err(D::Error::custom)?, value)))
+                .collect()
+        }
+    }
+}
+
+// Error handling
+#[derive(Debug, thiserror::Error)]
+pub enum TradingTerminalError {
+    #[error("WebSocket connection error: {0}")]
+    WebSocketError(String),
+    
+    #[error("Price calculation error: {0}")]
+    PriceCalculationError(String),
+    
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+    
+    #[error("Data parsing error: {0}")]
+    DataParsingError(String),
+    
+    #[error("Network error: {0}")]
+    NetworkError(String),
+}
+
+// Performance monitoring
+pub struct PerformanceMonitor {
+    pub websocket_latency_ms: f64,
+    pub chart_update_time_ms: f64,
+    pub price_updates_per_second: f64,
+    pub memory_usage_mb: f64,
+    pub last_update_time: std::time::Instant,
+    update_count: u64,
+    start_time: std::time::Instant,
+}
+
+impl Default for PerformanceMonitor {
+    fn default() -> Self {
+        Self {
+            websocket_latency_ms: 0.0,
+            chart_update_time_ms: 0.0,
+            price_updates_per_second: 0.0,
+            memory_usage_mb: 0.0,
+            last_update_time: std::time::Instant::now(),
+            update_count: 0,
+            start_time: std::time::Instant::now(),
+        }
+    }
+}
+
+impl PerformanceMonitor {
+    pub fn record_price_update(&mut self, latency_ms: f64) {
+        self.websocket_latency_ms = latency_ms;
+        self.update_count += 1;
+        self.last_update_time = std::time::Instant::now();
+        
+        let elapsed_seconds = self.start_time.elapsed().as_secs_f64();
+        if elapsed_seconds > 0.0 {
+            self.price_updates_per_second = self.update_count as f64 / elapsed_seconds;
+        }
+    }
+
+    pub fn record_chart_update(&mut self, update_time_ms: f64) {
+        self.chart_update_time_ms = update_time_ms;
+    }
+
+    pub fn show_stats(&self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.label("Performance Monitor");
+            ui.separator();
+            ui.label(format!("WebSocket Latency: {:.1}ms", self.websocket_latency_ms));
+            ui.label(format!("Chart Update Time: {:.1}ms", self.chart_update_time_ms));
+            ui.label(format!("Updates/sec: {:.1}", self.price_updates_per_second));
+            ui.label(format!("Memory Usage: {:.1}MB", self.memory_usage_mb));
+            ui.label(format!("Last Update: {:.1}s ago", self.last_update_time.elapsed().as_secs_f32()));
+        });
+    }
+}
+
+// Arbitrage detection system
+pub struct ArbitrageDetector {
+    pub opportunities: Vec<ArbitrageOpportunity>,
+    pub min_profit_threshold: f64,
+    pub max_price_age_seconds: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArbitrageOpportunity {
+    pub token_pair: String,
+    pub buy_dex: String,
+    pub sell_dex: String,
+    pub buy_price: f64,
+    pub sell_price: f64,
+    pub profit_percentage: f64,
+    pub profit_usd: f64,
+    pub timestamp: u64,
+    pub confidence_score: f64,
+}
+
+impl ArbitrageDetector {
+    pub fn new(min_profit_threshold: f64) -> Self {
+        Self {
+            opportunities: Vec::new(),
+            min_profit_threshold,
+            max_price_age_seconds: 10, // Only consider prices from last 10 seconds
+        }
+    }
+
+    pub fn detect_opportunities(
+        &mut self,
+        prices: &std::collections::HashMap<String, std::collections::HashMap<String, f64>>,
+    ) {
+        self.opportunities.clear();
+        let current_time = current_timestamp();
+
+        // Compare prices across different DEXes for the same token pair
+        for (token_pair, dex_prices) in prices {
+            let mut dex_price_vec: Vec<(String, f64)> = dex_prices.iter()
+                .map(|(dex, price)| (dex.clone(), *price))
+                .collect();
+            
+            dex_price_vec.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+            if dex_price_vec.len() >= 2 {
+                let (buy_dex, buy_price) = &dex_price_vec[0];
+                let (sell_dex, sell_price) = &dex_price_vec[dex_price_vec.len() - 1];
+
+                let profit_percentage = (sell_price - buy_price) / buy_price * 100.0;
+                
+                if profit_percentage >= self.min_profit_threshold {
+                    self.opportunities.push(ArbitrageOpportunity {
+                        token_pair: token_pair.clone(),
+                        buy_dex: buy_dex.clone(),
+                        sell_dex: sell_dex.clone(),
+                        buy_price: *buy_price,
+                        sell_price: *sell_price,
+                        profit_percentage,
+                        profit_usd: 0.0, // Would calculate based on trade size
+                        timestamp: current_time,
+                        confidence_score: self.calculate_confidence_score(profit_percentage),
+                    });
+                }
+            }
+        }
+
+        // Sort by profit percentage
+        self.opportunities.sort_by(|a, b| 
+            b.profit_percentage.partial_cmp(&a.profit_percentage).unwrap()
+        );
+    }
+
+    /// Compares pools quoting the same token pair, buying at one pool's fee-inclusive ask and
+    /// selling at another's fee-inclusive bid. Each entry is `(pair, pool, decimals_a, decimals_b)`.
+    pub fn detect_from_pools(&mut self, pools: &[(&str, &dyn DexPool, u8, u8)]) {
+        self.opportunities.clear();
+        let current_time = current_timestamp();
+
+        for (i, (pair, buy_pool, buy_decimals_a, buy_decimals_b)) in pools.iter().enumerate() {
+            let buy_price = buy_pool.mid_price(*buy_decimals_a, *buy_decimals_b) / (1.0 - buy_pool.fee_rate());
+            if !buy_price.is_finite() || buy_price <= 0.0 {
+                continue;
+            }
+
+            for (j, (other_pair, sell_pool, sell_decimals_a, sell_decimals_b)) in pools.iter().enumerate() {
+                if i == j || pair != other_pair {
+                    continue;
+                }
+                let sell_price = sell_pool.mid_price(*sell_decimals_a, *sell_decimals_b) * (1.0 - sell_pool.fee_rate());
+                let profit_percentage = (sell_price - buy_price) / buy_price * 100.0;
+
+                if profit_percentage >= self.min_profit_threshold {
+                    self.opportunities.push(ArbitrageOpportunity {
+                        token_pair: pair.to_string(),
+                        buy_dex: buy_pool.kind().to_string(),
+                        sell_dex: sell_pool.kind().to_string(),
+                        buy_price,
+                        sell_price,
+                        profit_percentage,
+                        profit_usd: 0.0, // Would calculate based on trade size
+                        timestamp: current_time,
+                        confidence_score: self.calculate_confidence_score(profit_percentage),
+                    });
+                }
+            }
+        }
+
+        self.opportunities.sort_by(|a, b|
+            b.profit_percentage.partial_cmp(&a.profit_percentage).unwrap()
+        );
+    }
+
+    fn calculate_confidence_score(&self, profit_percentage: f64) -> f64 {
+        // Simple confidence scoring based on profit size
+        // Higher profits get higher confidence, but capped at reasonable levels
+        (profit_percentage / 10.0).min(1.0).max(0.0)
+    }
+
+    pub fn show_opportunities(&self, ui: &mut egui::Ui) {
+        ui.heading("Arbitrage Opportunities");
+        
+        if self.opportunities.is_empty() {
+            ui.label("No arbitrage opportunities detected");
+            return;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for opp in &self.opportunities {
+                ui.group(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(&opp.token_pair);
+                        ui.separator();
+                        ui.colored_label(
+                            egui::Color32::GREEN,
+                            format!("+{:.2}%", opp.profit_percentage)
+                        );
+                    });
+                    
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Buy: {} @ ${:.4}", opp.buy_dex, opp.buy_price));
+                        ui.label(format!("Sell: {} @ ${:.4}", opp.sell_dex, opp.sell_price));
+                    });
+                    
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Confidence: {:.0}%", opp.confidence_score * 100.0));
+                        ui.label(format!("Age: {}s", current_timestamp() - opp.timestamp));
+                    });
+                });
+                ui.separator();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentage_change_calculation() {
+        assert_eq!(calculate_percentage_change(100.0, 110.0), 10.0);
+        assert_eq!(calculate_percentage_change(100.0, 90.0), -10.0);
+        assert_eq!(calculate_percentage_change(0.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn test_pubkey_validation() {
+        assert!(is_valid_pubkey("So11111111111111111111111111111111111111112"));
+        assert!(!is_valid_pubkey("invalid_pubkey"));
+    }
+
+    #[test]
+    fn test_volume_formatting() {
+        assert_eq!(format_volume(1500000.0), "$1.5M");
+        assert_eq!(format_volume(1500.0), "$1.5K");
+        assert_eq!(format_volume(15.5), "$15.50");
+    }
+
+    #[test]
+    fn test_arbitrage_detection() {
+        let mut detector = ArbitrageDetector::new(1.0);
+        let mut prices = std::collections::HashMap::new();
+        
+        let mut sol_usdc_prices = std::collections::HashMap::new();
+        sol_usdc_prices.insert("Whirlpool".to_string(), 100.0);
+        sol_usdc_prices.insert("Orca".to_string(), 102.0);
+        
+        prices.insert("SOL/USDC".to_string(), sol_usdc_prices);
+        
+        detector.detect_opportunities(&prices);
+        
+        assert_eq!(detector.opportunities.len(), 1);
+        assert_eq!(detector.opportunities[0].profit_percentage, 2.0);
+    }
+
+    #[test]
+    fn test_arbitrage_detection_from_pools_is_fee_aware() {
+        use crate::dex::whirlpool::state::Whirlpool;
+
+        let pool_at = |sqrt_price: u128, fee_rate: u16| {
+            let mut data = vec![0u8; Whirlpool::LEN];
+            data[45..47].copy_from_slice(&fee_rate.to_le_bytes());
+            data[65..81].copy_from_slice(&sqrt_price.to_le_bytes());
+            Whirlpool::try_deserialize(&data).unwrap()
+        };
+        // Prices 1.0 and ~1.02 (sqrt(1.02) ~ 1.00995)
+        let cheap = pool_at(1u128 << 64, 3000);
+        let rich = pool_at((1.00995f64 * 18446744073709551616.0) as u128, 3000);
+
+        let mut detector = ArbitrageDetector::new(1.0);
+        detector.detect_from_pools(&[("SOL/USDC", &cheap, 6, 6), ("SOL/USDC", &rich, 6, 6)]);
+        assert_eq!(detector.opportunities.len(), 1);
+        let opportunity = &detector.opportunities[0];
+        assert!(opportunity.profit_percentage > 1.0 && opportunity.profit_percentage < 1.5);
+
+        // A 1% fee on each side eats the spread
+        let cheap = pool_at(1u128 << 64, 10_000);
+        let rich = pool_at((1.00995f64 * 18446744073709551616.0) as u128, 10_000);
+        detector.detect_from_pools(&[("SOL/USDC", &cheap, 6, 6), ("SOL/USDC", &rich, 6, 6)]);
+        assert!(detector.opportunities.is_empty());
+    }
+}
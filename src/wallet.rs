@@ -0,0 +1,249 @@
+// src/wallet.rs - Keypair wallet for live Whirlpool swaps
+//
+// Only built with the `wallet` cargo feature: `cargo run --features wallet`
+
+use std::time::Duration;
+use solana_program::hash::Hash;
+use solana_program::instruction::Instruction;
+use solana_program::message::Message;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use crate::connection::websocket::{encode_transaction, RpcError, SolanaHttpClient, TransactionStatus};
+use crate::dex::whirlpool::instruction::{SwapAccounts, SwapInstructionArgs, SwapInstructionBuilder};
+use crate::dex::whirlpool::state::Whirlpool;
+use crate::utils::{compute_budget, spl_token};
+
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// Polls in a row that may fail transiently, each after the client's own retries
+const MAX_FAILED_POLLS: u32 = 10;
+
+/// Compute budget for one transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriorityFee {
+    pub compute_unit_limit: u32,
+    pub micro_lamports_per_unit: u64,
+}
+
+impl PriorityFee {
+    /// Most the transaction can pay on top of the base fee, in lamports.
+    pub fn max_lamports(&self) -> u64 {
+        (self.compute_unit_limit as u128 * self.micro_lamports_per_unit as u128).div_ceil(1_000_000) as u64
+    }
+
+    fn instructions(&self) -> [Instruction; 2] {
+        [
+            compute_budget::set_compute_unit_limit(self.compute_unit_limit),
+            compute_budget::set_compute_unit_price(self.micro_lamports_per_unit),
+        ]
+    }
+}
+
+pub struct Wallet {
+    keypair: Keypair,
+}
+
+impl Wallet {
+    /// Loads a Solana CLI keypair file.
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let keypair = read_keypair_file(path).map_err(|e| format!("Failed to read keypair {}: {}", path, e))?;
+        Ok(Self { keypair })
+    }
+
+    pub fn from_keypair(keypair: Keypair) -> Self {
+        Self { keypair }
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    /// The wallet's side of a swap: its associated token accounts for both mints. Only mints
+    /// of the SPL token program are supported, and both accounts must already exist.
+    pub fn swap_accounts(&self, whirlpool: &Whirlpool) -> SwapAccounts {
+        let owner = self.pubkey();
+        let token_program = spl_token::token_program_id();
+        SwapAccounts::new(
+            owner,
+            spl_token::associated_token_address(&owner, &whirlpool.token_mint_a, &token_program),
+            spl_token::associated_token_address(&owner, &whirlpool.token_mint_b, &token_program),
+        )
+    }
+
+    /// Compute budget instructions followed by a `swapV2`, paid for and signed by this wallet.
+    pub fn swap_instructions(
+        &self,
+        whirlpool_address: Pubkey,
+        whirlpool: &Whirlpool,
+        args: &SwapInstructionArgs,
+        priority_fee: PriorityFee,
+    ) -> Vec<Instruction> {
+        let swap = SwapInstructionBuilder::new(whirlpool, whirlpool_address, self.swap_accounts(whirlpool)).swap_v2(args);
+        let mut instructions = priority_fee.instructions().to_vec();
+        instructions.push(swap);
+        instructions
+    }
+
+    /// Signs `instructions` as the only signer and fee payer, returning the wire-encoded
+    /// transaction and its signature.
+    pub fn sign(&self, instructions: &[Instruction], recent_blockhash: Hash) -> (Vec<u8>, String) {
+        let mut message = Message::new(instructions, Some(&self.pubkey()));
+        message.recent_blockhash = recent_blockhash;
+        let signature = self.keypair.sign_message(&message.serialize());
+        let bytes: [u8; 64] = signature.into();
+        (encode_transaction(&message, &[bytes]), signature.to_string())
+    }
+
+    /// Signs and sends `instructions` with a fresh blockhash. Returns the signature and the
+    /// last block height the transaction can land at.
+    pub async fn send(
+        &self,
+        http: &SolanaHttpClient,
+        instructions: &[Instruction],
+    ) -> Result<(String, u64), Box<dyn std::error::Error + Send + Sync>> {
+        let (blockhash, last_valid_block_height) = http.get_latest_blockhash().await?;
+        let (transaction, signature) = self.sign(instructions, blockhash);
+        let sent = http.send_transaction(&transaction).await?;
+        debug_assert_eq!(sent, signature);
+        Ok((signature, last_valid_block_height))
+    }
+}
+
+/// Polls a sent transaction until it is confirmed, fails, or its blockhash expires.
+/// `on_status` sees every status change, so callers can surface `Processed` early. A poll
+/// that fails transiently is repeated; the node keeps rebroadcasting the transaction.
+pub async fn wait_for_confirmation(
+    http: &SolanaHttpClient,
+    signature: &str,
+    last_valid_block_height: u64,
+    mut on_status: impl FnMut(&TransactionStatus),
+) -> Result<TransactionStatus, Box<dyn std::error::Error + Send + Sync>> {
+    let signatures = [signature.to_string()];
+    let mut last = TransactionStatus::Pending;
+    let mut failed_polls = 0;
+    loop {
+        let (status, expired) = match poll_confirmation(http, &signatures, last_valid_block_height).await {
+            Ok(polled) => {
+                failed_polls = 0;
+                polled
+            }
+            Err(e) if is_transient(&*e) && failed_polls < MAX_FAILED_POLLS => {
+                failed_polls += 1;
+                tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if status != last {
+            on_status(&status);
+            last = status.clone();
+        }
+
+        match status {
+            TransactionStatus::Confirmed | TransactionStatus::Finalized | TransactionStatus::Failed(_) => {
+                return Ok(status);
+            }
+            TransactionStatus::Pending if expired => {
+                on_status(&TransactionStatus::Expired);
+                return Ok(TransactionStatus::Expired);
+            }
+            _ => tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await,
+        }
+    }
+}
+
+// The transaction's status, and whether its blockhash has expired while it is still pending
+async fn poll_confirmation(
+    http: &SolanaHttpClient,
+    signatures: &[String],
+    last_valid_block_height: u64,
+) -> Result<(TransactionStatus, bool), Box<dyn std::error::Error + Send + Sync>> {
+    let status = http
+        .get_signature_statuses(signatures)
+        .await?
+        .pop()
+        .unwrap_or(TransactionStatus::Pending);
+    let expired = status == TransactionStatus::Pending && http.get_block_height().await? > last_valid_block_height;
+    Ok((status, expired))
+}
+
+fn is_transient(error: &(dyn std::error::Error + 'static)) -> bool {
+    error.downcast_ref::<RpcError>().is_some_and(RpcError::is_transient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use solana_sdk::signature::Signature;
+
+    #[test]
+    fn test_signed_swap_transaction() {
+        let wallet = Wallet::from_keypair(Keypair::new());
+        let mut data = vec![0u8; Whirlpool::LEN];
+        data[41..43].copy_from_slice(&64u16.to_le_bytes());
+        let whirlpool = Whirlpool::try_deserialize(&data).unwrap();
+        let args = SwapInstructionArgs {
+            amount: 1_000,
+            other_amount_threshold: 990,
+            sqrt_price_limit: crate::dex::whirlpool::constants::MIN_SQRT_PRICE_X64,
+            amount_specified_is_input: true,
+            a_to_b: true,
+        };
+        let priority_fee = PriorityFee {
+            compute_unit_limit: 200_000,
+            micro_lamports_per_unit: 25_000,
+        };
+        assert_eq!(priority_fee.max_lamports(), 5_000);
+
+        let instructions = wallet.swap_instructions(Pubkey::new_unique(), &whirlpool, &args, priority_fee);
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[0].program_id, compute_budget::compute_budget_program_id());
+        assert_eq!(instructions[1].data[1..], 25_000u64.to_le_bytes());
+        assert_eq!(instructions[2].accounts[3].pubkey, wallet.pubkey());
+
+        let blockhash = Hash::new_unique();
+        let (transaction, signature) = wallet.sign(&instructions, blockhash);
+        assert_eq!(transaction[0], 1);
+        let signature: Signature = signature.parse().unwrap();
+        assert_eq!(transaction[1..65], *signature.as_ref());
+
+        let mut message = Message::new(&instructions, Some(&wallet.pubkey()));
+        message.recent_blockhash = blockhash;
+        assert_eq!(transaction[65..], message.serialize());
+        assert!(signature.verify(wallet.pubkey().as_ref(), &transaction[65..]));
+    }
+
+    async fn rpc(url: &str, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response = reqwest::Client::new().post(url).json(&request).send().await.unwrap();
+        response.json::<Value>().await.unwrap()
+    }
+
+    // Needs `solana-test-validator` on the default port
+    #[tokio::test]
+    #[ignore]
+    async fn test_send_and_confirm_on_local_validator() {
+        let url = "http://127.0.0.1:8899";
+        let wallet = Wallet::from_keypair(Keypair::new());
+        let airdrop = rpc(url, "requestAirdrop", json!([wallet.pubkey().to_string(), 1_000_000_000])).await;
+        let airdrop_signature = airdrop["result"].as_str().unwrap().to_string();
+
+        let http = SolanaHttpClient::new(url.to_string());
+        let (_, last_valid) = http.get_latest_blockhash().await.unwrap();
+        let status = wait_for_confirmation(&http, &airdrop_signature, last_valid, |_| {}).await.unwrap();
+        assert!(matches!(status, TransactionStatus::Confirmed | TransactionStatus::Finalized));
+
+        // A compute budget only transaction exercises signing, sending and confirmation
+        let priority_fee = PriorityFee {
+            compute_unit_limit: 10_000,
+            micro_lamports_per_unit: 1_000,
+        };
+        let (signature, last_valid) = wallet.send(&http, &priority_fee.instructions()).await.unwrap();
+        let mut seen = Vec::new();
+        let status = wait_for_confirmation(&http, &signature, last_valid, |s| seen.push(s.clone()))
+            .await
+            .unwrap();
+        assert!(matches!(status, TransactionStatus::Confirmed | TransactionStatus::Finalized));
+        assert_eq!(seen.last(), Some(&status));
+    }
+}